@@ -51,6 +51,16 @@ impl node {
     pub extern "C" fn accesskit_node_free(node: *mut node) {
         drop(box_from_ptr(node));
     }
+
+    /// Returns true if both nodes have the same properties,
+    /// or if both pointers are null.
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_equal(a: *const node, b: *const node) -> bool {
+        match (a.is_null(), b.is_null()) {
+            (false, false) => ref_from_ptr(a) == ref_from_ptr(b),
+            (a_is_null, b_is_null) => a_is_null && b_is_null,
+        }
+    }
 }
 
 pub struct node_builder {
@@ -154,7 +164,7 @@ macro_rules! property_getters {
     ($getter:ident, *mut $getter_result:tt) => {
         paste! {
             impl node {
-                /// Caller is responsible for freeing the returned value.
+                #[doc = "Caller must call `accesskit_" $getter_result "_free` with the return value."]
                 #[no_mangle]
                 pub extern "C" fn [<accesskit_node_ $getter>](node: *const node) -> *mut $getter_result {
                     let node = ref_from_ptr(node);
@@ -162,9 +172,9 @@ macro_rules! property_getters {
                 }
             }
             impl node_builder {
-                /// Caller is responsible for freeing the returned value.
+                #[doc = "Caller must call `accesskit_" $getter_result "_free` with the return value."]
                 #[no_mangle]
-                pub extern "C" fn [<accesskit_node_builder_ $getter>](builder: *const node_builder) -> *const $getter_result {
+                pub extern "C" fn [<accesskit_node_builder_ $getter>](builder: *const node_builder) -> *mut $getter_result {
                     let builder = ref_from_ptr(builder);
                     BoxCastPtr::to_mut_ptr(builder.$getter().into())
                 }
@@ -227,6 +237,8 @@ macro_rules! simple_property_methods {
 
 macro_rules! slice_struct {
     ($struct_name:ident, $rust_type:ty, $ffi_type:ty) => {
+        /// A borrowed array. Do not free it, and do not use it after
+        /// the object it was obtained from is modified or freed.
         #[repr(C)]
         pub struct $struct_name {
             pub length: usize,
@@ -243,7 +255,8 @@ macro_rules! slice_struct {
         impl From<$struct_name> for Vec<$rust_type> {
             fn from(values: $struct_name) -> Self {
                 unsafe {
-                    slice::from_raw_parts(values.values as *mut $rust_type, values.length).to_vec()
+                    slice::from_raw_parts(values.values as *const $rust_type, values.length)
+                        .to_vec()
                 }
             }
         }
@@ -252,10 +265,15 @@ macro_rules! slice_struct {
 
 macro_rules! array_struct {
     ($struct_name:ident, $rust_type:ty, $ffi_type:ty) => {
-        #[repr(C)]
-        pub struct $struct_name {
-            pub length: usize,
-            pub values: *mut $ffi_type,
+        paste! {
+            #[doc = "An array owned by the caller, who must free it with `accesskit_" $struct_name "_free`."]
+            #[doc = ""]
+            #[doc = "This also frees the strings held by its items, so do not free them separately."]
+            #[repr(C)]
+            pub struct $struct_name {
+                pub length: usize,
+                pub values: *mut $ffi_type,
+            }
         }
         impl CastPtr for $struct_name {
             type RustType = $struct_name;
@@ -363,9 +381,14 @@ macro_rules! string_property_methods {
                     }
                 }
                 /// Caller is responsible for freeing the memory pointed by `value`.
+                /// A null `value` clears the property.
                 #[no_mangle]
                 pub extern "C" fn [<accesskit_node_builder_ $setter>](builder: *mut node_builder, value: *const c_char) {
                     let builder = mut_from_ptr(builder);
+                    if value.is_null() {
+                        builder.$clearer();
+                        return;
+                    }
                     let value = unsafe { CStr::from_ptr(value) };
                     builder.$setter(value.to_string_lossy());
                 }
@@ -554,7 +577,10 @@ flag_methods! {
     (is_spelling_error, set_is_spelling_error, clear_is_spelling_error),
     (is_grammar_error, set_is_grammar_error, clear_is_grammar_error),
     (is_search_match, set_is_search_match, clear_is_search_match),
-    (is_suggestion, set_is_suggestion, clear_is_suggestion)
+    (is_suggestion, set_is_suggestion, clear_is_suggestion),
+    (is_bounds_unknown, set_bounds_unknown, clear_bounds_unknown),
+    (is_primary_content, set_is_primary_content, clear_is_primary_content),
+    (is_mixed, set_mixed, clear_mixed)
 }
 
 node_id_vec_property_methods! {
@@ -564,6 +590,7 @@ node_id_vec_property_methods! {
     (described_by, set_described_by, push_described_by, clear_described_by),
     (flow_to, set_flow_to, push_flow_to, clear_flow_to),
     (labelled_by, set_labelled_by, push_labelled_by, clear_labelled_by),
+    (owns, set_owns, push_owned, clear_owns),
     (radio_group, set_radio_group, push_to_radio_group, clear_radio_group)
 }
 
@@ -577,7 +604,9 @@ node_id_property_methods! {
     (popup_for, set_popup_for, clear_popup_for),
     (table_header, set_table_header, clear_table_header),
     (table_row_header, set_table_row_header, clear_table_row_header),
-    (table_column_header, set_table_column_header, clear_table_column_header)
+    (table_column_header, set_table_column_header, clear_table_column_header),
+    (return_focus_to, set_return_focus_to, clear_return_focus_to),
+    (caption, set_caption, clear_caption)
 }
 
 /// Only call this function with a string that originated from AccessKit.
@@ -592,6 +621,7 @@ string_property_methods! {
     (description, set_description, clear_description),
     (value, set_value, clear_value),
     (access_key, set_access_key, clear_access_key),
+    (aria_role, set_aria_role, clear_aria_role),
     (class_name, set_class_name, clear_class_name),
     (font_family, set_font_family, clear_font_family),
     (html_tag, set_html_tag, clear_html_tag),
@@ -602,7 +632,10 @@ string_property_methods! {
     (role_description, set_role_description, clear_role_description),
     (state_description, set_state_description, clear_state_description),
     (tooltip, set_tooltip, clear_tooltip),
-    (url, set_url, clear_url)
+    (url, set_url, clear_url),
+    (accessible_text, set_accessible_text, clear_accessible_text),
+    (automation_id, set_automation_id, clear_automation_id),
+    (item_status, set_item_status, clear_item_status)
 }
 
 f64_property_methods! {
@@ -618,7 +651,8 @@ f64_property_methods! {
     (numeric_value_step, set_numeric_value_step, clear_numeric_value_step),
     (numeric_value_jump, set_numeric_value_jump, clear_numeric_value_jump),
     (font_size, set_font_size, clear_font_size),
-    (font_weight, set_font_weight, clear_font_weight)
+    (font_weight, set_font_weight, clear_font_weight),
+    (reading_level, set_reading_level, clear_reading_level)
 }
 
 usize_property_methods! {
@@ -758,6 +792,7 @@ impl node_builder {
 clearer! { clear_text_selection }
 
 /// Use `accesskit_custom_action_new` to create this struct. Do not reallocate `description`.
+/// A null `description` is treated as an empty string.
 ///
 /// When you get this struct, you are responsible for freeing `description`.
 #[derive(Clone)]
@@ -773,10 +808,7 @@ impl custom_action {
         id: i32,
         description: *const c_char,
     ) -> custom_action {
-        let description = CString::new(String::from(
-            unsafe { CStr::from_ptr(description) }.to_string_lossy(),
-        ))
-        .unwrap();
+        let description = CString::new(string_from_ptr(description)).unwrap();
         Self {
             id,
             description: description.into_raw(),
@@ -786,15 +818,24 @@ impl custom_action {
 
 impl Drop for custom_action {
     fn drop(&mut self) {
-        accesskit_string_free(self.description);
+        if !self.description.is_null() {
+            accesskit_string_free(self.description);
+        }
+    }
+}
+
+fn string_from_ptr(string: *const c_char) -> String {
+    if string.is_null() {
+        return String::new();
     }
+    unsafe { CStr::from_ptr(string) }.to_string_lossy().into()
 }
 
 impl From<custom_action> for CustomAction {
     fn from(action: custom_action) -> Self {
         Self {
             id: action.id,
-            description: unsafe { CStr::from_ptr(action.description).to_string_lossy().into() },
+            description: string_from_ptr(action.description).into(),
         }
     }
 }
@@ -803,7 +844,7 @@ impl From<&custom_action> for CustomAction {
     fn from(action: &custom_action) -> Self {
         Self {
             id: action.id,
-            description: unsafe { CStr::from_ptr(action.description).to_string_lossy().into() },
+            description: string_from_ptr(action.description).into(),
         }
     }
 }
@@ -823,6 +864,71 @@ vec_property_methods! {
     (CustomAction, custom_actions, *mut custom_actions, set_custom_actions, custom_action, push_custom_action, clear_custom_actions)
 }
 
+/// Use `accesskit_custom_content_new` to create this struct. Do not reallocate `label` or `value`.
+/// A null `label` or `value` passed to `accesskit_custom_content_new` is treated as an empty string.
+///
+/// When you get this struct, you are responsible for freeing `label` and `value`.
+#[derive(Clone)]
+#[repr(C)]
+pub struct custom_content {
+    pub label: *mut c_char,
+    pub value: *mut c_char,
+}
+
+impl custom_content {
+    #[no_mangle]
+    pub extern "C" fn accesskit_custom_content_new(
+        label: *const c_char,
+        value: *const c_char,
+    ) -> custom_content {
+        Self {
+            label: CString::new(string_from_ptr(label)).unwrap().into_raw(),
+            value: CString::new(string_from_ptr(value)).unwrap().into_raw(),
+        }
+    }
+}
+
+impl Drop for custom_content {
+    fn drop(&mut self) {
+        if !self.label.is_null() {
+            accesskit_string_free(self.label);
+        }
+        if !self.value.is_null() {
+            accesskit_string_free(self.value);
+        }
+    }
+}
+
+impl From<custom_content> for CustomContent {
+    fn from(content: custom_content) -> Self {
+        Self::from(&content)
+    }
+}
+
+impl From<&custom_content> for CustomContent {
+    fn from(content: &custom_content) -> Self {
+        Self {
+            label: string_from_ptr(content.label).into(),
+            value: string_from_ptr(content.value).into(),
+        }
+    }
+}
+
+impl From<&CustomContent> for custom_content {
+    fn from(content: &CustomContent) -> Self {
+        Self {
+            label: CString::new(&*content.label).unwrap().into_raw(),
+            value: CString::new(&*content.value).unwrap().into_raw(),
+        }
+    }
+}
+
+array_struct! { custom_contents, CustomContent, custom_content }
+
+vec_property_methods! {
+    (CustomContent, custom_content, *mut custom_contents, set_custom_content, custom_content, push_custom_content, clear_custom_content)
+}
+
 impl node_builder {
     #[no_mangle]
     pub extern "C" fn accesskit_node_builder_new(role: Role) -> *mut node_builder {
@@ -849,6 +955,19 @@ impl node_builder {
     pub extern "C" fn accesskit_node_builder_free(builder: *mut node_builder) {
         drop(box_from_ptr(builder));
     }
+
+    /// Returns true if both builders have the same properties,
+    /// or if both pointers are null.
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_builder_equal(
+        a: *const node_builder,
+        b: *const node_builder,
+    ) -> bool {
+        match (a.is_null(), b.is_null()) {
+            (false, false) => ref_from_ptr(a) == ref_from_ptr(b),
+            (a_is_null, b_is_null) => a_is_null && b_is_null,
+        }
+    }
 }
 
 pub struct tree {
@@ -1023,6 +1142,74 @@ impl tree_update {
     }
 }
 
+pub struct tree_snapshot {
+    _private: [u8; 0],
+}
+
+impl CastPtr for tree_snapshot {
+    type RustType = TreeSnapshot;
+}
+
+impl BoxCastPtr for tree_snapshot {}
+
+impl tree_snapshot {
+    /// Builds an owned copy of the tree described by `update`, so that it can
+    /// be inspected without a platform adapter, for example in tests.
+    /// Takes ownership of `update`. Returns a null pointer if `update`
+    /// doesn't include a tree.
+    ///
+    /// Caller must call `accesskit_tree_snapshot_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_new(update: *mut tree_update) -> *mut tree_snapshot {
+        let update = box_from_ptr(update);
+        if update.tree.is_none() {
+            return ptr::null_mut();
+        }
+        BoxCastPtr::to_mut_ptr(TreeSnapshot::new(*update))
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_free(snapshot: *mut tree_snapshot) {
+        drop(box_from_ptr(snapshot));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_root(snapshot: *const tree_snapshot) -> node_id {
+        let snapshot = ref_from_ptr(snapshot);
+        snapshot.root_id().into()
+    }
+
+    /// Returns a null pointer if there is no node with this ID in the snapshot.
+    /// The returned node is owned by the snapshot and is only valid
+    /// until the snapshot is freed.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_node(
+        snapshot: *const tree_snapshot,
+        id: node_id,
+    ) -> *const node {
+        let snapshot = ref_from_ptr(snapshot);
+        match snapshot.nodes().get(&id.into()) {
+            Some(node) => node as *const Node as *const node,
+            None => ptr::null(),
+        }
+    }
+
+    /// Returns an empty array if there is no node with this ID in the snapshot.
+    /// The returned array is owned by the snapshot and is only valid
+    /// until the snapshot is freed.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_children(
+        snapshot: *const tree_snapshot,
+        id: node_id,
+    ) -> node_ids {
+        let snapshot = ref_from_ptr(snapshot);
+        match snapshot.nodes().get(&id.into()) {
+            Some(node) => node.children().into(),
+            None => (&[] as &[NodeId]).into(),
+        }
+    }
+}
+
 #[repr(C)]
 pub enum action_data {
     CustomAction(i32),
@@ -1131,3 +1318,181 @@ unsafe impl Send for tree_update_factory_userdata {}
 /// This function can't return a null pointer. Ownership of the returned value will be transfered to the caller.
 pub type tree_update_factory =
     Option<extern "C" fn(tree_update_factory_userdata) -> *mut tree_update>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn children_round_trip() {
+        let builder = node_builder::accesskit_node_builder_new(Role::Window);
+        let ids: Vec<node_id> = vec![1, 2, 3];
+        node_builder::accesskit_node_builder_set_children(builder, ids.len(), ids.as_ptr());
+        drop(ids);
+        let children = Vec::<NodeId>::from(node_builder::accesskit_node_builder_children(builder));
+        assert_eq!(children, vec![NodeId(1), NodeId(2), NodeId(3)]);
+        let classes = node_class_set::accesskit_node_class_set_new();
+        let node = node_builder::accesskit_node_builder_build(builder, classes);
+        let children = Vec::<NodeId>::from(node::accesskit_node_children(node));
+        assert_eq!(children, vec![NodeId(1), NodeId(2), NodeId(3)]);
+        node::accesskit_node_free(node);
+        node_class_set::accesskit_node_class_set_free(classes);
+        assert_eq!(children, vec![NodeId(1), NodeId(2), NodeId(3)]);
+    }
+
+    #[test]
+    fn null_strings() {
+        let builder = node_builder::accesskit_node_builder_new(Role::Button);
+        let name = CString::new("OK").unwrap();
+        node_builder::accesskit_node_builder_set_name(builder, name.as_ptr());
+        assert_eq!(ref_from_ptr(builder).name(), Some("OK"));
+        node_builder::accesskit_node_builder_set_name(builder, ptr::null());
+        assert_eq!(ref_from_ptr(builder).name(), None);
+        node_builder::accesskit_node_builder_free(builder);
+
+        let action = custom_action::accesskit_custom_action_new(1, ptr::null());
+        assert_eq!(&*CustomAction::from(&action).description, "");
+        let action = custom_action {
+            id: 2,
+            description: ptr::null_mut(),
+        };
+        assert_eq!(&*CustomAction::from(action).description, "");
+
+        let label = CString::new("Status").unwrap();
+        let content = custom_content::accesskit_custom_content_new(label.as_ptr(), ptr::null());
+        let content = CustomContent::from(&content);
+        assert_eq!(&*content.label, "Status");
+        assert_eq!(&*content.value, "");
+        let content = custom_content {
+            label: ptr::null_mut(),
+            value: ptr::null_mut(),
+        };
+        let content = CustomContent::from(content);
+        assert_eq!(&*content.label, "");
+        assert_eq!(&*content.value, "");
+    }
+
+    #[test]
+    fn custom_actions_round_trip() {
+        let builder = node_builder::accesskit_node_builder_new(Role::ListItem);
+        for (id, description) in [(1, "Archive"), (2, "Delete")] {
+            let description = CString::new(description).unwrap();
+            let action = custom_action::accesskit_custom_action_new(id, description.as_ptr());
+            node_builder::accesskit_node_builder_push_custom_action(builder, action);
+        }
+        let classes = node_class_set::accesskit_node_class_set_new();
+        let node = node_builder::accesskit_node_builder_build(builder, classes);
+        let actions = node::accesskit_node_custom_actions(node);
+        let values = {
+            let actions = ref_from_ptr(actions);
+            unsafe { slice::from_raw_parts(actions.values, actions.length) }
+                .iter()
+                .map(CustomAction::from)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            values,
+            vec![
+                CustomAction {
+                    id: 1,
+                    description: "Archive".into(),
+                },
+                CustomAction {
+                    id: 2,
+                    description: "Delete".into(),
+                },
+            ]
+        );
+        custom_actions::accesskit_custom_actions_free(actions);
+        node::accesskit_node_free(node);
+        node_class_set::accesskit_node_class_set_free(classes);
+    }
+
+    #[test]
+    fn node_equality() {
+        let classes = node_class_set::accesskit_node_class_set_new();
+        let named_button = |name: &str| {
+            let builder = node_builder::accesskit_node_builder_new(Role::Button);
+            let name = CString::new(name).unwrap();
+            node_builder::accesskit_node_builder_set_name(builder, name.as_ptr());
+            builder
+        };
+        let a = named_button("OK");
+        let b = named_button("OK");
+        let c = named_button("Cancel");
+        assert!(node_builder::accesskit_node_builder_equal(a, b));
+        assert!(!node_builder::accesskit_node_builder_equal(a, c));
+        assert!(!node_builder::accesskit_node_builder_equal(a, ptr::null()));
+        assert!(node_builder::accesskit_node_builder_equal(
+            ptr::null(),
+            ptr::null()
+        ));
+        let a = node_builder::accesskit_node_builder_build(a, classes);
+        let b = node_builder::accesskit_node_builder_build(b, classes);
+        let c = node_builder::accesskit_node_builder_build(c, classes);
+        assert!(node::accesskit_node_equal(a, b));
+        assert!(!node::accesskit_node_equal(a, c));
+        assert!(!node::accesskit_node_equal(ptr::null(), b));
+        assert!(node::accesskit_node_equal(ptr::null(), ptr::null()));
+        for node in [a, b, c] {
+            node::accesskit_node_free(node);
+        }
+        node_class_set::accesskit_node_class_set_free(classes);
+    }
+
+    #[test]
+    fn walk_tree_snapshot() {
+        let classes = node_class_set::accesskit_node_class_set_new();
+        let update = tree_update::accesskit_tree_update_with_focus(0);
+        let window = node_builder::accesskit_node_builder_new(Role::Window);
+        node_builder::accesskit_node_builder_set_children(window, 2, [1, 2].as_ptr());
+        let window = node_builder::accesskit_node_builder_build(window, classes);
+        tree_update::accesskit_tree_update_push_node(update, 0, window);
+        for id in [1, 2] {
+            let button = node_builder::accesskit_node_builder_new(Role::Button);
+            let button = node_builder::accesskit_node_builder_build(button, classes);
+            tree_update::accesskit_tree_update_push_node(update, id, button);
+        }
+        tree_update::accesskit_tree_update_set_tree(update, tree::accesskit_tree_new(0));
+        node_class_set::accesskit_node_class_set_free(classes);
+
+        let snapshot = tree_snapshot::accesskit_tree_snapshot_new(update);
+        assert!(!snapshot.is_null());
+        let root = tree_snapshot::accesskit_tree_snapshot_root(snapshot);
+        assert_eq!(root, 0);
+        let children = tree_snapshot::accesskit_tree_snapshot_children(snapshot, root);
+        let children = Vec::<NodeId>::from(children);
+        assert_eq!(children, vec![NodeId(1), NodeId(2)]);
+        for child in children {
+            let node = tree_snapshot::accesskit_tree_snapshot_node(snapshot, child.into());
+            assert_eq!(ref_from_ptr(node).role(), Role::Button);
+            let grandchildren =
+                tree_snapshot::accesskit_tree_snapshot_children(snapshot, child.into());
+            assert_eq!(grandchildren.length, 0);
+        }
+        assert!(tree_snapshot::accesskit_tree_snapshot_node(snapshot, 3).is_null());
+        assert_eq!(
+            tree_snapshot::accesskit_tree_snapshot_children(snapshot, 3).length,
+            0
+        );
+        tree_snapshot::accesskit_tree_snapshot_free(snapshot);
+
+        let update = tree_update::accesskit_tree_update_with_focus(0);
+        assert!(tree_snapshot::accesskit_tree_snapshot_new(update).is_null());
+    }
+
+    #[test]
+    fn scroll_into_view_request() {
+        let rect = Rect::new(0.0, 10.0, 20.0, 30.0);
+        let request = action_request::from(ActionRequest {
+            action: Action::ScrollIntoView,
+            target: NodeId(1),
+            data: Some(ActionData::ScrollTargetRect(rect)),
+        });
+        assert_eq!(request.action, Action::ScrollIntoView);
+        assert_eq!(NodeId::from(request.target), NodeId(1));
+        assert!(request.data.has_value);
+        let data = unsafe { request.data.value.assume_init_ref() };
+        assert!(matches!(data, action_data::ScrollTargetRect(data) if *data == rect));
+    }
+}
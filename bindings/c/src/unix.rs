@@ -8,8 +8,25 @@ use crate::{
     BoxCastPtr, CastPtr,
 };
 use accesskit::Rect;
-use accesskit_unix::Adapter;
-use std::os::raw::c_void;
+use accesskit_unix::{Adapter, ConnectionStatus};
+use std::{
+    ffi::CString,
+    os::raw::{c_char, c_void},
+    ptr,
+};
+
+/// The state of the application's connection to the AT-SPI bus.
+#[repr(C)]
+pub enum unix_connection_state {
+    /// The connection hasn't been attempted yet.
+    Pending,
+    /// The desktop reports that accessibility is disabled.
+    Disabled,
+    /// Connected to the accessibility bus.
+    Connected,
+    /// Connecting to the session bus or the accessibility bus failed.
+    Failed,
+}
 
 pub struct unix_adapter {
     _private: [u8; 0],
@@ -66,6 +83,38 @@ impl unix_adapter {
         adapter.update_if_active(|| *box_from_ptr(update_factory(update_factory_userdata)));
     }
 
+    #[no_mangle]
+    pub extern "C" fn accesskit_unix_adapter_connection_state(
+        adapter: *const unix_adapter,
+    ) -> unix_connection_state {
+        let adapter = ref_from_ptr(adapter);
+        match adapter.connection_status() {
+            ConnectionStatus::Pending => unix_connection_state::Pending,
+            ConnectionStatus::Disabled => unix_connection_state::Disabled,
+            ConnectionStatus::Connected(_) => unix_connection_state::Connected,
+            ConnectionStatus::Failed(_) => unix_connection_state::Failed,
+        }
+    }
+
+    /// Returns the address of the accessibility bus if the adapter is connected,
+    /// the error message if connecting failed, or null otherwise.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_unix_adapter_connection_detail(
+        adapter: *const unix_adapter,
+    ) -> *mut c_char {
+        let adapter = ref_from_ptr(adapter);
+        match adapter.connection_status() {
+            ConnectionStatus::Connected(detail) | ConnectionStatus::Failed(detail) => {
+                // Error messages can include text from the bus, which
+                // isn't guaranteed to be free of NULs.
+                CString::new(detail.replace('\0', "")).unwrap().into_raw()
+            }
+            _ => ptr::null_mut(),
+        }
+    }
+
     /// Update the tree state based on whether the window is focused.
     #[no_mangle]
     pub extern "C" fn accesskit_unix_adapter_update_window_focus_state(
@@ -4,7 +4,11 @@
 // the LICENSE-MIT file), at your option.
 
 use crate::{Point, Rect};
-use pyo3::{prelude::*, types::PyList};
+use pyo3::{
+    exceptions::{PyKeyError, PyValueError},
+    prelude::*,
+    types::{PyDict, PyIterator, PyList, PyTuple},
+};
 
 #[pyclass(module = "accesskit")]
 pub struct NodeClassSet(accesskit::NodeClassSet);
@@ -44,6 +48,25 @@ impl Node {
     pub fn supports_action(&self, action: accesskit::Action) -> bool {
         self.inner().supports_action(action)
     }
+
+    /// Returns the number of children of this node, which is the length
+    /// of `children`.
+    pub fn __len__(&self) -> usize {
+        self.inner().children().len()
+    }
+
+    /// A node is always truthy, even if it has no children.
+    pub fn __bool__(&self) -> bool {
+        true
+    }
+
+    /// Iterates over the IDs of this node's children, in the same order as
+    /// `children`. These are plain node IDs; use `TreeSnapshot.node`
+    /// to look up the child nodes themselves.
+    pub fn __iter__(&self, py: Python<'_>) -> PyResult<Py<PyIterator>> {
+        let children = PyList::new(py, self.inner().children().iter().map(|id| id.0));
+        Ok(PyIterator::from_object(children)?.into())
+    }
 }
 
 #[pyclass(module = "accesskit")]
@@ -148,6 +171,53 @@ impl From<accesskit::CustomAction> for CustomAction {
     }
 }
 
+#[derive(Clone)]
+#[pyclass(module = "accesskit")]
+pub struct CustomContent(accesskit::CustomContent);
+
+#[pymethods]
+impl CustomContent {
+    #[new]
+    pub fn new(label: &str, value: &str) -> Self {
+        Self(accesskit::CustomContent {
+            label: label.into(),
+            value: value.into(),
+        })
+    }
+
+    #[getter]
+    pub fn label(&self) -> &str {
+        &self.0.label
+    }
+
+    #[setter]
+    pub fn set_label(&mut self, label: &str) {
+        self.0.label = label.into()
+    }
+
+    #[getter]
+    pub fn value(&self) -> &str {
+        &self.0.value
+    }
+
+    #[setter]
+    pub fn set_value(&mut self, value: &str) {
+        self.0.value = value.into()
+    }
+}
+
+impl From<CustomContent> for accesskit::CustomContent {
+    fn from(content: CustomContent) -> Self {
+        content.0
+    }
+}
+
+impl From<accesskit::CustomContent> for CustomContent {
+    fn from(content: accesskit::CustomContent) -> Self {
+        Self(content)
+    }
+}
+
 #[derive(Clone)]
 #[pyclass(module = "accesskit")]
 pub struct TextPosition(accesskit::TextPosition);
@@ -196,11 +266,32 @@ pub struct TextSelection {
     pub focus: Py<TextPosition>,
 }
 
+/// Either a `TextPosition` or a `(node, character_index)` tuple.
+#[derive(FromPyObject)]
+pub enum TextPositionArg {
+    Position(Py<TextPosition>),
+    Tuple(NodeId, usize),
+}
+
+impl TextPositionArg {
+    fn into_position(self, py: Python<'_>) -> PyResult<Py<TextPosition>> {
+        match self {
+            Self::Position(position) => Ok(position),
+            Self::Tuple(node, character_index) => {
+                Py::new(py, TextPosition::new(node, character_index))
+            }
+        }
+    }
+}
+
 #[pymethods]
 impl TextSelection {
     #[new]
-    pub fn new(anchor: Py<TextPosition>, focus: Py<TextPosition>) -> Self {
-        Self { anchor, focus }
+    pub fn new(py: Python<'_>, anchor: TextPositionArg, focus: TextPositionArg) -> PyResult<Self> {
+        Ok(Self {
+            anchor: anchor.into_position(py)?,
+            focus: focus.into_position(py)?,
+        })
     }
 }
 
@@ -306,6 +397,7 @@ macro_rules! converting_setter {
 
 macro_rules! flag_methods {
     ($(($getter:ident, $setter:ident, $clearer:ident)),+) => {
+        const FLAG_SETTERS: &[&str] = &[$(stringify!($setter)),*];
         $(getters! { $getter, simple_getter, bool }
         #[pymethods]
         impl NodeBuilder {
@@ -470,7 +562,10 @@ flag_methods! {
     (is_spelling_error, set_is_spelling_error, clear_is_spelling_error),
     (is_grammar_error, set_is_grammar_error, clear_is_grammar_error),
     (is_search_match, set_is_search_match, clear_is_search_match),
-    (is_suggestion, set_is_suggestion, clear_is_suggestion)
+    (is_suggestion, set_is_suggestion, clear_is_suggestion),
+    (is_bounds_unknown, set_bounds_unknown, clear_bounds_unknown),
+    (is_primary_content, set_is_primary_content, clear_is_primary_content),
+    (is_mixed, set_mixed, clear_mixed)
 }
 
 node_id_vec_property_methods! {
@@ -480,6 +575,7 @@ node_id_vec_property_methods! {
     (described_by, set_described_by, push_described_by, clear_described_by),
     (flow_to, set_flow_to, push_flow_to, clear_flow_to),
     (labelled_by, set_labelled_by, push_labelled_by, clear_labelled_by),
+    (owns, set_owns, push_owned, clear_owns),
     (radio_group, set_radio_group, push_to_radio_group, clear_radio_group)
 }
 
@@ -493,7 +589,9 @@ node_id_property_methods! {
     (popup_for, set_popup_for, clear_popup_for),
     (table_header, set_table_header, clear_table_header),
     (table_row_header, set_table_row_header, clear_table_row_header),
-    (table_column_header, set_table_column_header, clear_table_column_header)
+    (table_column_header, set_table_column_header, clear_table_column_header),
+    (return_focus_to, set_return_focus_to, clear_return_focus_to),
+    (caption, set_caption, clear_caption)
 }
 
 string_property_methods! {
@@ -501,6 +599,7 @@ string_property_methods! {
     (description, set_description, clear_description),
     (value, set_value, clear_value),
     (access_key, set_access_key, clear_access_key),
+    (aria_role, set_aria_role, clear_aria_role),
     (class_name, set_class_name, clear_class_name),
     (font_family, set_font_family, clear_font_family),
     (html_tag, set_html_tag, clear_html_tag),
@@ -511,7 +610,10 @@ string_property_methods! {
     (role_description, set_role_description, clear_role_description),
     (state_description, set_state_description, clear_state_description),
     (tooltip, set_tooltip, clear_tooltip),
-    (url, set_url, clear_url)
+    (url, set_url, clear_url),
+    (accessible_text, set_accessible_text, clear_accessible_text),
+    (automation_id, set_automation_id, clear_automation_id),
+    (item_status, set_item_status, clear_item_status)
 }
 
 f64_property_methods! {
@@ -527,7 +629,8 @@ f64_property_methods! {
     (numeric_value_step, set_numeric_value_step, clear_numeric_value_step),
     (numeric_value_jump, set_numeric_value_jump, clear_numeric_value_jump),
     (font_size, set_font_size, clear_font_size),
-    (font_weight, set_font_weight, clear_font_weight)
+    (font_weight, set_font_weight, clear_font_weight),
+    (reading_level, set_reading_level, clear_reading_level)
 }
 
 usize_property_methods! {
@@ -594,7 +697,8 @@ property_methods! {
 }
 
 vec_property_methods! {
-    (CustomAction, accesskit::CustomAction, custom_actions, set_custom_actions, push_custom_action, clear_custom_actions)
+    (CustomAction, accesskit::CustomAction, custom_actions, set_custom_actions, push_custom_action, clear_custom_actions),
+    (CustomContent, accesskit::CustomContent, custom_content, set_custom_content, push_custom_content, clear_custom_content)
 }
 
 #[derive(Clone)]
@@ -648,6 +752,31 @@ impl TreeUpdate {
             focus,
         }
     }
+
+    /// Create the initial state of a tree consisting of a single root node,
+    /// built from the given builder, which also has the focus.
+    #[staticmethod]
+    #[pyo3(signature = (root_id, builder, classes = None))]
+    pub fn with_root(
+        py: Python<'_>,
+        root_id: NodeId,
+        builder: &mut NodeBuilder,
+        classes: Option<PyRefMut<'_, NodeClassSet>>,
+    ) -> PyResult<Self> {
+        let root = match classes {
+            Some(mut classes) => builder.build(&mut classes),
+            None => builder.build(&mut NodeClassSet::__new__()),
+        };
+        Ok(Self {
+            nodes: PyList::new(
+                py,
+                [PyTuple::new(py, [root_id.into_py(py), root.into_py(py)])],
+            )
+            .into(),
+            tree: Some(Py::new(py, Tree::new(root_id))?),
+            focus: root_id,
+        })
+    }
 }
 
 impl From<TreeUpdate> for accesskit::TreeUpdate {
@@ -661,20 +790,221 @@ impl From<TreeUpdate> for accesskit::TreeUpdate {
                 .filter_map(Result::ok)
                 .map(|(id, node)| (id.into(), node.into()))
                 .collect(),
-            tree: update.tree.map(|tree| {
-                let tree = tree.as_ref(py).borrow();
-                accesskit::Tree {
-                    root: tree.root.into(),
-                    app_name: tree.app_name.clone(),
-                    toolkit_name: tree.toolkit_name.clone(),
-                    toolkit_version: tree.toolkit_version.clone(),
-                }
-            }),
+            tree: update
+                .tree
+                .map(|tree| tree.as_ref(py).borrow().clone().into()),
             focus: update.focus.into(),
         })
     }
 }
 
+fn build_tree_node(
+    py: Python<'_>,
+    spec: &PyDict,
+    classes: &mut accesskit::NodeClassSet,
+    next_id: &mut NodeId,
+    nodes: &mut Vec<Py<PyTuple>>,
+) -> PyResult<NodeId> {
+    let id = *next_id;
+    *next_id += 1;
+    let role = spec
+        .get_item("role")?
+        .ok_or_else(|| PyKeyError::new_err("node specification is missing a role"))?
+        .extract::<accesskit::Role>()?;
+    let builder = PyCell::new(py, NodeBuilder::new(role))?;
+    let mut children = Vec::new();
+    for (key, value) in spec.iter() {
+        let key = key.extract::<&str>()?;
+        match key {
+            "role" => (),
+            "children" => {
+                for child in value.iter()? {
+                    let child = child?.downcast::<PyDict>()?;
+                    children.push(build_tree_node(py, child, classes, next_id, nodes)?);
+                }
+            }
+            "actions" => {
+                for action in value.iter()? {
+                    builder.borrow_mut().add_action(action?.extract()?);
+                }
+            }
+            _ => {
+                let setter = format!("set_{}", key);
+                if FLAG_SETTERS.contains(&setter.as_str()) {
+                    if value.is_true()? {
+                        builder.call_method0(setter.as_str())?;
+                    }
+                } else {
+                    builder.call_method1(setter.as_str(), (value,))?;
+                }
+            }
+        }
+    }
+    if !children.is_empty() {
+        builder.borrow_mut().inner_mut().set_children(
+            children
+                .into_iter()
+                .map(accesskit::NodeId)
+                .collect::<Vec<_>>(),
+        );
+    }
+    let node = Node(builder.borrow_mut().0.take().unwrap().build(classes));
+    nodes.push(PyTuple::new(py, [id.into_py(py), node.into_py(py)]).into());
+    Ok(id)
+}
+
+/// Builds a complete tree from a nested specification.
+///
+/// Each node is described by a `dict` with a mandatory `role` key,
+/// an optional `children` list of nested specifications, an optional
+/// `actions` list, and any number of properties named after their
+/// `NodeBuilder` setter without the `set_` prefix, e.g. `name` or `bounds`.
+/// Flags such as `hidden` are set when their value is truthy.
+///
+/// Node IDs are allocated in depth-first order, starting at 0 for the root.
+/// Returns a tuple of the resulting `TreeUpdate`, with focus on the root,
+/// and the root's ID.
+#[pyfunction]
+pub fn build_tree(py: Python<'_>, root_spec: &PyDict) -> PyResult<(TreeUpdate, NodeId)> {
+    let mut classes = accesskit::NodeClassSet::new();
+    let mut next_id = 0;
+    let mut nodes = Vec::new();
+    let root = build_tree_node(py, root_spec, &mut classes, &mut next_id, &mut nodes)?;
+    Ok((
+        TreeUpdate {
+            nodes: PyList::new(py, nodes).into(),
+            tree: Some(Py::new(py, Tree::new(root))?),
+            focus: root,
+        },
+        root,
+    ))
+}
+
+/// An owned copy of a whole tree, so that it can be inspected without
+/// a platform adapter, for example in tests.
+#[derive(Clone)]
+#[pyclass(module = "accesskit")]
+pub struct TreeSnapshot(accesskit::TreeSnapshot);
+
+#[pymethods]
+impl TreeSnapshot {
+    /// Raises `ValueError` if `update` doesn't include a tree.
+    #[new]
+    pub fn new(update: TreeUpdate) -> PyResult<Self> {
+        let update: accesskit::TreeUpdate = update.into();
+        if update.tree.is_none() {
+            return Err(PyValueError::new_err(
+                "the initial update must include a tree",
+            ));
+        }
+        Ok(Self(accesskit::TreeSnapshot::new(update)))
+    }
+
+    #[getter]
+    pub fn root(&self) -> NodeId {
+        self.0.root_id().0
+    }
+
+    #[getter]
+    pub fn focus(&self) -> NodeId {
+        self.0.focus.0
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<Node> {
+        self.0
+            .nodes()
+            .get(&accesskit::NodeId(id))
+            .cloned()
+            .map(Node)
+    }
+
+    /// Returns the first and last focusable descendants of `container`,
+    /// or `None` if it has none.
+    pub fn focus_trap_bounds(&self, container: NodeId) -> Option<(NodeId, NodeId)> {
+        self.0
+            .focus_trap_bounds(accesskit::NodeId(container))
+            .map(|(first, last)| (first.0, last.0))
+    }
+
+    /// Returns `start` followed by the regions reached through its
+    /// `flow_to` chain, stopping at the first cycle.
+    pub fn reading_order(&self, start: NodeId) -> Vec<NodeId> {
+        self.0
+            .reading_order(accesskit::NodeId(start))
+            .into_iter()
+            .map(|id| id.0)
+            .collect()
+    }
+
+    /// Returns a new snapshot with `update` applied, leaving this one
+    /// unchanged.
+    pub fn apply(&self, update: TreeUpdate) -> Self {
+        let mut state = self.0.clone();
+        state.apply(update.into());
+        Self(state)
+    }
+}
+
+#[derive(Clone, PartialEq)]
+#[pyclass(module = "accesskit", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NodeDiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A node that differs between two snapshots, as returned by
+/// `snapshot_diff`. For changed nodes, `changes` holds a
+/// `(property, old, new)` tuple for each property that differs;
+/// the format of the values is only meant for test assertions
+/// and diagnostics, and isn't stable.
+#[pyclass(get_all, module = "accesskit")]
+pub struct NodeDiff {
+    pub id: NodeId,
+    pub kind: NodeDiffKind,
+    pub changes: Vec<(String, String, String)>,
+}
+
+/// Compares two snapshots, returning the nodes that were added, removed
+/// or changed, in ascending order of ID.
+#[pyfunction]
+pub fn snapshot_diff(old: &TreeSnapshot, new: &TreeSnapshot) -> Vec<NodeDiff> {
+    let mut diffs = Vec::new();
+    for (id, old_node) in old.0.nodes() {
+        match new.0.nodes().get(id) {
+            Some(new_node) => {
+                let changes = old_node.property_changes(new_node);
+                if !changes.is_empty() {
+                    diffs.push(NodeDiff {
+                        id: id.0,
+                        kind: NodeDiffKind::Changed,
+                        changes: changes
+                            .into_iter()
+                            .map(|change| (change.name, change.old, change.new))
+                            .collect(),
+                    });
+                }
+            }
+            None => diffs.push(NodeDiff {
+                id: id.0,
+                kind: NodeDiffKind::Removed,
+                changes: Vec::new(),
+            }),
+        }
+    }
+    for id in new.0.nodes().keys() {
+        if !old.0.nodes().contains_key(id) {
+            diffs.push(NodeDiff {
+                id: id.0,
+                kind: NodeDiffKind::Added,
+                changes: Vec::new(),
+            });
+        }
+    }
+    diffs.sort_by_key(|diff| diff.id);
+    diffs
+}
+
 #[derive(Clone)]
 #[pyclass(module = "accesskit", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ActionDataKind {
@@ -734,7 +1064,46 @@ impl accesskit::ActionHandler for PythonActionHandler {
     fn do_action(&mut self, request: accesskit::ActionRequest) {
         let request = ActionRequest::from(request);
         Python::with_gil(|py| {
-            self.0.call(py, (request,), None).unwrap();
+            // Panicking here would abort the adapter's thread without a
+            // traceback, so report the exception through `sys.excepthook`.
+            if let Err(err) = self.0.call(py, (request,), None) {
+                err.print(py);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use accesskit::ActionHandler;
+
+    #[test]
+    fn action_handler_exception_reaches_excepthook() {
+        pyo3::prepare_freethreaded_python();
+        let (handler, errors) = Python::with_gil(|py| {
+            let globals = pyo3::types::PyDict::new(py);
+            py.run(
+                "import sys\n\
+                 errors = []\n\
+                 sys.excepthook = lambda kind, value, tb: errors.append(str(value))\n\
+                 def handler(request):\n    raise RuntimeError('handler failed')\n",
+                Some(globals),
+                None,
+            )
+            .unwrap();
+            let handler = globals.get_item("handler").unwrap().unwrap();
+            let errors = globals.get_item("errors").unwrap().unwrap();
+            (handler.into_py(py), errors.into_py(py))
+        });
+        PythonActionHandler(handler).do_action(accesskit::ActionRequest {
+            action: accesskit::Action::Default,
+            target: accesskit::NodeId(0),
+            data: None,
+        });
+        Python::with_gil(|py| {
+            let errors: Vec<String> = errors.extract(py).unwrap();
+            assert_eq!(errors, ["handler failed"]);
         });
     }
 }
@@ -41,18 +41,26 @@ fn accesskit(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<::accesskit::TextAlign>()?;
     m.add_class::<::accesskit::VerticalOffset>()?;
     m.add_class::<::accesskit::TextDecoration>()?;
+    m.add_class::<CustomContent>()?;
     m.add_class::<NodeClassSet>()?;
     m.add_class::<Node>()?;
     m.add_class::<NodeBuilder>()?;
     m.add_class::<Tree>()?;
     m.add_class::<TreeUpdate>()?;
+    m.add_class::<TreeSnapshot>()?;
+    m.add_class::<NodeDiffKind>()?;
+    m.add_class::<NodeDiff>()?;
     m.add_class::<ActionDataKind>()?;
     m.add_class::<ActionRequest>()?;
     m.add_class::<Affine>()?;
     m.add_class::<Point>()?;
     m.add_class::<Rect>()?;
     m.add_class::<Size>()?;
+    m.add_class::<TextPosition>()?;
+    m.add_class::<TextSelection>()?;
     m.add_class::<Vec2>()?;
+    m.add_function(wrap_pyfunction!(build_tree, m)?)?;
+    m.add_function(wrap_pyfunction!(snapshot_diff, m)?)?;
 
     #[cfg(target_os = "macos")]
     {
@@ -75,6 +83,7 @@ fn accesskit(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     ))]
     {
         let unix_module = PyModule::new(py, "unix")?;
+        unix_module.add_class::<unix::ConnectionState>()?;
         unix_module.add_class::<unix::Adapter>()?;
         m.add_submodule(unix_module)?;
     }
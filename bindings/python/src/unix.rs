@@ -4,8 +4,19 @@
 // the LICENSE-MIT file), at your option.
 
 use crate::{PythonActionHandler, Rect, TreeUpdate};
+use accesskit_unix::ConnectionStatus;
 use pyo3::prelude::*;
 
+/// The state of the application's connection to the AT-SPI bus.
+#[derive(Clone, Copy)]
+#[pyclass(module = "accesskit.unix", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ConnectionState {
+    Pending,
+    Disabled,
+    Connected,
+    Failed,
+}
+
 #[pyclass(module = "accesskit.unix")]
 pub struct Adapter(accesskit_unix::Adapter);
 
@@ -39,6 +50,17 @@ impl Adapter {
         });
     }
 
+    /// Returns the connection state, along with the address of the
+    /// accessibility bus if connected or the error message if connecting failed.
+    pub fn connection_status(&self) -> (ConnectionState, Option<String>) {
+        match self.0.connection_status() {
+            ConnectionStatus::Pending => (ConnectionState::Pending, None),
+            ConnectionStatus::Disabled => (ConnectionState::Disabled, None),
+            ConnectionStatus::Connected(address) => (ConnectionState::Connected, Some(address)),
+            ConnectionStatus::Failed(error) => (ConnectionState::Failed, Some(error)),
+        }
+    }
+
     pub fn update_window_focus_state(&self, is_focused: bool) {
         self.0.update_window_focus_state(is_focused);
     }
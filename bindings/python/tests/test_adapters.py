@@ -0,0 +1,55 @@
+import time
+
+import accesskit
+import pytest
+from accesskit import Role
+
+unix_only = pytest.mark.skipif(
+    not hasattr(accesskit, "unix"), reason="the Unix adapter isn't available"
+)
+
+
+def test_tree_update_with_root():
+    builder = accesskit.NodeBuilder(Role.WINDOW)
+    builder.set_name("Hello")
+    update = accesskit.TreeUpdate.with_root(7, builder)
+    assert update.focus == 7
+    assert update.tree.root == 7
+    assert len(update.nodes) == 1
+    id, node = update.nodes[0]
+    assert id == 7
+    assert node.role == Role.WINDOW
+    assert node.name == "Hello"
+
+
+@unix_only
+def test_adapter_from_root_builder():
+    builder = accesskit.NodeBuilder(Role.WINDOW)
+    builder.set_name("Hello")
+    classes = accesskit.NodeClassSet()
+    adapter = accesskit.unix.Adapter(
+        lambda: accesskit.TreeUpdate.with_root(0, builder, classes),
+        lambda request: None,
+    )
+    adapter.update_window_focus_state(True)
+
+
+@unix_only
+def test_unix_connection_status():
+    ConnectionState = accesskit.unix.ConnectionState
+    adapter = accesskit.unix.Adapter(
+        lambda: accesskit.TreeUpdate.with_root(
+            0, accesskit.NodeBuilder(Role.WINDOW), accesskit.NodeClassSet()
+        ),
+        lambda request: None,
+    )
+    deadline = time.monotonic() + 5
+    state, detail = adapter.connection_status()
+    while state == ConnectionState.PENDING and time.monotonic() < deadline:
+        time.sleep(0.01)
+        state, detail = adapter.connection_status()
+    if state in (ConnectionState.CONNECTED, ConnectionState.FAILED):
+        assert detail
+    else:
+        assert state == ConnectionState.DISABLED
+        assert detail is None
@@ -0,0 +1,28 @@
+import accesskit
+from accesskit import Role, TextPosition, TextSelection
+
+
+def test_text_selection_from_tuples():
+    selection = TextSelection(anchor=(1, 0), focus=(2, 5))
+    assert (selection.anchor.node, selection.anchor.character_index) == (1, 0)
+    assert (selection.focus.node, selection.focus.character_index) == (2, 5)
+
+    builder = accesskit.NodeBuilder(Role.TEXT_INPUT)
+    builder.set_text_selection(TextSelection((1, 0), TextPosition(1, 3)))
+    node = builder.build(accesskit.NodeClassSet())
+    assert node.text_selection.anchor.character_index == 0
+    assert node.text_selection.focus.character_index == 3
+
+
+def test_reading_level_round_trips():
+    builder = accesskit.NodeBuilder(Role.PARAGRAPH)
+    assert builder.reading_level is None
+    builder.set_reading_level(3.5)
+    assert builder.reading_level == 3.5
+    node = builder.build(accesskit.NodeClassSet())
+    assert node.reading_level == 3.5
+
+    builder = accesskit.NodeBuilder(Role.PARAGRAPH)
+    builder.set_reading_level(3.5)
+    builder.clear_reading_level()
+    assert builder.build(accesskit.NodeClassSet()).reading_level is None
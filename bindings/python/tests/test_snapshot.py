@@ -0,0 +1,166 @@
+import accesskit
+from accesskit import NodeDiffKind, Role, TreeSnapshot
+
+
+def build_form():
+    return accesskit.build_tree(
+        {
+            "role": Role.WINDOW,
+            "children": [
+                {"role": Role.BUTTON, "name": "Save"},
+                {"role": Role.BUTTON, "name": "Cancel"},
+            ],
+        }
+    )
+
+
+def test_snapshot_diff_reports_changed_properties():
+    update, root = build_form()
+    old = TreeSnapshot(update)
+    builder = accesskit.NodeBuilder(Role.BUTTON)
+    builder.set_name("Save as")
+    builder.set_disabled()
+    change = accesskit.TreeUpdate(root)
+    change.nodes.append((1, builder.build(accesskit.NodeClassSet())))
+    new = old.apply(change)
+
+    diffs = accesskit.snapshot_diff(old, new)
+    assert len(diffs) == 1
+    diff = diffs[0]
+    assert diff.id == 1
+    assert diff.kind == NodeDiffKind.CHANGED
+    assert sorted(name for name, _, _ in diff.changes) == ["Disabled", "Name"]
+    assert old.node(1).name == "Save"
+    assert new.node(1).name == "Save as"
+
+
+def test_snapshot_diff_reports_added_and_removed_nodes():
+    update, root = build_form()
+    old = TreeSnapshot(update)
+    window = accesskit.NodeBuilder(Role.WINDOW)
+    window.set_children([1, 3])
+    label = accesskit.NodeBuilder(Role.STATIC_TEXT)
+    label.set_name("Saved")
+    classes = accesskit.NodeClassSet()
+    change = accesskit.TreeUpdate(root)
+    change.nodes.append((root, window.build(classes)))
+    change.nodes.append((3, label.build(classes)))
+    new = old.apply(change)
+
+    diffs = [
+        (diff.id, diff.kind)
+        for diff in accesskit.snapshot_diff(old, new)
+        if diff.kind != NodeDiffKind.CHANGED
+    ]
+    assert diffs == [(2, NodeDiffKind.REMOVED), (3, NodeDiffKind.ADDED)]
+
+
+def test_node_iterates_over_child_ids():
+    update, root = accesskit.build_tree(
+        {
+            "role": Role.LIST,
+            "children": [
+                {"role": Role.LIST_ITEM, "position_in_set": 1},
+                {"role": Role.LIST_ITEM, "position_in_set": 2},
+                {"role": Role.LIST_ITEM, "position_in_set": 3},
+            ],
+        }
+    )
+    snapshot = TreeSnapshot(update)
+    node = snapshot.node(root)
+    assert len(node) == 3
+    assert list(node) == node.children == [1, 2, 3]
+    assert sum(snapshot.node(child).position_in_set for child in node) == 6
+
+
+def test_node_without_children_is_truthy():
+    update, root = accesskit.build_tree({"role": Role.BUTTON})
+    node = TreeSnapshot(update).node(root)
+    assert len(node) == 0
+    assert node
+
+
+def test_build_tree_allocates_ids_depth_first():
+    update, root = accesskit.build_tree(
+        {
+            "role": Role.WINDOW,
+            "name": "Settings",
+            "children": [
+                {
+                    "role": Role.GROUP,
+                    "name": "Display",
+                    "children": [
+                        {"role": Role.CHECK_BOX, "name": "Dark mode"},
+                        {"role": Role.CHECK_BOX, "name": "Large text"},
+                    ],
+                },
+                {"role": Role.BUTTON, "name": "Close", "hidden": True},
+            ],
+        }
+    )
+    assert root == 0
+    assert update.focus == root
+    assert update.tree.root == root
+    snapshot = TreeSnapshot(update)
+
+    def walk(id, depth=0):
+        node = snapshot.node(id)
+        yield depth, node.role, node.name
+        for child in node.children:
+            yield from walk(child, depth + 1)
+
+    assert list(walk(root)) == [
+        (0, Role.WINDOW, "Settings"),
+        (1, Role.GROUP, "Display"),
+        (2, Role.CHECK_BOX, "Dark mode"),
+        (2, Role.CHECK_BOX, "Large text"),
+        (1, Role.BUTTON, "Close"),
+    ]
+    assert snapshot.node(4).is_hidden
+    assert not snapshot.node(1).is_hidden
+
+
+def test_filter_snapshot_to_text_inputs():
+    update, root = accesskit.build_tree(
+        {
+            "role": Role.FORM,
+            "children": [
+                {"role": Role.STATIC_TEXT, "name": "Email"},
+                {"role": Role.EMAIL_INPUT},
+                {"role": Role.EDITABLE_COMBO_BOX},
+                {"role": Role.COMBO_BOX},
+                {"role": Role.BUTTON, "name": "Submit"},
+            ],
+        }
+    )
+    snapshot = TreeSnapshot(update)
+    nodes = [(id, snapshot.node(id)) for id, _ in update.nodes]
+    assert [id for id, node in nodes if node.role.is_text_input()] == [2, 3]
+    assert [id for id, node in nodes if node.role.is_container()] == [root]
+    assert snapshot.node(root).role.is_landmark()
+
+
+def test_reading_order_follows_flow_to():
+    update, root = accesskit.build_tree(
+        {
+            "role": Role.ARTICLE,
+            "children": [
+                {"role": Role.SECTION, "name": "Column 1", "flow_to": [3]},
+                {"role": Role.COMPLEMENTARY, "name": "Advert"},
+                {"role": Role.SECTION, "name": "Column 2"},
+            ],
+        }
+    )
+    snapshot = TreeSnapshot(update)
+    assert snapshot.reading_order(1) == [1, 3]
+    assert snapshot.reading_order(2) == [2]
+    assert snapshot.reading_order(99) == []
+
+    column = accesskit.NodeBuilder(Role.SECTION)
+    column.set_name("Column 2")
+    column.set_flow_to([1])
+    change = accesskit.TreeUpdate(root)
+    change.nodes.append((3, column.build(accesskit.NodeClassSet())))
+    cyclic = snapshot.apply(change)
+    assert cyclic.reading_order(1) == [1, 3]
+    assert cyclic.reading_order(3) == [3, 1]
@@ -0,0 +1,68 @@
+// Copyright 2023 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+//! Compares building many similar nodes one at a time with
+//! [`NodeClassSet::build_many`]. Run with `cargo bench -p accesskit`.
+
+use accesskit::{Node, NodeBuilder, NodeClassSet, Rect, Role};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const NODE_COUNT: usize = 10_000;
+const ITERATIONS: u32 = 50;
+
+fn builders() -> Vec<NodeBuilder> {
+    (0..NODE_COUNT)
+        .map(|i| {
+            let mut builder = NodeBuilder::new(Role::ListItem);
+            builder.set_name(format!("Item {}", i));
+            builder.set_bounds(Rect::new(
+                0.0,
+                i as f64 * 20.0,
+                200.0,
+                (i + 1) as f64 * 20.0,
+            ));
+            builder
+        })
+        .collect()
+}
+
+fn measure(mut f: impl FnMut(&mut NodeClassSet, Vec<NodeBuilder>) -> Vec<Node>) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        let mut classes = NodeClassSet::new();
+        let builders = builders();
+        let start = Instant::now();
+        let nodes = black_box(f(&mut classes, builders));
+        total += start.elapsed();
+        // Don't count the time spent freeing the nodes.
+        drop(nodes);
+    }
+    total / ITERATIONS
+}
+
+fn report(name: &str, time: Duration) {
+    println!(
+        "{:<12} {:>10.3?} per {} nodes ({:.1} ns/node)",
+        name,
+        time,
+        NODE_COUNT,
+        time.as_nanos() as f64 / NODE_COUNT as f64
+    );
+}
+
+fn main() {
+    let build = measure(|classes, builders| {
+        builders
+            .into_iter()
+            .map(|builder| builder.build(classes))
+            .collect()
+    });
+    let build_many = measure(|classes, builders| classes.build_many(builders));
+    report("build", build);
+    report("build_many", build_many);
+}
@@ -107,9 +107,20 @@ impl Affine {
         self.0[0] * self.0[3] - self.0[1] * self.0[2]
     }
 
+    /// Returns `true` if this transform has an inverse, i.e. its coefficients
+    /// are finite and its determinant is non-zero. A transform that
+    /// collapses space onto a line or a point, such as a scale by zero,
+    /// isn't invertible, so points can't be mapped back through it
+    /// for hit testing.
+    pub fn is_invertible(self) -> bool {
+        let det = self.determinant();
+        det != 0.0 && det.is_finite() && self.0.iter().all(|c| c.is_finite())
+    }
+
     /// Compute the inverse transform.
     ///
     /// Produces NaN values when the determinant is zero.
+    /// See [`Affine::is_invertible`].
     pub fn inverse(self) -> Affine {
         let inv_det = self.determinant().recip();
         Affine([
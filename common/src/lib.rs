@@ -9,7 +9,7 @@
 // found in the LICENSE.chromium file.
 
 #[cfg(feature = "pyo3")]
-use pyo3::pyclass;
+use pyo3::{pyclass, pymethods};
 #[cfg(feature = "schemars")]
 use schemars::{
     gen::SchemaGenerator,
@@ -22,8 +22,13 @@ use serde::{
     ser::{SerializeMap, SerializeSeq, Serializer},
     Deserialize, Serialize,
 };
-use std::{collections::BTreeSet, ops::DerefMut, sync::Arc};
-#[cfg(feature = "serde")]
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    ops::DerefMut,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+#[cfg(feature = "enumn")]
 use std::{fmt, mem::size_of_val};
 
 mod geometry;
@@ -183,6 +188,10 @@ pub enum Role {
     Slider,
     SpinButton,
     Splitter,
+    /// A status bar or other advisory message region, equivalent to the ARIA
+    /// `status` role. Unless [`Node::live`] is set, it's treated as
+    /// a [`Live::Polite`] live region, and platform adapters map it
+    /// to the platform's status bar role where there is one.
     Status,
     Strong,
     Suggestion,
@@ -273,6 +282,118 @@ impl Default for Role {
     }
 }
 
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl Role {
+    /// Returns true for the roles of controls that accept text input,
+    /// including the specialized input types and editable combo boxes.
+    pub fn is_text_input(&self) -> bool {
+        matches!(
+            self,
+            Role::TextInput
+                | Role::MultilineTextInput
+                | Role::SearchInput
+                | Role::DateInput
+                | Role::DateTimeInput
+                | Role::WeekInput
+                | Role::MonthInput
+                | Role::TimeInput
+                | Role::EmailInput
+                | Role::NumberInput
+                | Role::PasswordInput
+                | Role::PhoneNumberInput
+                | Role::UrlInput
+                | Role::EditableComboBox
+                | Role::SpinButton
+        )
+    }
+
+    /// Returns true for landmark roles, which assistive technologies let
+    /// users navigate between.
+    pub fn is_landmark(&self) -> bool {
+        matches!(
+            self,
+            Role::Banner
+                | Role::Complementary
+                | Role::ContentInfo
+                | Role::Form
+                | Role::Main
+                | Role::Navigation
+                | Role::Region
+                | Role::Search
+        )
+    }
+
+    /// Returns true for roles whose main purpose is to group other nodes,
+    /// such as windows, lists, tables, menus and landmarks.
+    pub fn is_container(&self) -> bool {
+        self.is_landmark()
+            || matches!(
+                self,
+                Role::GenericContainer
+                    | Role::Group
+                    | Role::Pane
+                    | Role::ScrollView
+                    | Role::Window
+                    | Role::Dialog
+                    | Role::AlertDialog
+                    | Role::Application
+                    | Role::Document
+                    | Role::RootWebArea
+                    | Role::Article
+                    | Role::Section
+                    | Role::Figure
+                    | Role::Feed
+                    | Role::List
+                    | Role::ListBox
+                    | Role::ListGrid
+                    | Role::DescriptionList
+                    | Role::Menu
+                    | Role::MenuBar
+                    | Role::MenuListPopup
+                    | Role::RadioGroup
+                    | Role::TabList
+                    | Role::TabPanel
+                    | Role::Toolbar
+                    | Role::Tree
+                    | Role::TreeGrid
+                    | Role::Table
+                    | Role::Grid
+                    | Role::RowGroup
+                    | Role::Row
+            )
+    }
+
+    /// Returns true for roles that need an accessible name to be usable,
+    /// such as controls, images and dialogs. Nodes with these roles should
+    /// have a name, labels or descendants that a name can be computed from;
+    /// decorative images should be hidden instead.
+    pub fn requires_name(&self) -> bool {
+        self.is_text_input()
+            || matches!(
+                self,
+                Role::Button
+                    | Role::DefaultButton
+                    | Role::ToggleButton
+                    | Role::Link
+                    | Role::Image
+                    | Role::CheckBox
+                    | Role::RadioButton
+                    | Role::Switch
+                    | Role::ComboBox
+                    | Role::ListBox
+                    | Role::Slider
+                    | Role::Meter
+                    | Role::ProgressIndicator
+                    | Role::MenuItem
+                    | Role::MenuItemCheckBox
+                    | Role::MenuItemRadio
+                    | Role::Tab
+                    | Role::Dialog
+                    | Role::AlertDialog
+            )
+    }
+}
+
 /// An action to be taken on an accessibility node.
 ///
 /// In contrast to [`DefaultActionVerb`], these describe what happens to the
@@ -368,7 +489,7 @@ impl Serialize for Actions {
     where
         S: Serializer,
     {
-        let mut seq = serializer.serialize_seq(None)?;
+        let mut seq = serializer.serialize_seq(Some(self.0.count_ones() as usize))?;
         for i in 0..((size_of_val(&self.0) as u8) * 8) {
             if let Some(action) = Action::n(i) {
                 if (self.0 & action.mask()) != 0 {
@@ -577,6 +698,13 @@ pub enum AriaCurrent {
     False,
     True,
     Page,
+    /// The current step in a process such as a wizard or stepper. By
+    /// convention, each step is a child of the same container, with
+    /// [`Node::position_in_set`] and [`Node::size_of_set`] set so that
+    /// assistive technologies can report progress like "step 2 of 5";
+    /// [`TreeSnapshot::fill_set_positions`] can fill these in. Only the
+    /// current step should have this value. See
+    /// [`TreeSnapshot::current_step`].
     Step,
     Location,
     Date,
@@ -713,6 +841,27 @@ pub type NodeIdContent = u64;
 #[repr(transparent)]
 pub struct NodeId(pub NodeIdContent);
 
+impl NodeId {
+    /// Derives an ID from a string path such as `"window/toolbar/save"`,
+    /// for toolkits that can't easily carry integer IDs of their own.
+    ///
+    /// The path is hashed with 64-bit FNV-1a over its UTF-8 bytes. This
+    /// hash is part of the API contract and will not change, so the same
+    /// path yields the same ID across runs, platforms, and versions of
+    /// this crate. The result is never zero. Distinct paths can collide;
+    /// among `n` paths, the probability of any collision is roughly
+    /// `n * n / 2^65`, which is negligible for realistic trees, but callers
+    /// must not mix these IDs with IDs allocated some other way.
+    pub fn from_path(path: &str) -> Self {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let hash = path.bytes().fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        });
+        Self(hash.max(1))
+    }
+}
+
 impl From<NodeIdContent> for NodeId {
     #[inline]
     fn from(inner: NodeIdContent) -> Self {
@@ -741,6 +890,20 @@ pub struct CustomAction {
     pub description: Box<str>,
 }
 
+/// A labeled piece of supplementary information about a UI element,
+/// which assistive technologies can announce on demand, e.g. a message's
+/// status in a list of messages. Both strings should be human-friendly
+/// and localized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CustomContent {
+    pub label: Box<str>,
+    pub value: Box<str>,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
@@ -772,7 +935,8 @@ pub struct TextSelection {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, enumn::N))]
+#[cfg_attr(feature = "enumn", derive(enumn::N))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 #[repr(u8)]
@@ -798,6 +962,9 @@ enum Flag {
     IsGrammarError,
     IsSearchMatch,
     IsSuggestion,
+    BoundsUnknown,
+    IsPrimaryContent,
+    Mixed,
 }
 
 impl Flag {
@@ -839,10 +1006,49 @@ enum PropertyValue {
     Rect(Rect),
     TextSelection(Box<TextSelection>),
     CustomActionVec(Vec<CustomAction>),
+    CustomContentVec(Vec<CustomContent>),
+}
+
+#[cfg(feature = "enumn")]
+impl PropertyValue {
+    fn describe(&self) -> String {
+        match self {
+            PropertyValue::None => "unset".into(),
+            PropertyValue::NodeIdVec(value) => format!("{:?}", value),
+            PropertyValue::NodeId(value) => format!("{:?}", value),
+            PropertyValue::String(value) => format!("{:?}", value),
+            PropertyValue::F64(value) => format!("{:?}", value),
+            PropertyValue::Usize(value) => format!("{:?}", value),
+            PropertyValue::Color(value) => format!("#{:08x}", value),
+            PropertyValue::TextDecoration(value) => format!("{:?}", value),
+            PropertyValue::LengthSlice(value) => format!("{:?}", value),
+            PropertyValue::CoordSlice(value) => format!("{:?}", value),
+            PropertyValue::Bool(value) => format!("{:?}", value),
+            PropertyValue::Invalid(value) => format!("{:?}", value),
+            PropertyValue::Checked(value) => format!("{:?}", value),
+            PropertyValue::Live(value) => format!("{:?}", value),
+            PropertyValue::DefaultActionVerb(value) => format!("{:?}", value),
+            PropertyValue::TextDirection(value) => format!("{:?}", value),
+            PropertyValue::Orientation(value) => format!("{:?}", value),
+            PropertyValue::SortDirection(value) => format!("{:?}", value),
+            PropertyValue::AriaCurrent(value) => format!("{:?}", value),
+            PropertyValue::AutoComplete(value) => format!("{:?}", value),
+            PropertyValue::HasPopup(value) => format!("{:?}", value),
+            PropertyValue::ListStyle(value) => format!("{:?}", value),
+            PropertyValue::TextAlign(value) => format!("{:?}", value),
+            PropertyValue::VerticalOffset(value) => format!("{:?}", value),
+            PropertyValue::Affine(value) => format!("{:?}", value),
+            PropertyValue::Rect(value) => format!("{:?}", value),
+            PropertyValue::TextSelection(value) => format!("{:?}", value),
+            PropertyValue::CustomActionVec(value) => format!("{:?}", value),
+            PropertyValue::CustomContentVec(value) => format!("{:?}", value),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, enumn::N))]
+#[cfg_attr(feature = "enumn", derive(enumn::N))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 #[repr(u8)]
@@ -956,10 +1162,238 @@ enum PropertyId {
     TextSelection,
     CustomActions,
 
+    // Compact postcard keys are the positions of these variants, so new
+    // properties must be added here, after all of the existing ones,
+    // rather than with the properties of the same type.
+    AriaRole,
+    CustomContent,
+    Owns,
+    ReturnFocusTo,
+    ReadingLevel,
+    AccessibleText,
+    AutomationId,
+    Caption,
+    ItemStatus,
+
     // This MUST be last.
     Unset,
 }
 
+impl PropertyId {
+    /// Every property, in the order of their discriminants. The length of
+    /// the array makes this fail to compile when a property is added.
+    const ALL: [PropertyId; PropertyId::Unset as usize] = [
+        PropertyId::Children,
+        PropertyId::Controls,
+        PropertyId::Details,
+        PropertyId::DescribedBy,
+        PropertyId::FlowTo,
+        PropertyId::LabelledBy,
+        PropertyId::RadioGroup,
+        PropertyId::ActiveDescendant,
+        PropertyId::ErrorMessage,
+        PropertyId::InPageLinkTarget,
+        PropertyId::MemberOf,
+        PropertyId::NextOnLine,
+        PropertyId::PreviousOnLine,
+        PropertyId::PopupFor,
+        PropertyId::TableHeader,
+        PropertyId::TableRowHeader,
+        PropertyId::TableColumnHeader,
+        PropertyId::Name,
+        PropertyId::Description,
+        PropertyId::Value,
+        PropertyId::AccessKey,
+        PropertyId::ClassName,
+        PropertyId::FontFamily,
+        PropertyId::HtmlTag,
+        PropertyId::InnerHtml,
+        PropertyId::KeyboardShortcut,
+        PropertyId::Language,
+        PropertyId::Placeholder,
+        PropertyId::RoleDescription,
+        PropertyId::StateDescription,
+        PropertyId::Tooltip,
+        PropertyId::Url,
+        PropertyId::ScrollX,
+        PropertyId::ScrollXMin,
+        PropertyId::ScrollXMax,
+        PropertyId::ScrollY,
+        PropertyId::ScrollYMin,
+        PropertyId::ScrollYMax,
+        PropertyId::NumericValue,
+        PropertyId::MinNumericValue,
+        PropertyId::MaxNumericValue,
+        PropertyId::NumericValueStep,
+        PropertyId::NumericValueJump,
+        PropertyId::FontSize,
+        PropertyId::FontWeight,
+        PropertyId::TableRowCount,
+        PropertyId::TableColumnCount,
+        PropertyId::TableRowIndex,
+        PropertyId::TableColumnIndex,
+        PropertyId::TableCellColumnIndex,
+        PropertyId::TableCellColumnSpan,
+        PropertyId::TableCellRowIndex,
+        PropertyId::TableCellRowSpan,
+        PropertyId::HierarchicalLevel,
+        PropertyId::SizeOfSet,
+        PropertyId::PositionInSet,
+        PropertyId::ColorValue,
+        PropertyId::BackgroundColor,
+        PropertyId::ForegroundColor,
+        PropertyId::Overline,
+        PropertyId::Strikethrough,
+        PropertyId::Underline,
+        PropertyId::CharacterLengths,
+        PropertyId::WordLengths,
+        PropertyId::CharacterPositions,
+        PropertyId::CharacterWidths,
+        PropertyId::Expanded,
+        PropertyId::Selected,
+        PropertyId::Invalid,
+        PropertyId::Checked,
+        PropertyId::Live,
+        PropertyId::DefaultActionVerb,
+        PropertyId::TextDirection,
+        PropertyId::Orientation,
+        PropertyId::SortDirection,
+        PropertyId::AriaCurrent,
+        PropertyId::AutoComplete,
+        PropertyId::HasPopup,
+        PropertyId::ListStyle,
+        PropertyId::TextAlign,
+        PropertyId::VerticalOffset,
+        PropertyId::Transform,
+        PropertyId::Bounds,
+        PropertyId::TextSelection,
+        PropertyId::CustomActions,
+        PropertyId::AriaRole,
+        PropertyId::CustomContent,
+        PropertyId::Owns,
+        PropertyId::ReturnFocusTo,
+        PropertyId::ReadingLevel,
+        PropertyId::AccessibleText,
+        PropertyId::AutomationId,
+        PropertyId::Caption,
+        PropertyId::ItemStatus,
+    ];
+
+    /// Returns `false` for the properties that no platform adapter or
+    /// consumer helper reads, which [`Node::has_exposed_changes`] ignores.
+    /// This match is exhaustive, so each new property must be classified
+    /// here, and a property must move to the first arm as soon as
+    /// an adapter starts exposing it.
+    const fn is_exposed(self) -> bool {
+        match self {
+            PropertyId::Children
+            | PropertyId::Controls
+            | PropertyId::Details
+            | PropertyId::DescribedBy
+            | PropertyId::FlowTo
+            | PropertyId::LabelledBy
+            | PropertyId::RadioGroup
+            | PropertyId::ActiveDescendant
+            | PropertyId::ErrorMessage
+            | PropertyId::InPageLinkTarget
+            | PropertyId::MemberOf
+            | PropertyId::NextOnLine
+            | PropertyId::PreviousOnLine
+            | PropertyId::TableRowHeader
+            | PropertyId::TableColumnHeader
+            | PropertyId::Name
+            | PropertyId::Description
+            | PropertyId::Value
+            | PropertyId::AccessKey
+            | PropertyId::KeyboardShortcut
+            | PropertyId::Language
+            | PropertyId::RoleDescription
+            | PropertyId::StateDescription
+            | PropertyId::Tooltip
+            | PropertyId::Url
+            | PropertyId::NumericValue
+            | PropertyId::MinNumericValue
+            | PropertyId::MaxNumericValue
+            | PropertyId::NumericValueStep
+            | PropertyId::NumericValueJump
+            | PropertyId::TableRowCount
+            | PropertyId::TableColumnCount
+            | PropertyId::TableCellColumnIndex
+            | PropertyId::TableCellColumnSpan
+            | PropertyId::TableCellRowIndex
+            | PropertyId::TableCellRowSpan
+            | PropertyId::HierarchicalLevel
+            | PropertyId::SizeOfSet
+            | PropertyId::PositionInSet
+            | PropertyId::ColorValue
+            | PropertyId::CharacterLengths
+            | PropertyId::WordLengths
+            | PropertyId::CharacterPositions
+            | PropertyId::CharacterWidths
+            | PropertyId::Expanded
+            | PropertyId::Selected
+            | PropertyId::Invalid
+            | PropertyId::Checked
+            | PropertyId::Live
+            | PropertyId::DefaultActionVerb
+            | PropertyId::TextDirection
+            | PropertyId::Orientation
+            | PropertyId::AriaCurrent
+            | PropertyId::Transform
+            | PropertyId::Bounds
+            | PropertyId::TextSelection
+            | PropertyId::CustomActions
+            | PropertyId::AriaRole
+            | PropertyId::CustomContent
+            | PropertyId::Owns
+            | PropertyId::ReturnFocusTo
+            | PropertyId::AccessibleText
+            | PropertyId::AutomationId
+            | PropertyId::Caption
+            | PropertyId::ItemStatus => true,
+            PropertyId::PopupFor
+            | PropertyId::TableHeader
+            | PropertyId::ClassName
+            | PropertyId::FontFamily
+            | PropertyId::HtmlTag
+            | PropertyId::InnerHtml
+            | PropertyId::Placeholder
+            | PropertyId::ScrollX
+            | PropertyId::ScrollXMin
+            | PropertyId::ScrollXMax
+            | PropertyId::ScrollY
+            | PropertyId::ScrollYMin
+            | PropertyId::ScrollYMax
+            | PropertyId::FontSize
+            | PropertyId::FontWeight
+            | PropertyId::ReadingLevel
+            | PropertyId::TableRowIndex
+            | PropertyId::TableColumnIndex
+            | PropertyId::BackgroundColor
+            | PropertyId::ForegroundColor
+            | PropertyId::Overline
+            | PropertyId::Strikethrough
+            | PropertyId::Underline
+            | PropertyId::SortDirection
+            | PropertyId::AutoComplete
+            | PropertyId::HasPopup
+            | PropertyId::ListStyle
+            | PropertyId::TextAlign
+            | PropertyId::VerticalOffset
+            | PropertyId::Unset => false,
+        }
+    }
+}
+
+// Checks at compile time that `PropertyId::ALL` lists each property once.
+const _: () = {
+    let mut i = 0;
+    while i < PropertyId::ALL.len() {
+        assert!(PropertyId::ALL[i] as usize == i);
+        i += 1;
+    }
+};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 struct PropertyIndices([u8; PropertyId::Unset as usize]);
@@ -1037,6 +1471,43 @@ impl NodeClassSet {
         instance.get_or_insert_with(Default::default);
         Guard(instance)
     }
+
+    fn intern(&mut self, class: NodeClass) -> Arc<NodeClass> {
+        if let Some(class) = self.0.get(&class) {
+            Arc::clone(class)
+        } else {
+            let class = Arc::new(class);
+            self.0.insert(Arc::clone(&class));
+            class
+        }
+    }
+
+    /// Builds all of the given nodes, in order. The result is the same as
+    /// calling [`NodeBuilder::build`] on each builder, but runs of builders
+    /// with the same role, actions, and set of defined properties, which are
+    /// common when building many similar nodes, only look up their shared
+    /// class in this set once.
+    pub fn build_many(&mut self, builders: Vec<NodeBuilder>) -> Vec<Node> {
+        let mut last_class: Option<Arc<NodeClass>> = None;
+        builders
+            .into_iter()
+            .map(|builder| {
+                let class = match &last_class {
+                    Some(class) if **class == builder.class => Arc::clone(class),
+                    _ => {
+                        let class = self.intern(builder.class);
+                        last_class = Some(Arc::clone(&class));
+                        class
+                    }
+                };
+                Node {
+                    class,
+                    flags: builder.flags,
+                    props: builder.props.into(),
+                }
+            })
+            .collect()
+    }
 }
 
 /// A single accessible object. A complete UI is represented as a tree of these.
@@ -1399,19 +1870,48 @@ impl NodeBuilder {
     }
 
     pub fn build(self, classes: &mut NodeClassSet) -> Node {
-        let class = if let Some(class) = classes.0.get(&self.class) {
-            Arc::clone(class)
-        } else {
-            let class = Arc::new(self.class);
-            classes.0.insert(Arc::clone(&class));
-            class
-        };
+        let class = classes.intern(self.class);
         Node {
             class,
             flags: self.flags,
             props: self.props.into(),
         }
     }
+
+    fn from_node(node: &Node) -> Self {
+        Self {
+            class: *node.class,
+            flags: node.flags,
+            props: node.props.to_vec(),
+        }
+    }
+
+    /// Overlay the properties that are set in another builder onto this one.
+    /// Every property that is set in `overlay` replaces the corresponding
+    /// property in this builder, while properties that aren't set in
+    /// `overlay`, including those that were cleared, are left alone.
+    /// Flags and actions are combined, so an overlay can add them but
+    /// can't remove them. The role of this builder is kept.
+    pub fn merge(&mut self, overlay: &NodeBuilder) {
+        self.class.actions.0 |= overlay.class.actions.0;
+        self.flags |= overlay.flags;
+        for (id, overlay_index) in overlay.class.indices.0.iter().enumerate() {
+            if *overlay_index == PropertyId::Unset as u8 {
+                continue;
+            }
+            let value = &overlay.props[*overlay_index as usize];
+            if matches!(value, PropertyValue::None) {
+                continue;
+            }
+            let index = self.class.indices.0[id];
+            if index == PropertyId::Unset as u8 {
+                self.props.push(value.clone());
+                self.class.indices.0[id] = (self.props.len() - 1) as u8;
+            } else {
+                self.props[index as usize] = value.clone();
+            }
+        }
+    }
 }
 
 impl Node {
@@ -1419,6 +1919,235 @@ impl Node {
     pub fn role(&self) -> Role {
         self.class.role
     }
+
+    fn property_at(&self, id: usize) -> &PropertyValue {
+        match self.class.indices.0[id] {
+            index if index == PropertyId::Unset as u8 => &PropertyValue::None,
+            index => &self.props[index as usize],
+        }
+    }
+
+    fn eq_except_children(&self, other: &Node) -> bool {
+        self.class.role == other.class.role
+            && self.class.actions == other.class.actions
+            && self.flags == other.flags
+            && (0..PropertyId::Unset as usize)
+                .filter(|id| *id != PropertyId::Children as usize)
+                .all(|id| self.property_at(id) == other.property_at(id))
+    }
+
+    /// Returns which groups of properties differ between this node and
+    /// an older version of the same node, so that platform adapters can
+    /// decide which events to fire.
+    pub fn diff(&self, old: &Node) -> NodeChangeSet {
+        const NAME: &[PropertyId] = &[
+            PropertyId::Name,
+            PropertyId::LabelledBy,
+            PropertyId::Caption,
+        ];
+        const DESCRIPTION: &[PropertyId] = &[PropertyId::Description, PropertyId::DescribedBy];
+        const VALUE: &[PropertyId] = &[
+            PropertyId::Value,
+            PropertyId::NumericValue,
+            PropertyId::MinNumericValue,
+            PropertyId::MaxNumericValue,
+            PropertyId::NumericValueStep,
+            PropertyId::NumericValueJump,
+            PropertyId::ColorValue,
+        ];
+        const STATES: &[PropertyId] = &[
+            PropertyId::Checked,
+            PropertyId::Expanded,
+            PropertyId::Invalid,
+            PropertyId::AriaCurrent,
+            PropertyId::StateDescription,
+            PropertyId::ItemStatus,
+        ];
+        const BOUNDS: &[PropertyId] = &[PropertyId::Transform, PropertyId::Bounds];
+        const CHILDREN: &[PropertyId] = &[PropertyId::Children];
+        const SELECTION: &[PropertyId] = &[
+            PropertyId::Selected,
+            PropertyId::TextSelection,
+            PropertyId::ActiveDescendant,
+        ];
+        const ACTIONS: &[PropertyId] = &[PropertyId::DefaultActionVerb, PropertyId::CustomActions];
+        const GROUPS: &[&[PropertyId]] = &[
+            NAME,
+            DESCRIPTION,
+            VALUE,
+            STATES,
+            BOUNDS,
+            CHILDREN,
+            SELECTION,
+            ACTIONS,
+        ];
+        const BOUNDS_FLAGS: u32 = 1 << (Flag::BoundsUnknown as u8);
+        const OTHER_FLAGS: u32 = (1 << (Flag::Bold as u8)) | (1 << (Flag::Italic as u8));
+
+        let property_changed = |id: usize| self.property_at(id) != old.property_at(id);
+        let changed = |ids: &[PropertyId]| ids.iter().any(|id| property_changed(*id as usize));
+        let changed_flags = self.flags ^ old.flags;
+        NodeChangeSet {
+            role: self.class.role != old.class.role,
+            name: changed(NAME),
+            description: changed(DESCRIPTION),
+            value: changed(VALUE),
+            states: changed(STATES) || (changed_flags & !(BOUNDS_FLAGS | OTHER_FLAGS)) != 0,
+            bounds: changed(BOUNDS) || (changed_flags & BOUNDS_FLAGS) != 0,
+            children: changed(CHILDREN),
+            selection: changed(SELECTION),
+            actions: self.class.actions != old.class.actions || changed(ACTIONS),
+            other: (changed_flags & OTHER_FLAGS) != 0
+                || (0..PropertyId::Unset as usize)
+                    .filter(|id| {
+                        !GROUPS
+                            .iter()
+                            .any(|group| group.iter().any(|grouped| *grouped as usize == *id))
+                    })
+                    .any(property_changed),
+        }
+    }
+
+    /// Returns `true` if this node differs from an older version of
+    /// the same node in a way that at least one platform adapter exposes
+    /// to assistive technologies. This is based on [`Node::diff`], but
+    /// ignores properties and flags that no adapter currently surfaces,
+    /// such as [`Node::class_name`], [`Node::font_size`] and
+    /// [`Node::is_bold`], so it can be used to skip updates that would
+    /// be a no-op from the user's perspective.
+    pub fn has_exposed_changes(&self, old: &Node) -> bool {
+        let changes = self.diff(old);
+        if !changes.other {
+            return !changes.is_empty();
+        }
+        let other_only = NodeChangeSet {
+            other: true,
+            ..Default::default()
+        };
+        // Bold and italic, the only flags in the `other` group,
+        // aren't exposed, so only the remaining properties matter.
+        changes != other_only
+            || PropertyId::ALL
+                .iter()
+                .filter(|id| id.is_exposed())
+                .any(|id| self.property_at(*id as usize) != old.property_at(*id as usize))
+    }
+
+    #[cfg(feature = "enumn")]
+    /// Returns each property that differs between this node and another,
+    /// with a description of both values. The role and supported actions
+    /// are compared too, under the names `Role` and `Actions`.
+    /// The format of the descriptions isn't stable.
+    pub fn property_changes(&self, other: &Node) -> Vec<PropertyChange> {
+        let mut changes = Vec::new();
+        if self.class.role != other.class.role {
+            changes.push(PropertyChange {
+                name: "Role".into(),
+                old: format!("{:?}", self.class.role),
+                new: format!("{:?}", other.class.role),
+            });
+        }
+        if self.class.actions != other.class.actions {
+            let describe_actions = |actions: Actions| {
+                let names = (0..((size_of_val(&actions.0) as u8) * 8))
+                    .filter_map(Action::n)
+                    .filter(|action| (actions.0 & action.mask()) != 0)
+                    .map(|action| format!("{:?}", action))
+                    .collect::<Vec<_>>();
+                format!("[{}]", names.join(", "))
+            };
+            changes.push(PropertyChange {
+                name: "Actions".into(),
+                old: describe_actions(self.class.actions),
+                new: describe_actions(other.class.actions),
+            });
+        }
+        for flag in (0..((size_of_val(&self.flags) as u8) * 8)).filter_map(Flag::n) {
+            let old = (self.flags & flag.mask()) != 0;
+            let new = (other.flags & flag.mask()) != 0;
+            if old != new {
+                changes.push(PropertyChange {
+                    name: format!("{:?}", flag),
+                    old: old.to_string(),
+                    new: new.to_string(),
+                });
+            }
+        }
+        for id in (0..(PropertyId::Unset as u8)).filter_map(PropertyId::n) {
+            let old = self.property_at(id as usize);
+            let new = other.property_at(id as usize);
+            if old != new {
+                changes.push(PropertyChange {
+                    name: format!("{:?}", id),
+                    old: old.describe(),
+                    new: new.describe(),
+                });
+            }
+        }
+        changes
+    }
+
+    #[cfg(feature = "enumn")]
+    /// Returns a line for each property that differs between this node and
+    /// another, giving the property's name and both values, such as
+    /// `Name: "Save" -> "Save as"`. The role and supported actions are
+    /// compared too. Intended for printing when a test comparing trees
+    /// fails; the exact format isn't stable.
+    pub fn describe_diff(&self, other: &Node) -> Vec<String> {
+        self.property_changes(other)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+}
+
+#[cfg(feature = "enumn")]
+/// A property that differs between two versions of a node, as returned
+/// by [`Node::property_changes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropertyChange {
+    pub name: String,
+    pub old: String,
+    pub new: String,
+}
+
+#[cfg(feature = "enumn")]
+impl fmt::Display for PropertyChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.name, self.old, self.new)
+    }
+}
+
+/// The groups of properties that differ between two versions of a node,
+/// as returned by [`Node::diff`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NodeChangeSet {
+    pub role: bool,
+    /// [`Node::name`], [`Node::labelled_by`] or [`Node::caption`].
+    pub name: bool,
+    /// [`Node::description`] or [`Node::described_by`].
+    pub description: bool,
+    /// The textual, numeric or color value, or the numeric range.
+    pub value: bool,
+    /// Boolean flags such as [`Node::is_disabled`], and other states such
+    /// as [`Node::checked`] and [`Node::is_expanded`].
+    pub states: bool,
+    /// [`Node::bounds`], [`Node::transform`] or [`Node::is_bounds_unknown`].
+    pub bounds: bool,
+    pub children: bool,
+    /// [`Node::is_selected`], [`Node::text_selection`] or
+    /// [`Node::active_descendant`].
+    pub selection: bool,
+    /// The supported actions, default action verb or custom actions.
+    pub actions: bool,
+    /// Any property not covered by the other groups.
+    pub other: bool,
+}
+
+impl NodeChangeSet {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 impl NodeBuilder {
@@ -1478,6 +2207,9 @@ flag_methods! {
     /// Use for a textbox that allows focus/selection but not input.
     (ReadOnly, is_read_only, set_read_only, clear_read_only),
     /// Use for a control or group of controls that disallows input.
+    /// Setting this on a container implicitly disables all of its
+    /// descendants; see [`TreeUpdate::propagate_disabled`] for consumers
+    /// that need the flag set on each node.
     (Disabled, is_disabled, set_disabled, clear_disabled),
     (Bold, is_bold, set_bold, clear_bold),
     (Italic, is_italic, set_italic, clear_italic),
@@ -1492,7 +2224,30 @@ flag_methods! {
     (IsSpellingError, is_spelling_error, set_is_spelling_error, clear_is_spelling_error),
     (IsGrammarError, is_grammar_error, set_is_grammar_error, clear_is_grammar_error),
     (IsSearchMatch, is_search_match, set_is_search_match, clear_is_search_match),
-    (IsSuggestion, is_suggestion, set_is_suggestion, clear_is_suggestion)
+    (IsSuggestion, is_suggestion, set_is_suggestion, clear_is_suggestion),
+    /// Indicates that the node's geometry hasn't been measured yet,
+    /// e.g. because layout is still in progress. This is different from
+    /// leaving [`bounds`] unset, which means that the node has no geometry
+    /// of its own, and from setting it to an empty rectangle, which means
+    /// that the node is deliberately zero-sized. Platform adapters treat
+    /// a node with unknown bounds as if it had no bounds, rather than
+    /// reporting a bogus rectangle, even if [`bounds`] is also set.
+    ///
+    /// [`bounds`]: Node::bounds
+    (BoundsUnknown, is_bounds_unknown, set_bounds_unknown, clear_bounds_unknown),
+    /// Marks this node as the primary content area of the application,
+    /// i.e. the region that assistive technologies should treat as the
+    /// main document when navigating by landmark. This can be set
+    /// on a node of any role, e.g. a [`Role::ScrollView`] hosting the
+    /// content of an editor; platform adapters expose it the same way as
+    /// [`Role::Main`]. At most one node in the tree should have this flag.
+    (IsPrimaryContent, is_primary_content, set_is_primary_content, clear_is_primary_content),
+    /// Indicates that this node represents a partial or mixed state,
+    /// e.g. an expandable group in which only some of the items are selected.
+    /// This is the general form of [`Checked::Mixed`], for nodes that
+    /// aren't checkable themselves; platform adapters expose both
+    /// the same way where the platform has a concept of a mixed state.
+    (Mixed, is_mixed, set_mixed, clear_mixed)
 }
 
 option_ref_type_getters! {
@@ -1536,7 +2291,8 @@ copy_type_setters! {
 
 vec_type_methods! {
     (NodeId, NodeIdVec, get_node_id_vec, set_node_id_vec, push_to_node_id_vec),
-    (CustomAction, CustomActionVec, get_custom_action_vec, set_custom_action_vec, push_to_custom_action_vec)
+    (CustomAction, CustomActionVec, get_custom_action_vec, set_custom_action_vec, push_to_custom_action_vec),
+    (CustomContent, CustomContentVec, get_custom_content_vec, set_custom_content_vec, push_to_custom_content_vec)
 }
 
 node_id_vec_property_methods! {
@@ -1546,6 +2302,14 @@ node_id_vec_property_methods! {
     (DescribedBy, described_by, set_described_by, push_described_by, clear_described_by),
     (FlowTo, flow_to, set_flow_to, push_flow_to, clear_flow_to),
     (LabelledBy, labelled_by, set_labelled_by, push_labelled_by, clear_labelled_by),
+    /// Nodes that this node logically owns even though they aren't among
+    /// its [`children`], e.g. a popup menu that is rendered elsewhere in the
+    /// tree but belongs to the button that opened it. This corresponds to
+    /// the ARIA `aria-owns` attribute, but unlike in ARIA, the owned nodes
+    /// aren't moved; they remain children of their actual parents.
+    ///
+    /// [`children`]: Node::children
+    (Owns, owns, set_owns, push_owned, clear_owns),
     /// On radio buttons this should be set to a list of all of the buttons
     /// in the same group as this one, including this radio button itself.
     (RadioGroup, radio_group, set_radio_group, push_to_radio_group, clear_radio_group)
@@ -1561,7 +2325,18 @@ node_id_property_methods! {
     (PopupFor, popup_for, set_popup_for, clear_popup_for),
     (TableHeader, table_header, set_table_header, clear_table_header),
     (TableRowHeader, table_row_header, set_table_row_header, clear_table_row_header),
-    (TableColumnHeader, table_column_header, set_table_column_header, clear_table_column_header)
+    (TableColumnHeader, table_column_header, set_table_column_header, clear_table_column_header),
+    /// On the root of a modal, such as a dialog, the node that should get
+    /// the focus back when the modal is closed, usually the control that
+    /// opened it. AccessKit doesn't move the focus itself; this lets the host
+    /// keep track of where the focus should go.
+    (ReturnFocusTo, return_focus_to, set_return_focus_to, clear_return_focus_to),
+    /// On a table or figure, the node containing its caption, typically
+    /// with the [`Role::Caption`] or [`Role::FigureCaption`] role. If the node
+    /// has no name or labels, its name is computed from the caption,
+    /// so it's announced without having to duplicate the caption's text
+    /// in [`Node::name`].
+    (Caption, caption, set_caption, clear_caption)
 }
 
 string_property_methods! {
@@ -1576,6 +2351,14 @@ string_property_methods! {
     ///
     /// [`keyboard_shortcut`]: Node::keyboard_shortcut
     (AccessKey, access_key, set_access_key, clear_access_key),
+    /// The ARIA role token that this node was authored with, e.g. `"feed"`,
+    /// for platforms that expose it directly. This doesn't affect how
+    /// platform adapters map [`role`] unless [`role_description`] is also set.
+    /// See [`NodeBuilder::set_custom_role`].
+    ///
+    /// [`role`]: Node::role
+    /// [`role_description`]: Node::role_description
+    (AriaRole, aria_role, set_aria_role, clear_aria_role),
     (ClassName, class_name, set_class_name, clear_class_name),
     /// Only present when different from parent.
     (FontFamily, font_family, set_font_family, clear_font_family),
@@ -1608,7 +2391,125 @@ string_property_methods! {
     ///
     /// [`name`]: Node::name
     (Tooltip, tooltip, set_tooltip, clear_tooltip),
-    (Url, url, set_url, clear_url)
+    (Url, url, set_url, clear_url),
+    /// Text that assistive technologies should present for this node
+    /// instead of its computed name, without affecting [`name`] itself
+    /// or any visible label. This should be used sparingly, only when
+    /// the computed name is awkward when spoken; in most cases it's better
+    /// to fix the name or the labels it's computed from.
+    ///
+    /// [`name`]: Node::name
+    (AccessibleText, accessible_text, set_accessible_text, clear_accessible_text),
+    /// A stable identifier for locating this node in automated UI tests.
+    /// This isn't presented to users; adapters may expose it through
+    /// a platform property meant for test automation, such as UI Automation's
+    /// `AutomationId` on Windows. It should be unique within the tree.
+    (AutomationId, automation_id, set_automation_id, clear_automation_id),
+    /// A short, localized description of a transient status of this item,
+    /// such as "loading" or "error". Unlike [`state_description`], this
+    /// doesn't replace the description of any other state.
+    ///
+    /// [`state_description`]: Node::state_description
+    (ItemStatus, item_status, set_item_status, clear_item_status)
+}
+
+fn millis_since_epoch(time: SystemTime) -> f64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs_f64() * 1000.0,
+        Err(error) => -error.duration().as_secs_f64() * 1000.0,
+    }
+}
+
+fn system_time_from_millis(millis: f64) -> Option<SystemTime> {
+    let duration = Duration::try_from_secs_f64(millis.abs() / 1000.0).ok()?;
+    if millis < 0.0 {
+        UNIX_EPOCH.checked_sub(duration)
+    } else {
+        UNIX_EPOCH.checked_add(duration)
+    }
+}
+
+impl NodeBuilder {
+    /// Sets the earliest and latest values accepted by a date or date-time
+    /// input. The bounds are stored as [`min_numeric_value`] and
+    /// [`max_numeric_value`] in milliseconds since the Unix epoch, so
+    /// platform adapters report them wherever they report numeric ranges.
+    ///
+    /// [`min_numeric_value`]: Node::min_numeric_value
+    /// [`max_numeric_value`]: Node::max_numeric_value
+    pub fn set_date_time_range(&mut self, min: SystemTime, max: SystemTime) {
+        self.set_min_numeric_value(millis_since_epoch(min));
+        self.set_max_numeric_value(millis_since_epoch(max));
+    }
+
+    /// Sets the earliest and latest values accepted by a time input, as
+    /// offsets from midnight. Like [`NodeBuilder::set_date_time_range`],
+    /// the bounds are stored as numeric values in milliseconds.
+    pub fn set_time_of_day_range(&mut self, min: Duration, max: Duration) {
+        self.set_min_numeric_value(min.as_secs_f64() * 1000.0);
+        self.set_max_numeric_value(max.as_secs_f64() * 1000.0);
+    }
+
+    /// Sets the progress of a task, such as 450 of 1000 megabytes copied,
+    /// along with the text that should be spoken for it, such as
+    /// "450 of 1000 MB". The counts are stored as [`numeric_value`] with
+    /// a [`min_numeric_value`] of zero and a [`max_numeric_value`] of `total`,
+    /// so platform adapters report both counts wherever they report numeric
+    /// ranges, and the text is stored as [`value`].
+    ///
+    /// [`numeric_value`]: Node::numeric_value
+    /// [`min_numeric_value`]: Node::min_numeric_value
+    /// [`max_numeric_value`]: Node::max_numeric_value
+    /// [`value`]: Node::value
+    pub fn set_progress(&mut self, current: f64, total: f64, text: impl Into<Box<str>>) {
+        self.set_numeric_value(current);
+        self.set_min_numeric_value(0.0);
+        self.set_max_numeric_value(total);
+        self.set_value(text);
+    }
+}
+
+impl Node {
+    /// Decodes the bounds set by [`NodeBuilder::set_date_time_range`].
+    /// Returns `None` unless both bounds are set and representable.
+    pub fn date_time_range(&self) -> Option<(SystemTime, SystemTime)> {
+        Some((
+            system_time_from_millis(self.min_numeric_value()?)?,
+            system_time_from_millis(self.max_numeric_value()?)?,
+        ))
+    }
+
+    /// Decodes the bounds set by [`NodeBuilder::set_time_of_day_range`].
+    /// Returns `None` unless both bounds are set and non-negative.
+    pub fn time_of_day_range(&self) -> Option<(Duration, Duration)> {
+        let decode = |millis: f64| Duration::try_from_secs_f64(millis / 1000.0).ok();
+        Some((
+            decode(self.min_numeric_value()?)?,
+            decode(self.max_numeric_value()?)?,
+        ))
+    }
+}
+
+impl NodeBuilder {
+    /// Describe a custom control type by setting [`aria_role`] and
+    /// [`role_description`] together. Platform adapters give these
+    /// precedence over the mapping of the node's [`role`]: the role
+    /// description is what assistive technologies speak as the control type,
+    /// and the ARIA role is exposed where the platform has a place for it.
+    /// The node's [`role`] should still be set to the closest standard role,
+    /// since it determines the interfaces and patterns that are exposed.
+    ///
+    /// [`aria_role`]: Node::aria_role
+    /// [`role_description`]: Node::role_description
+    /// [`role`]: Node::role
+    pub fn set_custom_role(
+        &mut self,
+        aria_role: impl Into<Box<str>>,
+        role_description: impl Into<Box<str>>,
+    ) {
+        self.set_aria_role(aria_role);
+        self.set_role_description(role_description);
+    }
 }
 
 f64_property_methods! {
@@ -1618,6 +2519,12 @@ f64_property_methods! {
     (ScrollY, scroll_y, set_scroll_y, clear_scroll_y),
     (ScrollYMin, scroll_y_min, set_scroll_y_min, clear_scroll_y_min),
     (ScrollYMax, scroll_y_max, set_scroll_y_max, clear_scroll_y_max),
+    /// For date and time inputs, the numeric value and its bounds use the
+    /// encoding of the HTML `valueAsNumber` attribute: milliseconds since
+    /// the Unix epoch for [`Role::DateInput`] and [`Role::DateTimeInput`],
+    /// and milliseconds since midnight for [`Role::TimeInput`]. See
+    /// [`NodeBuilder::set_date_time_range`] and
+    /// [`NodeBuilder::set_time_of_day_range`].
     (NumericValue, numeric_value, set_numeric_value, clear_numeric_value),
     (MinNumericValue, min_numeric_value, set_min_numeric_value, clear_min_numeric_value),
     (MaxNumericValue, max_numeric_value, set_max_numeric_value, clear_max_numeric_value),
@@ -1627,7 +2534,14 @@ f64_property_methods! {
     (FontSize, font_size, set_font_size, clear_font_size),
     /// Font weight can take on any arbitrary numeric value. Increments of 100 in
     /// range `[0, 900]` represent keywords such as light, normal, bold, etc.
-    (FontWeight, font_weight, set_font_weight, clear_font_weight)
+    (FontWeight, font_weight, set_font_weight, clear_font_weight),
+    /// The reading level of the node's content, expressed as a school grade
+    /// level such as the result of the Flesch-Kincaid grade formula, where
+    /// higher values indicate more complex text. Assistive technologies that
+    /// adapt content for readers with cognitive disabilities can use this
+    /// to decide whether to offer a simplified alternative. Platform
+    /// adapters that have no equivalent ignore it.
+    (ReadingLevel, reading_level, set_reading_level, clear_reading_level)
 }
 
 usize_property_methods! {
@@ -1646,6 +2560,12 @@ usize_property_methods! {
 
 color_property_methods! {
     /// For [`Role::ColorWell`], specifies the selected color in RGBA.
+    /// Assistive technologies announce the node's [`value`] for this color,
+    /// so that should be set to a human-friendly, localized description
+    /// such as a color name. If it isn't set, platform adapters fall back
+    /// to a hexadecimal notation such as `#ff8000`.
+    ///
+    /// [`value`]: Node::value
     (ColorValue, color_value, set_color_value, clear_color_value),
     /// Background color in RGBA.
     (BackgroundColor, background_color, set_background_color, clear_background_color),
@@ -1659,6 +2579,117 @@ text_decoration_property_methods! {
     (Underline, underline, set_underline, clear_underline)
 }
 
+/// The decorations of a text run, as returned by [`Node::text_decorations`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextDecorations {
+    pub underline: Option<TextDecoration>,
+    pub overline: Option<TextDecoration>,
+    pub strikethrough: Option<TextDecoration>,
+}
+
+impl NodeBuilder {
+    /// Sets [`underline`], [`overline`] and [`strikethrough`] together,
+    /// clearing any of them that are `None`.
+    ///
+    /// [`underline`]: Node::underline
+    /// [`overline`]: Node::overline
+    /// [`strikethrough`]: Node::strikethrough
+    pub fn set_text_decorations(
+        &mut self,
+        underline: Option<TextDecoration>,
+        overline: Option<TextDecoration>,
+        strikethrough: Option<TextDecoration>,
+    ) {
+        match underline {
+            Some(value) => self.set_underline(value),
+            None => self.clear_underline(),
+        }
+        match overline {
+            Some(value) => self.set_overline(value),
+            None => self.clear_overline(),
+        }
+        match strikethrough {
+            Some(value) => self.set_strikethrough(value),
+            None => self.clear_strikethrough(),
+        }
+    }
+}
+
+impl Node {
+    /// Returns the decorations set with [`NodeBuilder::set_text_decorations`]
+    /// or the individual setters.
+    pub fn text_decorations(&self) -> TextDecorations {
+        TextDecorations {
+            underline: self.underline(),
+            overline: self.overline(),
+            strikethrough: self.strikethrough(),
+        }
+    }
+}
+
+impl Node {
+    /// Returns the bounding box of the characters from `start` up to but
+    /// not including `end` within an inline text node, in the node's own
+    /// coordinate space. The range is clamped to the node's characters;
+    /// an empty range gives a zero-width box at the insertion point, and
+    /// the full range gives the node's [`Node::bounds`]. Returns `None`
+    /// if the node lacks bounds, [`Node::character_positions`],
+    /// [`Node::character_widths`] or [`Node::text_direction`].
+    pub fn character_range_bounds(&self, start: usize, end: usize) -> Option<Rect> {
+        let mut rect = self.bounds()?;
+        let positions = self.character_positions()?;
+        let widths = self.character_widths()?;
+        let direction = self.text_direction()?;
+        let len = self
+            .character_lengths()
+            .len()
+            .min(positions.len())
+            .min(widths.len());
+        let end = end.min(len);
+        let start = start.min(end);
+        if start == 0 && end == len {
+            return Some(rect);
+        }
+        let pixel_start = if start < len {
+            positions[start]
+        } else {
+            positions[start - 1] + widths[start - 1]
+        };
+        let pixel_end = if end == start {
+            pixel_start
+        } else {
+            positions[end - 1] + widths[end - 1]
+        };
+        let pixel_start = f64::from(pixel_start);
+        let pixel_end = f64::from(pixel_end);
+        match direction {
+            TextDirection::LeftToRight => {
+                let orig_left = rect.x0;
+                rect.x0 = orig_left + pixel_start;
+                rect.x1 = orig_left + pixel_end;
+            }
+            TextDirection::RightToLeft => {
+                let orig_right = rect.x1;
+                rect.x1 = orig_right - pixel_start;
+                rect.x0 = orig_right - pixel_end;
+            }
+            // Note: The following directions assume that the rectangle,
+            // in the node's coordinate space, is y-down.
+            TextDirection::TopToBottom => {
+                let orig_top = rect.y0;
+                rect.y0 = orig_top + pixel_start;
+                rect.y1 = orig_top + pixel_end;
+            }
+            TextDirection::BottomToTop => {
+                let orig_bottom = rect.y1;
+                rect.y1 = orig_bottom - pixel_start;
+                rect.y0 = orig_bottom - pixel_end;
+            }
+        }
+        Some(rect)
+    }
+}
+
 length_slice_property_methods! {
     /// For inline text. The length (non-inclusive) of each character
     /// in UTF-8 code units (bytes). The sum of these lengths must equal
@@ -1812,7 +2843,11 @@ property_methods! {
 }
 
 vec_property_methods! {
-    (CustomActions, CustomAction, custom_actions, get_custom_action_vec, set_custom_actions, set_custom_action_vec, push_custom_action, push_to_custom_action_vec, clear_custom_actions)
+    (CustomActions, CustomAction, custom_actions, get_custom_action_vec, set_custom_actions, set_custom_action_vec, push_custom_action, push_to_custom_action_vec, clear_custom_actions),
+    /// Supplementary details that assistive technologies can announce
+    /// after the node's name, value and other standard properties,
+    /// in the order given. Currently only exposed on macOS.
+    (CustomContent, CustomContent, custom_content, get_custom_content_vec, set_custom_content, set_custom_content_vec, push_custom_content, push_to_custom_content_vec, clear_custom_content)
 }
 
 #[cfg(feature = "serde")]
@@ -1833,20 +2868,76 @@ enum DeserializeKey {
     Unknown(String),
 }
 
+/// The key of a node field in the postcard format, which isn't
+/// self-describing, so the untagged [`DeserializeKey`] can't be used.
+/// Properties and flags are identified by their position in
+/// [`PropertyId`] and [`Flag`], so new variants must be added at the end.
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+enum CompactKey {
+    ClassField(ClassFieldId),
+    Flag(Flag),
+    Property(PropertyId),
+}
+
+#[cfg(feature = "serde")]
+impl From<CompactKey> for DeserializeKey {
+    fn from(key: CompactKey) -> Self {
+        match key {
+            CompactKey::ClassField(id) => Self::ClassField(id),
+            CompactKey::Flag(flag) => Self::Flag(flag),
+            CompactKey::Property(id) => Self::Property(id),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct SerializeKey {
+    key: CompactKey,
+    compact: bool,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SerializeKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.compact {
+            return self.key.serialize(serializer);
+        }
+        match &self.key {
+            CompactKey::ClassField(id) => id.serialize(serializer),
+            CompactKey::Flag(flag) => flag.serialize(serializer),
+            CompactKey::Property(id) => id.serialize(serializer),
+        }
+    }
+}
+
 #[cfg(feature = "serde")]
 macro_rules! serialize_class_fields {
-    ($self:ident, $map:ident, { $(($name:ident, $id:ident)),+ }) => {
-        $($map.serialize_entry(&ClassFieldId::$id, &$self.class.$name)?;)*
+    ($self:ident, $map:ident, $compact:ident, { $(($name:ident, $id:ident)),+ }) => {
+        $($map.serialize_entry(
+            &SerializeKey {
+                key: CompactKey::ClassField(ClassFieldId::$id),
+                compact: $compact,
+            },
+            &$self.class.$name,
+        )?;)*
     }
 }
 
 #[cfg(feature = "serde")]
 macro_rules! serialize_property {
-    ($self:ident, $map:ident, $index:ident, $id:ident, { $($variant:ident),+ }) => {
+    ($self:ident, $map:ident, $compact:ident, $index:ident, $id:ident, { $($variant:ident),+ }) => {
         match &$self.props[$index as usize] {
             PropertyValue::None => (),
             $(PropertyValue::$variant(value) => {
-                $map.serialize_entry(&$id, &Some(value))?;
+                let key = SerializeKey {
+                    key: CompactKey::Property($id),
+                    compact: $compact,
+                };
+                $map.serialize_entry(&key, &Some(value))?;
             })*
         }
     }
@@ -1887,15 +2978,40 @@ impl Serialize for Node {
     where
         S: Serializer,
     {
-        let mut map = serializer.serialize_map(None)?;
-        serialize_class_fields!(self, map, {
+        self.serialize_with_keys(serializer, false)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Node {
+    fn serialize_with_keys<S>(&self, serializer: S, compact: bool) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let property_count = self
+            .class
+            .indices
+            .0
+            .iter()
+            .filter(|index| {
+                **index != PropertyId::Unset as u8
+                    && !matches!(self.props[**index as usize], PropertyValue::None)
+            })
+            .count();
+        let len = 2 + self.flags.count_ones() as usize + property_count;
+        let mut map = serializer.serialize_map(Some(len))?;
+        serialize_class_fields!(self, map, compact, {
             (role, Role),
             (actions, Actions)
         });
         for i in 0..((size_of_val(&self.flags) as u8) * 8) {
             if let Some(flag) = Flag::n(i) {
                 if (self.flags & flag.mask()) != 0 {
-                    map.serialize_entry(&flag, &true)?;
+                    let key = SerializeKey {
+                        key: CompactKey::Flag(flag),
+                        compact,
+                    };
+                    map.serialize_entry(&key, &true)?;
                 }
             }
         }
@@ -1904,7 +3020,7 @@ impl Serialize for Node {
                 continue;
             }
             let id = PropertyId::n(id as _).unwrap();
-            serialize_property!(self, map, index, id, {
+            serialize_property!(self, map, compact, index, id, {
                 NodeIdVec,
                 NodeId,
                 String,
@@ -1931,7 +3047,8 @@ impl Serialize for Node {
                 Affine,
                 Rect,
                 TextSelection,
-                CustomActionVec
+                CustomActionVec,
+                CustomContentVec
             });
         }
         map.end()
@@ -1939,7 +3056,9 @@ impl Serialize for Node {
 }
 
 #[cfg(feature = "serde")]
-struct NodeVisitor;
+struct NodeVisitor {
+    compact: bool,
+}
 
 #[cfg(feature = "serde")]
 impl<'de> Visitor<'de> for NodeVisitor {
@@ -1955,7 +3074,15 @@ impl<'de> Visitor<'de> for NodeVisitor {
         V: MapAccess<'de>,
     {
         let mut builder = NodeBuilder::default();
-        while let Some(key) = map.next_key()? {
+        loop {
+            let key = if self.compact {
+                map.next_key::<CompactKey>()?.map(DeserializeKey::from)
+            } else {
+                map.next_key()?
+            };
+            let Some(key) = key else {
+                break;
+            };
             match key {
                 DeserializeKey::ClassField(id) => {
                     deserialize_class_field!(builder, map, id, {
@@ -1979,6 +3106,7 @@ impl<'de> Visitor<'de> for NodeVisitor {
                             DescribedBy,
                             FlowTo,
                             LabelledBy,
+                            Owns,
                             RadioGroup
                         },
                         NodeId {
@@ -1991,13 +3119,16 @@ impl<'de> Visitor<'de> for NodeVisitor {
                             PopupFor,
                             TableHeader,
                             TableRowHeader,
-                            TableColumnHeader
+                            TableColumnHeader,
+                            ReturnFocusTo,
+                            Caption
                         },
                         String {
                             Name,
                             Description,
                             Value,
                             AccessKey,
+                            AriaRole,
                             ClassName,
                             FontFamily,
                             HtmlTag,
@@ -2008,7 +3139,10 @@ impl<'de> Visitor<'de> for NodeVisitor {
                             RoleDescription,
                             StateDescription,
                             Tooltip,
-                            Url
+                            Url,
+                            AccessibleText,
+                            AutomationId,
+                            ItemStatus
                         },
                         F64 {
                             ScrollX,
@@ -2023,7 +3157,8 @@ impl<'de> Visitor<'de> for NodeVisitor {
                             NumericValueStep,
                             NumericValueJump,
                             FontSize,
-                            FontWeight
+                            FontWeight,
+                            ReadingLevel
                         },
                         Usize {
                             TableRowCount,
@@ -2076,7 +3211,8 @@ impl<'de> Visitor<'de> for NodeVisitor {
                         Affine { Transform },
                         Rect { Bounds },
                         TextSelection { TextSelection },
-                        CustomActionVec { CustomActions }
+                        CustomActionVec { CustomActions },
+                        CustomContentVec { CustomContent }
                     });
                 }
                 DeserializeKey::Unknown(_) => {
@@ -2095,7 +3231,37 @@ impl<'de> Deserialize<'de> for Node {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_map(NodeVisitor)
+        deserializer.deserialize_map(NodeVisitor { compact: false })
+    }
+}
+
+/// A node that's serialized with [`CompactKey`]s, for the postcard format.
+#[cfg(feature = "postcard")]
+struct CompactNode<'a>(&'a Node);
+
+#[cfg(feature = "postcard")]
+impl Serialize for CompactNode<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize_with_keys(serializer, true)
+    }
+}
+
+/// A node that's deserialized from [`CompactKey`]s, for the postcard format.
+#[cfg(feature = "postcard")]
+struct OwnedCompactNode(Node);
+
+#[cfg(feature = "postcard")]
+impl<'de> Deserialize<'de> for OwnedCompactNode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_map(NodeVisitor { compact: true })
+            .map(Self)
     }
 }
 
@@ -2155,7 +3321,10 @@ impl JsonSchema for Node {
             IsSpellingError,
             IsGrammarError,
             IsSearchMatch,
-            IsSuggestion
+            IsSuggestion,
+            BoundsUnknown,
+            IsPrimaryContent,
+            Mixed
         });
         add_properties_to_schema!(gen, properties, {
             Vec<NodeId> {
@@ -2165,6 +3334,7 @@ impl JsonSchema for Node {
                 DescribedBy,
                 FlowTo,
                 LabelledBy,
+                Owns,
                 RadioGroup
             },
             NodeId {
@@ -2177,13 +3347,16 @@ impl JsonSchema for Node {
                 PopupFor,
                 TableHeader,
                 TableRowHeader,
-                TableColumnHeader
+                TableColumnHeader,
+                ReturnFocusTo,
+                Caption
             },
             Box<str> {
                 Name,
                 Description,
                 Value,
                 AccessKey,
+                AriaRole,
                 ClassName,
                 FontFamily,
                 HtmlTag,
@@ -2194,7 +3367,10 @@ impl JsonSchema for Node {
                 RoleDescription,
                 StateDescription,
                 Tooltip,
-                Url
+                Url,
+                AccessibleText,
+                AutomationId,
+                ItemStatus
             },
             f64 {
                 ScrollX,
@@ -2209,7 +3385,8 @@ impl JsonSchema for Node {
                 NumericValueStep,
                 NumericValueJump,
                 FontSize,
-                FontWeight
+                FontWeight,
+                ReadingLevel
             },
             usize {
                 TableRowCount,
@@ -2262,7 +3439,8 @@ impl JsonSchema for Node {
             Affine { Transform },
             Rect { Bounds },
             TextSelection { TextSelection },
-            Vec<CustomAction> { CustomActions }
+            Vec<CustomAction> { CustomActions },
+            Vec<CustomContent> { CustomContent }
         });
         SchemaObject {
             instance_type: Some(InstanceType::Object.into()),
@@ -2368,39 +3546,806 @@ impl<T: FnOnce() -> TreeUpdate> From<T> for TreeUpdate {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
-#[repr(C)]
-pub enum ActionData {
-    CustomAction(i32),
-    Value(Box<str>),
-    NumericValue(f64),
-    /// Optional target rectangle for [`Action::ScrollIntoView`], in
-    /// the coordinate space of the action's target node.
-    ScrollTargetRect(Rect),
-    /// Target for [`Action::ScrollToPoint`], in platform-native coordinates
-    /// relative to the origin of the tree's container (e.g. window).
-    ScrollToPoint(Point),
-    /// Target for [`Action::SetScrollOffset`], in the coordinate space
-    /// of the action's target node.
-    SetScrollOffset(Point),
-    SetTextSelection(TextSelection),
-}
+impl TreeUpdate {
+    /// Create the initial state of a tree consisting of a single root node,
+    /// which also has the focus.
+    pub fn with_root(root_id: NodeId, root: Node) -> Self {
+        Self {
+            nodes: vec![(root_id, root)],
+            tree: Some(Tree::new(root_id)),
+            focus: root_id,
+        }
+    }
 
-#[derive(Clone, Debug, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
-#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
-pub struct ActionRequest {
-    pub action: Action,
-    pub target: NodeId,
-    pub data: Option<ActionData>,
-}
+    /// Fold this update into an owned copy of the tree.
+    /// This is equivalent to [`TreeSnapshot::apply`].
+    pub fn apply_to(self, state: &mut TreeSnapshot) {
+        state.apply(self);
+    }
 
-/// Handles requests from assistive technologies or other clients.
+    /// Set [`Node::is_disabled`] on every descendant of a disabled node
+    /// in the subtree rooted at the given node.
+    ///
+    /// A disabled node implicitly disables its descendants, and AccessKit
+    /// platform adapters take that into account. This is a bridge for
+    /// other consumers of tree updates that only look at each node's own
+    /// flag. Only nodes included in this update are visited; a child that
+    /// isn't in the update is skipped along with its descendants.
+    pub fn propagate_disabled(&mut self, root: NodeId, classes: &mut NodeClassSet) {
+        let indices = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, (id, _))| (*id, index))
+            .collect::<HashMap<_, _>>();
+        let mut visited = HashSet::new();
+        let mut stack = vec![(root, false)];
+        while let Some((id, inherited)) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            let Some(&index) = indices.get(&id) else {
+                continue;
+            };
+            let node = &mut self.nodes[index].1;
+            if inherited && !node.is_disabled() {
+                let mut builder = NodeBuilder::from_node(node);
+                builder.set_disabled();
+                *node = builder.build(classes);
+            }
+            let is_disabled = node.is_disabled();
+            stack.extend(
+                node.children()
+                    .iter()
+                    .rev()
+                    .map(|child| (*child, is_disabled)),
+            );
+        }
+    }
+}
+
+#[cfg(feature = "postcard")]
+impl TreeUpdate {
+    /// Serialize this update to the compact binary format provided by
+    /// the [postcard](https://docs.rs/postcard) crate. This is much smaller
+    /// than JSON, and is intended for sending updates between processes.
+    pub fn to_postcard(&self) -> Vec<u8> {
+        #[derive(Serialize)]
+        struct CompactTreeUpdate<'a> {
+            nodes: Vec<(NodeId, CompactNode<'a>)>,
+            tree: &'a Option<Tree>,
+            focus: NodeId,
+        }
+
+        let update = CompactTreeUpdate {
+            nodes: self
+                .nodes
+                .iter()
+                .map(|(id, node)| (*id, CompactNode(node)))
+                .collect(),
+            tree: &self.tree,
+            focus: self.focus,
+        };
+        postcard::to_allocvec(&update).unwrap()
+    }
+
+    /// Deserialize an update that was serialized with
+    /// [`TreeUpdate::to_postcard`].
+    pub fn from_postcard(bytes: &[u8]) -> Result<Self, postcard::Error> {
+        #[derive(Deserialize)]
+        struct CompactTreeUpdate {
+            nodes: Vec<(NodeId, OwnedCompactNode)>,
+            tree: Option<Tree>,
+            focus: NodeId,
+        }
+
+        let update: CompactTreeUpdate = postcard::from_bytes(bytes)?;
+        Ok(Self {
+            nodes: update
+                .nodes
+                .into_iter()
+                .map(|(id, node)| (id, node.0))
+                .collect(),
+            tree: update.tree,
+            focus: update.focus,
+        })
+    }
+}
+
+/// An owned copy of a tree, for code that needs to keep its own model
+/// of the tree in sync with successive [`TreeUpdate`]s.
+///
+/// Updates are applied with the same rules that AccessKit platform adapters
+/// use: nodes in an update replace any existing node with the same ID,
+/// and a node that is removed from its parent's children is removed
+/// from the tree along with all of its descendants.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeSnapshot {
+    nodes: HashMap<NodeId, Node>,
+    /// Maps each node that is some node's child to that parent. This is
+    /// kept in sync with `nodes` by [`TreeSnapshot::apply`].
+    parents: HashMap<NodeId, NodeId>,
+    pub tree: Tree,
+    pub focus: NodeId,
+}
+
+/// The kinds of change that a [`TreeUpdate`] would make to a [`TreeSnapshot`],
+/// as returned by [`TreeSnapshot::summarize`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UpdateSummary {
+    /// The focused node would change.
+    pub focus_changed: bool,
+    /// Nodes would be added, removed or reordered, or the root would change.
+    pub structure_changed: bool,
+    /// Properties of existing nodes other than their children would change,
+    /// or the tree's metadata would change.
+    pub content_changed: bool,
+}
+
+/// The current step of a process, as returned by
+/// [`TreeSnapshot::current_step`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StepInfo {
+    /// The node representing the current step.
+    pub id: NodeId,
+    /// The one-based position of the current step.
+    pub position: usize,
+    /// The total number of steps.
+    pub count: usize,
+}
+
+/// The focus and text selection of a tree, captured with
+/// [`TreeSnapshot::focus_snapshot`] so that they can be carried over
+/// to a rebuilt tree whose node IDs are different.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FocusSnapshot {
+    /// The focused node.
+    pub focus: NodeId,
+    /// The [text selection] of the focused node, if it has one.
+    ///
+    /// [text selection]: Node::text_selection
+    pub text_selection: Option<TextSelection>,
+}
+
+impl FocusSnapshot {
+    /// Carry the focus and text selection over to an update that rebuilds
+    /// the tree, using `map` to translate each old node ID to its new ID,
+    /// or to `None` if the node no longer exists. `map` can look IDs up in
+    /// a table produced by the rebuild, or match nodes by a stable key such
+    /// as [`Node::automation_id`]. The focus is only moved if the new node
+    /// is in the update, and the text selection is only set if every node
+    /// it refers to can be mapped. Returns `true` if the focus was restored.
+    pub fn restore(
+        &self,
+        update: &mut TreeUpdate,
+        mut map: impl FnMut(NodeId) -> Option<NodeId>,
+        classes: &mut NodeClassSet,
+    ) -> bool {
+        let Some(focus) = map(self.focus) else {
+            return false;
+        };
+        let Some(index) = update.nodes.iter().position(|(id, _)| *id == focus) else {
+            return false;
+        };
+        update.focus = focus;
+        if let Some(selection) = self.text_selection {
+            let mut map_position = |position: TextPosition| {
+                map(position.node).map(|node| TextPosition {
+                    node,
+                    character_index: position.character_index,
+                })
+            };
+            if let (Some(anchor), Some(focus)) = (
+                map_position(selection.anchor),
+                map_position(selection.focus),
+            ) {
+                let node = &mut update.nodes[index].1;
+                let mut builder = NodeBuilder::from_node(node);
+                builder.set_text_selection(TextSelection { anchor, focus });
+                *node = builder.build(classes);
+            }
+        }
+        true
+    }
+}
+
+impl TreeSnapshot {
+    /// Create a tree from its initial state. Panics if the update
+    /// doesn't include [`TreeUpdate::tree`].
+    pub fn new(mut initial_state: TreeUpdate) -> Self {
+        let tree = initial_state.tree.take().unwrap();
+        let mut state = Self {
+            nodes: HashMap::new(),
+            parents: HashMap::new(),
+            tree,
+            focus: initial_state.focus,
+        };
+        state.apply(initial_state);
+        state
+    }
+
+    pub fn root_id(&self) -> NodeId {
+        self.tree.root
+    }
+
+    pub fn root(&self) -> &Node {
+        &self.nodes[&self.tree.root]
+    }
+
+    /// Returns all of the nodes in the tree, keyed by ID. The nodes can
+    /// only be changed through [`TreeSnapshot::apply`].
+    pub fn nodes(&self) -> &HashMap<NodeId, Node> {
+        &self.nodes
+    }
+
+    /// Apply the provided update. Like the platform adapters, this panics
+    /// if the update isn't valid for the current state of the tree.
+    pub fn apply(&mut self, update: TreeUpdate) {
+        let mut orphans = HashSet::new();
+
+        if let Some(tree) = update.tree {
+            if tree.root != self.tree.root {
+                orphans.insert(self.tree.root);
+            }
+            self.tree = tree;
+        }
+
+        let mut new_child_ids = HashSet::new();
+        for (_, node) in &update.nodes {
+            for child_id in node.children().iter() {
+                assert!(new_child_ids.insert(*child_id));
+            }
+        }
+
+        for (node_id, node) in update.nodes {
+            orphans.remove(&node_id);
+            for child_id in node.children().iter() {
+                orphans.remove(child_id);
+            }
+            if let Some(old_node) = self.nodes.get(&node_id) {
+                for child_id in old_node.children().iter() {
+                    if !node.children().contains(child_id) && !new_child_ids.contains(child_id) {
+                        orphans.insert(*child_id);
+                    }
+                    if self.parents.get(child_id) == Some(&node_id) {
+                        self.parents.remove(child_id);
+                    }
+                }
+            } else {
+                assert!(node_id == self.tree.root || new_child_ids.contains(&node_id));
+            }
+            for child_id in node.children().iter() {
+                self.parents.insert(*child_id, node_id);
+            }
+            self.nodes.insert(node_id, node);
+        }
+
+        fn remove_subtree(
+            nodes: &mut HashMap<NodeId, Node>,
+            parents: &mut HashMap<NodeId, NodeId>,
+            id: NodeId,
+        ) {
+            parents.remove(&id);
+            if let Some(node) = nodes.remove(&id) {
+                for child_id in node.children().iter() {
+                    remove_subtree(nodes, parents, *child_id);
+                }
+            }
+        }
+
+        for id in orphans {
+            remove_subtree(&mut self.nodes, &mut self.parents, id);
+        }
+
+        self.focus = update.focus;
+        assert!(self.nodes.contains_key(&self.tree.root));
+        assert!(self.nodes.contains_key(&self.focus));
+    }
+
+    /// Returns the node that should be focused when the given modal closes,
+    /// as recorded in its [`Node::return_focus_to`] property, if that node
+    /// is still in the tree.
+    pub fn return_focus_target(&self, modal: NodeId) -> Option<NodeId> {
+        self.nodes
+            .get(&modal)
+            .and_then(Node::return_focus_to)
+            .filter(|id| self.nodes.contains_key(id))
+    }
+
+    /// Describe the kinds of change that applying the provided update
+    /// would make, without applying it. Nodes that are included in the
+    /// update but are unchanged don't count as changes.
+    pub fn summarize(&self, update: &TreeUpdate) -> UpdateSummary {
+        let mut summary = UpdateSummary {
+            focus_changed: update.focus != self.focus,
+            ..Default::default()
+        };
+        if let Some(tree) = &update.tree {
+            if tree.root != self.tree.root {
+                summary.structure_changed = true;
+            }
+            if tree.app_name != self.tree.app_name
+                || tree.toolkit_name != self.tree.toolkit_name
+                || tree.toolkit_version != self.tree.toolkit_version
+            {
+                summary.content_changed = true;
+            }
+        }
+        for (node_id, node) in &update.nodes {
+            match self.nodes.get(node_id) {
+                Some(old_node) => {
+                    if node.children() != old_node.children() {
+                        summary.structure_changed = true;
+                    }
+                    if !node.eq_except_children(old_node) {
+                        summary.content_changed = true;
+                    }
+                }
+                None => summary.structure_changed = true,
+            }
+        }
+        summary
+    }
+
+    /// Returns `true` if applying the update would change anything that
+    /// a platform adapter exposes to assistive technologies: the focus,
+    /// the tree's structure or metadata, or an [exposed property] of
+    /// an existing node. If this returns `false`, the update doesn't need
+    /// to be passed to an adapter, though it still needs to be applied
+    /// to any [`TreeSnapshot`] that later updates will be based on.
+    ///
+    /// [exposed property]: Node::has_exposed_changes
+    pub fn has_exposed_changes(&self, update: &TreeUpdate) -> bool {
+        if update.focus != self.focus {
+            return true;
+        }
+        if let Some(tree) = &update.tree {
+            if *tree != self.tree {
+                return true;
+            }
+        }
+        update
+            .nodes
+            .iter()
+            .any(|(id, node)| match self.nodes.get(id) {
+                Some(old_node) => node.has_exposed_changes(old_node),
+                None => true,
+            })
+    }
+
+    /// Returns the smallest update that turns this tree into `new`: the
+    /// nodes that were added or changed, the tree metadata if it changed,
+    /// and the new focus. Nodes that are no longer in `new` are removed by
+    /// the changes to their former parents. Applying the result to this
+    /// tree with [`TreeSnapshot::apply`] gives a tree equal to `new`.
+    pub fn diff_update(&self, new: &TreeSnapshot) -> TreeUpdate {
+        let mut nodes = new
+            .nodes
+            .iter()
+            .filter(|(id, node)| self.nodes.get(id) != Some(node))
+            .map(|(id, node)| (*id, node.clone()))
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|(id, _)| id.0);
+        TreeUpdate {
+            nodes,
+            tree: (new.tree != self.tree).then(|| new.tree.clone()),
+            focus: new.focus,
+        }
+    }
+
+    /// Capture the focus and the focused node's text selection, so they
+    /// can be [restored] after the tree is rebuilt with different IDs.
+    ///
+    /// [restored]: FocusSnapshot::restore
+    pub fn focus_snapshot(&self) -> FocusSnapshot {
+        FocusSnapshot {
+            focus: self.focus,
+            text_selection: self
+                .nodes
+                .get(&self.focus)
+                .and_then(|node| node.text_selection())
+                .copied(),
+        }
+    }
+
+    /// Set [`Node::position_in_set`] and [`Node::size_of_set`] on each child
+    /// of the given container that isn't hidden, based on the children's
+    /// order and count. Hidden children are skipped, and their positions
+    /// are cleared. Returns an update containing the children that changed,
+    /// so it can also be passed to a platform adapter. Panics if the
+    /// container isn't in the tree.
+    pub fn fill_set_positions(
+        &mut self,
+        container: NodeId,
+        classes: &mut NodeClassSet,
+    ) -> TreeUpdate {
+        let child_ids = self.nodes[&container].children().to_vec();
+        let items = child_ids
+            .iter()
+            .filter(|id| self.nodes.get(id).map_or(false, |node| !node.is_hidden()))
+            .copied()
+            .collect::<Vec<_>>();
+        let mut nodes = Vec::new();
+        for id in child_ids {
+            let Some(old_node) = self.nodes.get(&id) else {
+                continue;
+            };
+            let mut builder = NodeBuilder::from_node(old_node);
+            if let Some(index) = items.iter().position(|item| *item == id) {
+                builder.set_position_in_set(index + 1);
+                builder.set_size_of_set(items.len());
+            } else {
+                builder.clear_position_in_set();
+                builder.clear_size_of_set();
+            }
+            let node = builder.build(classes);
+            if node != *old_node {
+                self.nodes.insert(id, node.clone());
+                nodes.push((id, node));
+            }
+        }
+        TreeUpdate {
+            nodes,
+            tree: None,
+            focus: self.focus,
+        }
+    }
+
+    /// Returns the step of the given process container that is marked with
+    /// [`AriaCurrent::Step`], along with its position and the number of
+    /// steps, following the convention described there. Returns `None` if
+    /// no child is marked as the current step, or if the marked child
+    /// doesn't have both [`Node::position_in_set`] and
+    /// [`Node::size_of_set`].
+    pub fn current_step(&self, container: NodeId) -> Option<StepInfo> {
+        let container = self.nodes.get(&container)?;
+        container.children().iter().find_map(|id| {
+            let node = self.nodes.get(id)?;
+            if node.aria_current() != Some(AriaCurrent::Step) {
+                return None;
+            }
+            Some(StepInfo {
+                id: *id,
+                position: node.position_in_set()?,
+                count: node.size_of_set()?,
+            })
+        })
+    }
+
+    /// Returns the bounding box of the node in the coordinate space of the
+    /// tree's container, combining its [`Node::bounds`] with the
+    /// [`Node::transform`] of the node and each of its ancestors. Returns
+    /// `None` if the node isn't in the tree or its bounds are missing or
+    /// unknown.
+    pub fn absolute_bounds(&self, id: NodeId) -> Option<Rect> {
+        let node = self.nodes.get(&id)?;
+        if node.is_bounds_unknown() {
+            return None;
+        }
+        let bounds = node.bounds()?;
+        Some(self.absolute_transform(id).transform_rect_bbox(bounds))
+    }
+
+    /// Returns the bounding box of the characters from `start` up to but
+    /// not including `end` within an inline text node, in the same
+    /// coordinate space as [`TreeSnapshot::absolute_bounds`]. The range is
+    /// resolved as in [`Node::character_range_bounds`]. Returns `None` if
+    /// the node has no absolute bounds, or lacks
+    /// [`Node::character_positions`], [`Node::character_widths`] or
+    /// [`Node::text_direction`].
+    pub fn range_bounds(&self, id: NodeId, start: usize, end: usize) -> Option<Rect> {
+        let node = self.nodes.get(&id)?;
+        if node.is_bounds_unknown() {
+            return None;
+        }
+        let rect = node.character_range_bounds(start, end)?;
+        Some(self.absolute_transform(id).transform_rect_bbox(rect))
+    }
+
+    /// Combines the [`Node::transform`] of the node and each of its
+    /// ancestors.
+    fn absolute_transform(&self, id: NodeId) -> Affine {
+        let mut transform = Affine::IDENTITY;
+        let mut visited = HashSet::new();
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if !visited.insert(id) {
+                break;
+            }
+            if let Some(node_transform) = self.nodes.get(&id).and_then(Node::transform) {
+                transform = *node_transform * transform;
+            }
+            current = self.parents.get(&id).copied();
+        }
+        transform
+    }
+
+    /// Returns the ID of the node that follows the given node in its
+    /// parent's children, or `None` if it's the last child, the root,
+    /// or not in the tree.
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let (siblings, index) = self.siblings(id)?;
+        siblings.get(index + 1).copied()
+    }
+
+    /// Returns the ID of the node that precedes the given node in its
+    /// parent's children, or `None` if it's the first child, the root,
+    /// or not in the tree.
+    pub fn previous_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let (siblings, index) = self.siblings(id)?;
+        siblings.get(index.checked_sub(1)?).copied()
+    }
+
+    /// Returns the children of the given node's parent, along with the
+    /// node's index among them.
+    fn siblings(&self, id: NodeId) -> Option<(&[NodeId], usize)> {
+        let parent = *self.parents.get(&id)?;
+        let siblings = self.nodes.get(&parent)?.children();
+        let index = siblings.iter().position(|sibling| *sibling == id)?;
+        Some((siblings, index))
+    }
+
+    /// Returns the ID of the closest ancestor of the given node, not
+    /// counting the node itself, whose role matches the predicate, such as
+    /// the table containing a cell. Returns `None` if no ancestor matches
+    /// or the node isn't in the tree. Each ancestor is visited at most
+    /// once, even if the tree contains a cycle.
+    pub fn nearest_ancestor(
+        &self,
+        id: NodeId,
+        mut predicate: impl FnMut(Role) -> bool,
+    ) -> Option<NodeId> {
+        let mut visited = HashSet::from([id]);
+        let mut current = self.parents.get(&id).copied();
+        while let Some(id) = current {
+            if !visited.insert(id) {
+                break;
+            }
+            if predicate(self.nodes.get(&id)?.role()) {
+                return Some(id);
+            }
+            current = self.parents.get(&id).copied();
+        }
+        None
+    }
+
+    /// Returns `true` if the node's [absolute bounds] lie entirely outside
+    /// the given viewport, which is in the coordinate space of the tree's
+    /// container. A node that only touches the viewport's edge counts as
+    /// offscreen. Returns `false` if the node has no absolute bounds, since
+    /// its position can't be determined.
+    ///
+    /// [absolute bounds]: TreeSnapshot::absolute_bounds
+    pub fn is_offscreen(&self, id: NodeId, viewport: Rect) -> bool {
+        let Some(bounds) = self.absolute_bounds(id) else {
+            return false;
+        };
+        bounds.x1 <= viewport.x0
+            || bounds.x0 >= viewport.x1
+            || bounds.y1 <= viewport.y0
+            || bounds.y0 >= viewport.y1
+    }
+
+    /// If the focused node isn't fully visible within its closest scroll
+    /// container, returns that container and the area its viewport would
+    /// need to show to bring the node into view, in the same coordinate
+    /// space as [absolute bounds]. The scroll delta is the offset from the
+    /// container's current viewport to that area. A scroll container is an
+    /// ancestor with [`Role::ScrollView`] or a maximum scroll offset. Its
+    /// viewport is its absolute bounds, and the focused node's absolute
+    /// bounds are assumed to already reflect the current scroll offsets.
+    /// If the node is larger than the viewport, its top-left corner is
+    /// brought into view. The delta is clamped to the container's scroll
+    /// range where one is set. Returns `None` if the node is fully visible,
+    /// isn't in a scroll container, or either of them has no bounds.
+    ///
+    /// [absolute bounds]: TreeSnapshot::absolute_bounds
+    pub fn is_focus_offscreen_in_scroller(&self) -> Option<(NodeId, Rect)> {
+        let mut visited = HashSet::from([self.focus]);
+        let mut current = self.parents.get(&self.focus).copied();
+        let scroller = loop {
+            let id = current?;
+            if !visited.insert(id) {
+                return None;
+            }
+            let node = self.nodes.get(&id)?;
+            if node.role() == Role::ScrollView
+                || node.scroll_x_max().is_some()
+                || node.scroll_y_max().is_some()
+            {
+                break id;
+            }
+            current = self.parents.get(&id).copied();
+        };
+        let viewport = self.absolute_bounds(scroller)?;
+        let bounds = self.absolute_bounds(self.focus)?;
+        let axis_delta = |start: f64, end: f64, view_start: f64, view_end: f64| {
+            if start < view_start {
+                start - view_start
+            } else if end > view_end {
+                (end - view_end).min(start - view_start)
+            } else {
+                0.0
+            }
+        };
+        let clamp = |delta: f64, offset: Option<f64>, min: Option<f64>, max: Option<f64>| {
+            let Some(offset) = offset else {
+                return delta;
+            };
+            let target = (offset + delta)
+                .max(min.unwrap_or(f64::NEG_INFINITY))
+                .min(max.unwrap_or(f64::INFINITY));
+            target - offset
+        };
+        let node = &self.nodes[&scroller];
+        let delta = Vec2::new(
+            clamp(
+                axis_delta(bounds.x0, bounds.x1, viewport.x0, viewport.x1),
+                node.scroll_x(),
+                node.scroll_x_min(),
+                node.scroll_x_max(),
+            ),
+            clamp(
+                axis_delta(bounds.y0, bounds.y1, viewport.y0, viewport.y1),
+                node.scroll_y(),
+                node.scroll_y_min(),
+                node.scroll_y_max(),
+            ),
+        );
+        (delta != Vec2::ZERO).then_some((scroller, viewport + delta))
+    }
+
+    /// Returns the ID of the first node, in depth-first order starting
+    /// at the root, whose [`Node::automation_id`] matches the given string.
+    pub fn find_by_automation_id(&self, automation_id: &str) -> Option<NodeId> {
+        self.find(|_, node| node.automation_id() == Some(automation_id))
+    }
+
+    /// Returns the ID of the first node, in depth-first order starting
+    /// at the root, for which the predicate returns `true`. Each node is
+    /// visited at most once, even if the tree contains a cycle.
+    pub fn find(&self, mut predicate: impl FnMut(NodeId, &Node) -> bool) -> Option<NodeId> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![self.tree.root];
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            let Some(node) = self.nodes.get(&id) else {
+                continue;
+            };
+            if predicate(id, node) {
+                return Some(id);
+            }
+            stack.extend(node.children().iter().rev());
+        }
+        None
+    }
+
+    /// Returns the first and last focusable descendants of the given
+    /// container, in depth-first order, which are the boundaries that
+    /// keyboard focus should wrap between when it's trapped in a modal
+    /// dialog or menu. A node is focusable if it supports [`Action::Focus`]
+    /// and neither it nor any of its ancestors is disabled or hidden.
+    /// The container itself isn't included. Returns `None` if the container
+    /// doesn't exist or has no focusable descendants. If there's only one,
+    /// it's both the first and the last.
+    pub fn focus_trap_bounds(&self, container: NodeId) -> Option<(NodeId, NodeId)> {
+        let mut visited = HashSet::new();
+        let mut current = Some(container);
+        while let Some(id) = current {
+            if !visited.insert(id) {
+                break;
+            }
+            let node = self.nodes.get(&id)?;
+            if node.is_hidden() || node.is_disabled() {
+                return None;
+            }
+            current = self.parents.get(&id).copied();
+        }
+
+        let mut bounds = None;
+        let mut visited = HashSet::new();
+        let mut stack = self
+            .nodes
+            .get(&container)?
+            .children()
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Vec<_>>();
+        visited.insert(container);
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            let Some(node) = self.nodes.get(&id) else {
+                continue;
+            };
+            if node.is_hidden() || node.is_disabled() {
+                continue;
+            }
+            if node.supports_action(Action::Focus) {
+                bounds = match bounds {
+                    None => Some((id, id)),
+                    Some((first, _)) => Some((first, id)),
+                };
+            }
+            stack.extend(node.children().iter().rev());
+        }
+        bounds
+    }
+
+    /// Returns the sequence of regions that should be read, starting with
+    /// the given node and following its [`Node::flow_to`] chain, for layouts
+    /// such as multi-column articles where the intended reading order
+    /// differs from tree order. Where a node flows to several others, the
+    /// first one that's in the tree is followed. The chain stops at the first
+    /// node that has already been visited, so cyclic flows terminate.
+    /// Returns an empty vector if the start node doesn't exist.
+    pub fn reading_order(&self, start: NodeId) -> Vec<NodeId> {
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        let mut current = self.nodes.get(&start).map(|node| (start, node));
+        while let Some((id, node)) = current {
+            if !visited.insert(id) {
+                break;
+            }
+            order.push(id);
+            current = node
+                .flow_to()
+                .iter()
+                .find_map(|id| self.nodes.get(id).map(|node| (*id, node)));
+        }
+        order
+    }
+}
+
+#[cfg(feature = "postcard")]
+impl TreeSnapshot {
+    /// Serialize the [minimal update] from this tree to `new` with
+    /// [`TreeUpdate::to_postcard`], for sending only what changed
+    /// to another process that has a copy of this tree.
+    ///
+    /// [minimal update]: TreeSnapshot::diff_update
+    pub fn serialize_delta(&self, new: &TreeSnapshot) -> Vec<u8> {
+        self.diff_update(new).to_postcard()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[repr(C)]
+pub enum ActionData {
+    CustomAction(i32),
+    Value(Box<str>),
+    NumericValue(f64),
+    /// Optional target rectangle for [`Action::ScrollIntoView`], in
+    /// the coordinate space of the action's target node.
+    ScrollTargetRect(Rect),
+    /// Target for [`Action::ScrollToPoint`], in platform-native coordinates
+    /// relative to the origin of the tree's container (e.g. window).
+    ScrollToPoint(Point),
+    /// Target for [`Action::SetScrollOffset`], in the coordinate space
+    /// of the action's target node.
+    SetScrollOffset(Point),
+    SetTextSelection(TextSelection),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ActionRequest {
+    pub action: Action,
+    pub target: NodeId,
+    pub data: Option<ActionData>,
+}
+
+/// Handles requests from assistive technologies or other clients.
 pub trait ActionHandler {
     /// Perform the requested action. If the requested action is not supported,
     /// this method must do nothing.
@@ -2413,3 +4358,1423 @@ pub trait ActionHandler {
     /// the request to another thread.
     fn do_action(&mut self, request: ActionRequest);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_id_from_path() {
+        let save = NodeId::from_path("window/toolbar/save");
+        assert_eq!(save, NodeId::from_path("window/toolbar/save"));
+        assert_ne!(save, NodeId::from_path("window/toolbar/open"));
+        assert_ne!(save, NodeId::from_path("window/toolbar"));
+        assert_eq!(NodeId::from_path(""), NodeId(0xcbf2_9ce4_8422_2325));
+        assert_eq!(NodeId::from_path("a"), NodeId(0xaf63_dc4c_8601_ec8c));
+    }
+
+    #[test]
+    fn date_time_range_round_trip() {
+        let mut classes = NodeClassSet::new();
+        // 2024-01-01T00:00:00Z and 2024-12-31T00:00:00Z.
+        let min = UNIX_EPOCH + Duration::from_secs(1_704_067_200);
+        let max = UNIX_EPOCH + Duration::from_secs(1_735_603_200);
+        let mut builder = NodeBuilder::new(Role::DateInput);
+        builder.set_date_time_range(min, max);
+        let node = builder.build(&mut classes);
+        assert_eq!(node.min_numeric_value(), Some(1_704_067_200_000.0));
+        assert_eq!(node.max_numeric_value(), Some(1_735_603_200_000.0));
+        assert_eq!(node.date_time_range(), Some((min, max)));
+
+        let before_epoch = UNIX_EPOCH - Duration::from_secs(86_400);
+        let mut builder = NodeBuilder::new(Role::DateInput);
+        builder.set_date_time_range(before_epoch, min);
+        let node = builder.build(&mut classes);
+        assert_eq!(node.min_numeric_value(), Some(-86_400_000.0));
+        assert_eq!(node.date_time_range(), Some((before_epoch, min)));
+
+        let mut builder = NodeBuilder::new(Role::TimeInput);
+        builder.set_time_of_day_range(
+            Duration::from_secs(9 * 3600),
+            Duration::from_secs(17 * 3600),
+        );
+        let node = builder.build(&mut classes);
+        assert_eq!(node.max_numeric_value(), Some(61_200_000.0));
+        assert_eq!(
+            node.time_of_day_range(),
+            Some((
+                Duration::from_secs(9 * 3600),
+                Duration::from_secs(17 * 3600)
+            ))
+        );
+        assert_eq!(
+            NodeBuilder::new(Role::DateInput)
+                .build(&mut classes)
+                .date_time_range(),
+            None
+        );
+    }
+
+    #[test]
+    fn build_many_matches_build() {
+        let builders = (0..10)
+            .map(|i| {
+                let mut builder = NodeBuilder::new(if i % 3 == 0 {
+                    Role::Button
+                } else {
+                    Role::StaticText
+                });
+                builder.set_name(format!("node {}", i));
+                if i % 4 == 0 {
+                    builder.set_hidden();
+                }
+                if i > 5 {
+                    builder.add_action(Action::Focus);
+                }
+                builder
+            })
+            .collect::<Vec<_>>();
+        let mut classes = NodeClassSet::new();
+        let expected = builders
+            .iter()
+            .cloned()
+            .map(|builder| builder.build(&mut classes))
+            .collect::<Vec<_>>();
+        let mut bulk_classes = NodeClassSet::new();
+        let nodes = bulk_classes.build_many(builders);
+        assert_eq!(expected, nodes);
+        assert_eq!(classes.0.len(), bulk_classes.0.len());
+        for (a, b) in nodes.iter().zip(nodes.iter().skip(1)) {
+            if a.role() == b.role()
+                && a.supports_action(Action::Focus) == b.supports_action(Action::Focus)
+            {
+                assert!(Arc::ptr_eq(&a.class, &b.class));
+            }
+        }
+    }
+
+    fn node(classes: &mut NodeClassSet, role: Role, children: &[NodeId]) -> Node {
+        let mut builder = NodeBuilder::new(role);
+        builder.set_children(children);
+        builder.build(classes)
+    }
+
+    fn initial_snapshot(classes: &mut NodeClassSet) -> TreeSnapshot {
+        TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(0),
+                    node(classes, Role::Window, &[NodeId(1), NodeId(3)]),
+                ),
+                (NodeId(1), node(classes, Role::Group, &[NodeId(2)])),
+                (NodeId(2), node(classes, Role::Button, &[])),
+                (NodeId(3), node(classes, Role::Button, &[])),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(2),
+        })
+    }
+
+    #[test]
+    fn tree_snapshot_replaces_nodes() {
+        let mut classes = NodeClassSet::new();
+        let mut state = initial_snapshot(&mut classes);
+        let mut builder = NodeBuilder::new(Role::CheckBox);
+        builder.set_name("replaced");
+        let replacement = builder.build(&mut classes);
+        TreeUpdate {
+            nodes: vec![(NodeId(3), replacement.clone())],
+            tree: None,
+            focus: NodeId(2),
+        }
+        .apply_to(&mut state);
+        assert_eq!(state.nodes.len(), 4);
+        assert_eq!(state.nodes[&NodeId(3)], replacement);
+    }
+
+    #[test]
+    fn tree_snapshot_removes_descendants_of_removed_child() {
+        let mut classes = NodeClassSet::new();
+        let mut state = initial_snapshot(&mut classes);
+        state.apply(TreeUpdate {
+            nodes: vec![(NodeId(0), node(&mut classes, Role::Window, &[NodeId(3)]))],
+            tree: None,
+            focus: NodeId(3),
+        });
+        let mut ids = state.nodes.keys().copied().collect::<Vec<_>>();
+        ids.sort_by_key(|id| id.0);
+        assert_eq!(ids, vec![NodeId(0), NodeId(3)]);
+    }
+
+    #[test]
+    fn tree_snapshot_keeps_moved_child() {
+        let mut classes = NodeClassSet::new();
+        let mut state = initial_snapshot(&mut classes);
+        state.apply(TreeUpdate {
+            nodes: vec![
+                (NodeId(1), node(&mut classes, Role::Group, &[])),
+                (NodeId(3), node(&mut classes, Role::Group, &[NodeId(2)])),
+            ],
+            tree: None,
+            focus: NodeId(2),
+        });
+        assert_eq!(state.nodes.len(), 4);
+        assert_eq!(state.nodes[&NodeId(3)].children(), &[NodeId(2)]);
+        let parent = |state: &TreeSnapshot| state.nearest_ancestor(NodeId(2), |_| true);
+        assert_eq!(parent(&state), Some(NodeId(3)));
+
+        // Move it back, this time listing the new parent first.
+        state.apply(TreeUpdate {
+            nodes: vec![
+                (NodeId(1), node(&mut classes, Role::Group, &[NodeId(2)])),
+                (NodeId(3), node(&mut classes, Role::Group, &[])),
+            ],
+            tree: None,
+            focus: NodeId(2),
+        });
+        assert_eq!(parent(&state), Some(NodeId(1)));
+    }
+
+    #[test]
+    fn tree_snapshot_updates_focus() {
+        let mut classes = NodeClassSet::new();
+        let mut state = initial_snapshot(&mut classes);
+        assert_eq!(state.focus, NodeId(2));
+        state.apply(TreeUpdate {
+            nodes: vec![],
+            tree: None,
+            focus: NodeId(3),
+        });
+        assert_eq!(state.focus, NodeId(3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn tree_snapshot_rejects_focus_on_removed_node() {
+        let mut classes = NodeClassSet::new();
+        let mut state = initial_snapshot(&mut classes);
+        state.apply(TreeUpdate {
+            nodes: vec![(NodeId(0), node(&mut classes, Role::Window, &[NodeId(3)]))],
+            tree: None,
+            focus: NodeId(2),
+        });
+    }
+
+    #[test]
+    fn node_builder_merge() {
+        let mut base = NodeBuilder::new(Role::Button);
+        base.set_name("OK");
+        base.set_description("Confirm");
+        base.set_bounds(Rect::new(0.0, 0.0, 10.0, 10.0));
+        base.set_children(vec![NodeId(1)]);
+        base.set_disabled();
+        base.add_action(Action::Focus);
+
+        let mut overlay = NodeBuilder::new(Role::StaticText);
+        overlay.set_name("Apply");
+        overlay.set_numeric_value(0.5);
+        overlay.set_description("unused");
+        overlay.clear_description();
+        overlay.set_bold();
+        overlay.add_action(Action::Default);
+
+        base.merge(&overlay);
+        let node = base.build(&mut NodeClassSet::new());
+        assert_eq!(node.role(), Role::Button);
+        assert_eq!(node.name(), Some("Apply"));
+        assert_eq!(node.numeric_value(), Some(0.5));
+        assert_eq!(node.description(), Some("Confirm"));
+        assert_eq!(node.bounds(), Some(Rect::new(0.0, 0.0, 10.0, 10.0)));
+        assert_eq!(node.children(), &[NodeId(1)]);
+        assert!(node.is_disabled());
+        assert!(node.is_bold());
+        assert!(node.supports_action(Action::Focus));
+        assert!(node.supports_action(Action::Default));
+    }
+
+    #[test]
+    fn affine_is_invertible() {
+        assert!(Affine::IDENTITY.is_invertible());
+        assert!(Affine::new([2.0, 0.0, 0.5, 3.0, 10.0, -4.0]).is_invertible());
+        assert!(!Affine::scale(0.0).is_invertible());
+        assert!(!Affine::new([1.0, 2.0, 2.0, 4.0, 0.0, 0.0]).is_invertible());
+        assert!(!Affine::new([1.0, 0.0, 0.0, f64::NAN, 0.0, 0.0]).is_invertible());
+    }
+
+    #[test]
+    fn tree_update_with_root() {
+        let mut classes = NodeClassSet::new();
+        let root = node(&mut classes, Role::Window, &[]);
+        let state = TreeSnapshot::new(TreeUpdate::with_root(NodeId(1), root.clone()));
+        assert_eq!(state.root_id(), NodeId(1));
+        assert_eq!(state.root(), &root);
+        assert_eq!(state.focus, NodeId(1));
+    }
+
+    #[test]
+    fn progress_round_trip() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::ProgressIndicator);
+        builder.set_progress(450.0, 1000.0, "450 of 1000 MB");
+        let node = builder.build(&mut classes);
+        assert_eq!(node.numeric_value(), Some(450.0));
+        assert_eq!(node.min_numeric_value(), Some(0.0));
+        assert_eq!(node.max_numeric_value(), Some(1000.0));
+        assert_eq!(node.value(), Some("450 of 1000 MB"));
+    }
+
+    #[test]
+    fn text_decorations_round_trip() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::InlineTextBox);
+        builder.set_text_decorations(
+            Some(TextDecoration::Solid),
+            Some(TextDecoration::Double),
+            Some(TextDecoration::Wavy),
+        );
+        let decorations = TextDecorations {
+            underline: Some(TextDecoration::Solid),
+            overline: Some(TextDecoration::Double),
+            strikethrough: Some(TextDecoration::Wavy),
+        };
+        let node = builder.build(&mut classes);
+        assert_eq!(node.underline(), Some(TextDecoration::Solid));
+        assert_eq!(node.overline(), Some(TextDecoration::Double));
+        assert_eq!(node.strikethrough(), Some(TextDecoration::Wavy));
+        assert_eq!(node.text_decorations(), decorations);
+
+        let mut builder = NodeBuilder::from_node(&node);
+        builder.set_text_decorations(None, None, Some(TextDecoration::Solid));
+        assert_eq!(
+            builder.build(&mut classes).text_decorations(),
+            TextDecorations {
+                strikethrough: Some(TextDecoration::Solid),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn set_custom_role() {
+        let mut builder = NodeBuilder::new(Role::Group);
+        builder.set_custom_role("feed", "news feed");
+        let node = builder.build(&mut NodeClassSet::new());
+        assert_eq!(node.role(), Role::Group);
+        assert_eq!(node.aria_role(), Some("feed"));
+        assert_eq!(node.role_description(), Some("news feed"));
+    }
+
+    #[test]
+    fn tree_snapshot_find() {
+        let mut classes = NodeClassSet::new();
+        let mut state = initial_snapshot(&mut classes);
+        let mut builder = NodeBuilder::new(Role::Button);
+        builder.set_name("OK");
+        let ok_button = builder.build(&mut classes);
+        state.apply(TreeUpdate {
+            nodes: vec![(NodeId(3), ok_button)],
+            tree: None,
+            focus: NodeId(2),
+        });
+        let find_button = |name: &str| {
+            state.find(|_, node| node.role() == Role::Button && node.name() == Some(name))
+        };
+        assert_eq!(find_button("OK"), Some(NodeId(3)));
+        assert_eq!(find_button("Cancel"), None);
+        assert_eq!(
+            state.find(|_, node| node.role() == Role::Button),
+            Some(NodeId(2))
+        );
+    }
+
+    #[test]
+    fn tree_snapshot_siblings() {
+        let mut classes = NodeClassSet::new();
+        let state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(0),
+                    node(&mut classes, Role::Menu, &[NodeId(1), NodeId(2), NodeId(3)]),
+                ),
+                (NodeId(1), node(&mut classes, Role::MenuItem, &[])),
+                (NodeId(2), node(&mut classes, Role::MenuItem, &[])),
+                (NodeId(3), node(&mut classes, Role::MenuItem, &[])),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(2),
+        });
+        assert_eq!(state.next_sibling(NodeId(2)), Some(NodeId(3)));
+        assert_eq!(state.previous_sibling(NodeId(2)), Some(NodeId(1)));
+        assert_eq!(state.next_sibling(NodeId(1)), Some(NodeId(2)));
+        assert_eq!(state.previous_sibling(NodeId(1)), None);
+        assert_eq!(state.next_sibling(NodeId(3)), None);
+        assert_eq!(state.previous_sibling(NodeId(3)), Some(NodeId(2)));
+        assert_eq!(state.next_sibling(NodeId(0)), None);
+        assert_eq!(state.previous_sibling(NodeId(100)), None);
+    }
+
+    #[test]
+    fn return_focus_to() {
+        let mut classes = NodeClassSet::new();
+        let mut state = initial_snapshot(&mut classes);
+        let mut builder = NodeBuilder::new(Role::Dialog);
+        builder.set_modal();
+        builder.set_return_focus_to(NodeId(3));
+        let dialog = builder.build(&mut classes);
+        assert_eq!(dialog.return_focus_to(), Some(NodeId(3)));
+        state.apply(TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(0),
+                    node(
+                        &mut classes,
+                        Role::Window,
+                        &[NodeId(1), NodeId(3), NodeId(4)],
+                    ),
+                ),
+                (NodeId(4), dialog),
+            ],
+            tree: None,
+            focus: NodeId(4),
+        });
+        assert_eq!(state.return_focus_target(NodeId(4)), Some(NodeId(3)));
+        assert_eq!(state.return_focus_target(NodeId(1)), None);
+        state.apply(TreeUpdate {
+            nodes: vec![(
+                NodeId(0),
+                node(&mut classes, Role::Window, &[NodeId(1), NodeId(4)]),
+            )],
+            tree: None,
+            focus: NodeId(4),
+        });
+        assert_eq!(state.return_focus_target(NodeId(4)), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        type Case = (Role, fn(&mut NodeBuilder), &'static [&'static str]);
+        let cases: &[Case] = &[
+            (
+                Role::TreeItem,
+                |builder| builder.set_mixed(),
+                &["\"mixed\":true"],
+            ),
+            (
+                Role::Table,
+                |builder| builder.set_caption(NodeId(2)),
+                &["\"caption\":2"],
+            ),
+            (
+                Role::Button,
+                |builder| builder.set_automation_id("ok-button"),
+                &["\"automationId\":\"ok-button\""],
+            ),
+            (
+                Role::ScrollView,
+                |builder| builder.set_is_primary_content(),
+                &["\"isPrimaryContent\":true"],
+            ),
+            (
+                Role::Article,
+                |builder| builder.set_reading_level(4.5),
+                &["\"readingLevel\":4.5"],
+            ),
+            (
+                Role::Dialog,
+                |builder| builder.set_return_focus_to(NodeId(7)),
+                &["\"returnFocusTo\":7"],
+            ),
+            (
+                Role::ListItem,
+                |builder| {
+                    builder.set_aria_current(AriaCurrent::Step);
+                    builder.set_position_in_set(2);
+                    builder.set_size_of_set(5);
+                },
+                &[
+                    "\"ariaCurrent\":\"step\"",
+                    "\"positionInSet\":2",
+                    "\"sizeOfSet\":5",
+                ],
+            ),
+            // The application is busy while the root node is.
+            (
+                Role::Window,
+                |builder| builder.set_busy(),
+                &["\"busy\":true"],
+            ),
+        ];
+        for (role, set, fragments) in cases {
+            let mut builder = NodeBuilder::new(*role);
+            set(&mut builder);
+            let node = builder.build(&mut NodeClassSet::new());
+            let json = serde_json::to_string(&node).unwrap();
+            for fragment in *fragments {
+                assert!(json.contains(fragment), "{} lacks {}", json, fragment);
+            }
+            let deserialized: Node = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn primary_content_flag() {
+        let mut builder = NodeBuilder::new(Role::ScrollView);
+        assert!(!builder.is_primary_content());
+        builder.set_is_primary_content();
+        let node = builder.build(&mut NodeClassSet::new());
+        assert!(node.is_primary_content());
+        assert!(!node.is_hidden());
+    }
+
+    #[test]
+    fn mixed_flag() {
+        let mut builder = NodeBuilder::new(Role::TreeItem);
+        assert!(!builder.is_mixed());
+        builder.set_mixed();
+        let node = builder.build(&mut NodeClassSet::new());
+        assert!(node.is_mixed());
+        assert_eq!(node.checked(), None);
+    }
+
+    #[test]
+    fn reading_level_property() {
+        let mut builder = NodeBuilder::new(Role::Article);
+        assert_eq!(builder.reading_level(), None);
+        builder.set_reading_level(4.5);
+        let node = builder.build(&mut NodeClassSet::new());
+        assert_eq!(node.reading_level(), Some(4.5));
+        let mut builder = NodeBuilder::from_node(&node);
+        builder.clear_reading_level();
+        assert_eq!(builder.reading_level(), None);
+    }
+
+    #[test]
+    fn current_step() {
+        let mut classes = NodeClassSet::new();
+        let step = |classes: &mut NodeClassSet, current: bool| {
+            let mut builder = NodeBuilder::new(Role::ListItem);
+            if current {
+                builder.set_aria_current(AriaCurrent::Step);
+            }
+            builder.build(classes)
+        };
+        let mut state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(0),
+                    node(&mut classes, Role::List, &[NodeId(1), NodeId(2), NodeId(3)]),
+                ),
+                (NodeId(1), step(&mut classes, false)),
+                (NodeId(2), step(&mut classes, true)),
+                (NodeId(3), step(&mut classes, false)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        });
+        assert_eq!(state.current_step(NodeId(0)), None);
+        state.fill_set_positions(NodeId(0), &mut classes);
+        assert_eq!(
+            state.current_step(NodeId(0)),
+            Some(StepInfo {
+                id: NodeId(2),
+                position: 2,
+                count: 3,
+            })
+        );
+        assert_eq!(state.current_step(NodeId(2)), None);
+    }
+
+    #[test]
+    fn offscreen_nodes() {
+        let mut classes = NodeClassSet::new();
+        let bounded = |classes: &mut NodeClassSet, x: f64, y: f64| {
+            let mut builder = NodeBuilder::new(Role::Button);
+            builder.set_bounds(Rect::new(x, y, x + 20.0, y + 20.0));
+            builder.build(classes)
+        };
+        let mut list = NodeBuilder::new(Role::List);
+        list.set_children(vec![NodeId(2), NodeId(3), NodeId(4), NodeId(5)]);
+        list.set_transform(Affine::translate(Vec2::new(0.0, -100.0)));
+        let state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (NodeId(0), node(&mut classes, Role::Window, &[NodeId(1)])),
+                (NodeId(1), list.build(&mut classes)),
+                (NodeId(2), bounded(&mut classes, 10.0, 110.0)),
+                (NodeId(3), bounded(&mut classes, 10.0, 90.0)),
+                (NodeId(4), bounded(&mut classes, 10.0, 300.0)),
+                (
+                    NodeId(5),
+                    NodeBuilder::new(Role::Button).build(&mut classes),
+                ),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        });
+        let viewport = Rect::new(0.0, 0.0, 100.0, 100.0);
+        assert_eq!(
+            state.absolute_bounds(NodeId(2)),
+            Some(Rect::new(10.0, 10.0, 30.0, 30.0))
+        );
+        assert!(!state.is_offscreen(NodeId(2), viewport));
+        assert!(!state.is_offscreen(NodeId(3), viewport));
+        assert!(state.is_offscreen(NodeId(4), viewport));
+        assert!(!state.is_offscreen(NodeId(5), viewport));
+        assert!(!state.is_offscreen(NodeId(6), viewport));
+    }
+
+    #[test]
+    fn text_range_bounds() {
+        let mut classes = NodeClassSet::new();
+        let mut paragraph = NodeBuilder::new(Role::Paragraph);
+        paragraph.set_children(vec![NodeId(2)]);
+        paragraph.set_transform(Affine::translate(Vec2::new(100.0, 50.0)));
+        let mut text = NodeBuilder::new(Role::InlineTextBox);
+        text.set_bounds(Rect::new(10.0, 0.0, 40.0, 20.0));
+        text.set_value("abc");
+        text.set_character_lengths([1, 1, 1]);
+        text.set_character_positions([0.0, 10.0, 20.0]);
+        text.set_character_widths([10.0, 10.0, 10.0]);
+        text.set_text_direction(TextDirection::LeftToRight);
+        let state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (NodeId(0), node(&mut classes, Role::Window, &[NodeId(1)])),
+                (NodeId(1), paragraph.build(&mut classes)),
+                (NodeId(2), text.build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        });
+        assert_eq!(
+            state.range_bounds(NodeId(2), 1, 2),
+            Some(Rect::new(120.0, 50.0, 130.0, 70.0))
+        );
+        assert_eq!(
+            state.range_bounds(NodeId(2), 0, 3),
+            Some(Rect::new(110.0, 50.0, 140.0, 70.0))
+        );
+        assert_eq!(
+            state.range_bounds(NodeId(2), 2, 10),
+            Some(Rect::new(130.0, 50.0, 140.0, 70.0))
+        );
+        assert_eq!(
+            state.range_bounds(NodeId(2), 5, 10),
+            Some(Rect::new(140.0, 50.0, 140.0, 70.0))
+        );
+        assert_eq!(state.range_bounds(NodeId(1), 0, 1), None);
+        assert_eq!(state.range_bounds(NodeId(3), 0, 1), None);
+    }
+
+    #[cfg(feature = "enumn")]
+    #[test]
+    fn describe_diff_lists_changed_properties() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::Button);
+        builder.set_name("Save");
+        builder.add_action(Action::Focus);
+        let old = builder.build(&mut classes);
+        let mut builder = NodeBuilder::new(Role::Button);
+        builder.set_name("Save as");
+        builder.add_action(Action::Focus);
+        builder.set_disabled();
+        let new = builder.build(&mut classes);
+        assert_eq!(
+            old.describe_diff(&new),
+            vec![
+                "Disabled: false -> true".to_string(),
+                "Name: \"Save\" -> \"Save as\"".to_string(),
+            ]
+        );
+        assert!(old.describe_diff(&old).is_empty());
+        assert_eq!(
+            old.property_changes(&new)[1],
+            PropertyChange {
+                name: "Name".into(),
+                old: "\"Save\"".into(),
+                new: "\"Save as\"".into(),
+            }
+        );
+
+        let mut builder = NodeBuilder::new(Role::CheckBox);
+        builder.set_name("Save");
+        builder.add_action(Action::Default);
+        builder.add_action(Action::Focus);
+        let other = builder.build(&mut classes);
+        assert_eq!(
+            old.describe_diff(&other),
+            vec![
+                "Role: Button -> CheckBox".to_string(),
+                "Actions: [Focus] -> [Default, Focus]".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn summarize_content_update() {
+        let mut classes = NodeClassSet::new();
+        let state = initial_snapshot(&mut classes);
+        let mut builder = NodeBuilder::new(Role::Button);
+        builder.set_name("renamed");
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(3), builder.build(&mut classes)),
+                (NodeId(2), node(&mut classes, Role::Button, &[])),
+            ],
+            tree: None,
+            focus: NodeId(2),
+        };
+        assert_eq!(
+            state.summarize(&update),
+            UpdateSummary {
+                focus_changed: false,
+                structure_changed: false,
+                content_changed: true,
+            }
+        );
+    }
+
+    #[test]
+    fn summarize_focus_move() {
+        let mut classes = NodeClassSet::new();
+        let state = initial_snapshot(&mut classes);
+        let update = TreeUpdate {
+            nodes: vec![(NodeId(3), node(&mut classes, Role::Button, &[]))],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(3),
+        };
+        assert_eq!(
+            state.summarize(&update),
+            UpdateSummary {
+                focus_changed: true,
+                structure_changed: false,
+                content_changed: false,
+            }
+        );
+    }
+
+    #[test]
+    fn summarize_combined_update() {
+        let mut classes = NodeClassSet::new();
+        let state = initial_snapshot(&mut classes);
+        let mut builder = NodeBuilder::new(Role::Button);
+        builder.set_name("new");
+        let update = TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(1),
+                    node(&mut classes, Role::Group, &[NodeId(2), NodeId(4)]),
+                ),
+                (NodeId(4), builder.build(&mut classes)),
+            ],
+            tree: None,
+            focus: NodeId(4),
+        };
+        assert_eq!(
+            state.summarize(&update),
+            UpdateSummary {
+                focus_changed: true,
+                structure_changed: true,
+                content_changed: false,
+            }
+        );
+        let mut builder = NodeBuilder::new(Role::Group);
+        builder.set_children(vec![NodeId(2)]);
+        builder.set_name("toolbar");
+        let update = TreeUpdate {
+            nodes: vec![(NodeId(1), builder.build(&mut classes))],
+            tree: None,
+            focus: NodeId(3),
+        };
+        assert_eq!(
+            state.summarize(&update),
+            UpdateSummary {
+                focus_changed: true,
+                structure_changed: false,
+                content_changed: true,
+            }
+        );
+    }
+
+    #[test]
+    fn fill_set_positions_skips_hidden_items() {
+        let mut classes = NodeClassSet::new();
+        let mut state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(0),
+                    node(&mut classes, Role::List, &[NodeId(1), NodeId(2), NodeId(3)]),
+                ),
+                (NodeId(1), node(&mut classes, Role::ListItem, &[])),
+                (NodeId(2), {
+                    let mut builder = NodeBuilder::new(Role::ListItem);
+                    builder.set_hidden();
+                    builder.build(&mut classes)
+                }),
+                (NodeId(3), node(&mut classes, Role::ListItem, &[])),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        });
+        let update = state.fill_set_positions(NodeId(0), &mut classes);
+        let mut updated = update.nodes.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        updated.sort_by_key(|id| id.0);
+        assert_eq!(updated, vec![NodeId(1), NodeId(3)]);
+        let item = |id| &state.nodes[&NodeId(id)];
+        assert_eq!(item(1).position_in_set(), Some(1));
+        assert_eq!(item(1).size_of_set(), Some(2));
+        assert_eq!(item(2).position_in_set(), None);
+        assert_eq!(item(2).size_of_set(), None);
+        assert_eq!(item(3).position_in_set(), Some(2));
+        assert_eq!(item(3).size_of_set(), Some(2));
+        assert!(state
+            .fill_set_positions(NodeId(0), &mut classes)
+            .nodes
+            .is_empty());
+    }
+
+    #[test]
+    fn diff_single_property_changes() {
+        let mut classes = NodeClassSet::new();
+        let mut base = NodeBuilder::new(Role::CheckBox);
+        base.set_name("Subscribe");
+        base.set_checked(Checked::False);
+        base.set_children(vec![NodeId(1)]);
+        let old = base.clone().build(&mut classes);
+        let mut diff = |f: &dyn Fn(&mut NodeBuilder)| {
+            let mut builder = base.clone();
+            f(&mut builder);
+            builder.build(&mut classes).diff(&old)
+        };
+        assert!(diff(&|_| ()).is_empty());
+        assert_eq!(
+            diff(&|builder| builder.set_name("Unsubscribe")),
+            NodeChangeSet {
+                name: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff(&|builder| builder.set_numeric_value(1.0)),
+            NodeChangeSet {
+                value: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff(&|builder| builder.set_checked(Checked::True)),
+            NodeChangeSet {
+                states: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff(&|builder| builder.set_item_status("Loading")),
+            NodeChangeSet {
+                states: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff(&|builder| builder.set_disabled()),
+            NodeChangeSet {
+                states: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff(&|builder| builder.set_bounds(Rect::new(0.0, 0.0, 10.0, 10.0))),
+            NodeChangeSet {
+                bounds: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff(&|builder| builder.push_child(NodeId(2))),
+            NodeChangeSet {
+                children: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff(&|builder| builder.set_selected(true)),
+            NodeChangeSet {
+                selection: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff(&|builder| builder.add_action(Action::Focus)),
+            NodeChangeSet {
+                actions: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff(&|builder| builder.set_font_size(12.0)),
+            NodeChangeSet {
+                other: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff(&|builder| builder.set_role(Role::Switch)),
+            NodeChangeSet {
+                role: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn exposed_changes() {
+        let mut classes = NodeClassSet::new();
+        let mut base = NodeBuilder::new(Role::Button);
+        base.set_name("OK");
+        let old = base.clone().build(&mut classes);
+        let changed = |f: &dyn Fn(&mut NodeBuilder)| {
+            let mut builder = base.clone();
+            f(&mut builder);
+            builder.build(&mut NodeClassSet::new())
+        };
+        assert!(!old.has_exposed_changes(&old));
+        assert!(!changed(&|builder| builder.set_class_name("primary")).has_exposed_changes(&old));
+        assert!(!changed(&|builder| builder.set_font_size(12.0)).has_exposed_changes(&old));
+        assert!(!changed(&|builder| builder.set_bold()).has_exposed_changes(&old));
+        assert!(changed(&|builder| builder.set_name("Cancel")).has_exposed_changes(&old));
+        assert!(changed(&|builder| builder.set_tooltip("Confirm")).has_exposed_changes(&old));
+        assert!(changed(&|builder| builder.set_language("fr")).has_exposed_changes(&old));
+        assert!(changed(&|builder| builder.set_caption(NodeId(2))).has_exposed_changes(&old));
+        assert!(changed(&|builder| {
+            builder.set_class_name("primary");
+            builder.set_disabled();
+        })
+        .has_exposed_changes(&old));
+
+        let state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (NodeId(0), node(&mut classes, Role::Window, &[NodeId(1)])),
+                (NodeId(1), old.clone()),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(1),
+        });
+        let update = |node: Node| TreeUpdate {
+            nodes: vec![(NodeId(1), node)],
+            tree: None,
+            focus: NodeId(1),
+        };
+        assert!(!state.has_exposed_changes(&update(changed(&|builder| {
+            builder.set_html_tag("button");
+        }))));
+        assert!(state.has_exposed_changes(&update(changed(&|builder| {
+            builder.set_name("Cancel");
+        }))));
+        assert!(state.has_exposed_changes(&TreeUpdate {
+            nodes: vec![],
+            tree: None,
+            focus: NodeId(0),
+        }));
+    }
+
+    #[test]
+    fn role_predicates() {
+        assert!(Role::TextInput.is_text_input());
+        assert!(Role::EditableComboBox.is_text_input());
+        assert!(!Role::ComboBox.is_text_input());
+        assert!(Role::Navigation.is_landmark());
+        assert!(!Role::Group.is_landmark());
+        assert!(Role::Group.is_container());
+        assert!(Role::Navigation.is_container());
+        assert!(!Role::Button.is_container());
+        assert!(Role::Button.requires_name());
+        assert!(Role::SearchInput.requires_name());
+        assert!(!Role::GenericContainer.requires_name());
+    }
+
+    #[test]
+    fn propagate_disabled_to_descendants() {
+        let mut classes = NodeClassSet::new();
+        let mut update = TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(0),
+                    node(&mut classes, Role::Window, &[NodeId(1), NodeId(5)]),
+                ),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::Group);
+                    builder.set_children(vec![NodeId(2), NodeId(3)]);
+                    builder.set_disabled();
+                    builder.build(&mut classes)
+                }),
+                (NodeId(2), node(&mut classes, Role::Button, &[])),
+                (NodeId(3), node(&mut classes, Role::Group, &[NodeId(4)])),
+                (NodeId(4), node(&mut classes, Role::CheckBox, &[])),
+                (NodeId(5), node(&mut classes, Role::Button, &[])),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        update.propagate_disabled(NodeId(0), &mut classes);
+        let is_disabled = |id| {
+            update
+                .nodes
+                .iter()
+                .find(|(node_id, _)| *node_id == NodeId(id))
+                .unwrap()
+                .1
+                .is_disabled()
+        };
+        assert!(!is_disabled(0));
+        assert!(is_disabled(1));
+        assert!(is_disabled(2));
+        assert!(is_disabled(3));
+        assert!(is_disabled(4));
+        assert!(!is_disabled(5));
+        assert_eq!(update.nodes[3].1.role(), Role::Group);
+        assert_eq!(update.nodes[3].1.children(), &[NodeId(4)]);
+    }
+
+    #[test]
+    fn nearest_ancestor() {
+        let mut classes = NodeClassSet::new();
+        let state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (NodeId(0), node(&mut classes, Role::Window, &[NodeId(1)])),
+                (NodeId(1), node(&mut classes, Role::Table, &[NodeId(2)])),
+                (NodeId(2), node(&mut classes, Role::Row, &[NodeId(3)])),
+                (NodeId(3), node(&mut classes, Role::Cell, &[])),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        });
+        let is_table = |role| matches!(role, Role::Table | Role::Grid | Role::TreeGrid);
+        assert_eq!(state.nearest_ancestor(NodeId(3), is_table), Some(NodeId(1)));
+        assert_eq!(state.nearest_ancestor(NodeId(1), is_table), None);
+        assert_eq!(
+            state.nearest_ancestor(NodeId(3), |role| role == Role::Dialog),
+            None
+        );
+        assert_eq!(state.nearest_ancestor(NodeId(4), is_table), None);
+    }
+
+    #[test]
+    fn find_by_automation_id() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::Button);
+        builder.set_automation_id("submit");
+        let state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (NodeId(0), node(&mut classes, Role::Window, &[NodeId(1)])),
+                (NodeId(1), node(&mut classes, Role::Group, &[NodeId(2)])),
+                (NodeId(2), builder.build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        });
+        assert_eq!(state.find_by_automation_id("submit"), Some(NodeId(2)));
+        assert_eq!(state.find_by_automation_id("cancel"), None);
+        assert_eq!(state.nodes[&NodeId(0)].automation_id(), None);
+    }
+
+    #[test]
+    fn focus_follows_remapped_node() {
+        let mut classes = NodeClassSet::new();
+        let build = |classes: &mut NodeClassSet, base: u64| {
+            let (input, text_run) = (NodeId(base + 1), NodeId(base + 2));
+            let mut builder = NodeBuilder::new(Role::TextInput);
+            builder.set_automation_id("search");
+            builder.push_child(text_run);
+            builder.add_action(Action::Focus);
+            if base == 0 {
+                let position = TextPosition {
+                    node: text_run,
+                    character_index: 3,
+                };
+                builder.set_text_selection(TextSelection {
+                    anchor: position,
+                    focus: position,
+                });
+            }
+            TreeUpdate {
+                nodes: vec![
+                    (NodeId(0), node(classes, Role::Window, &[input])),
+                    (input, builder.build(classes)),
+                    (text_run, node(classes, Role::InlineTextBox, &[])),
+                ],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(0),
+            }
+        };
+        let mut old = TreeSnapshot::new(build(&mut classes, 0));
+        old.focus = NodeId(1);
+        let snapshot = old.focus_snapshot();
+
+        let mut update = build(&mut classes, 10);
+        let new = TreeSnapshot::new(update.clone());
+        let restored = snapshot.restore(
+            &mut update,
+            |id| match old.nodes.get(&id)?.automation_id() {
+                Some(key) => new.find_by_automation_id(key),
+                None => Some(NodeId(id.0 + 10)),
+            },
+            &mut classes,
+        );
+        assert!(restored);
+        let new = TreeSnapshot::new(update);
+        assert_eq!(new.focus, NodeId(11));
+        let selection = new.nodes[&NodeId(11)].text_selection().unwrap();
+        assert_eq!(selection.focus.node, NodeId(12));
+        assert_eq!(selection.focus.character_index, 3);
+
+        let mut update = build(&mut classes, 10);
+        assert!(!snapshot.restore(&mut update, |_| None, &mut classes));
+        assert_eq!(update.focus, NodeId(0));
+    }
+
+    #[test]
+    fn focus_offscreen_in_scroller() {
+        let mut classes = NodeClassSet::new();
+        let button = |classes: &mut NodeClassSet, bounds: Rect| {
+            let mut builder = NodeBuilder::new(Role::Button);
+            builder.set_bounds(bounds);
+            builder.build(classes)
+        };
+        let mut state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (NodeId(0), node(&mut classes, Role::Window, &[NodeId(1)])),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::GenericContainer);
+                    builder.set_children(vec![NodeId(2), NodeId(3), NodeId(4)]);
+                    builder.set_bounds(Rect::new(0.0, 0.0, 100.0, 100.0));
+                    builder.set_scroll_y(0.0);
+                    builder.set_scroll_y_min(0.0);
+                    builder.set_scroll_y_max(200.0);
+                    builder.build(&mut classes)
+                }),
+                (
+                    NodeId(2),
+                    button(&mut classes, Rect::new(10.0, 10.0, 50.0, 30.0)),
+                ),
+                (
+                    NodeId(3),
+                    button(&mut classes, Rect::new(10.0, 150.0, 50.0, 170.0)),
+                ),
+                (
+                    NodeId(4),
+                    button(&mut classes, Rect::new(10.0, 400.0, 50.0, 420.0)),
+                ),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(2),
+        });
+        assert_eq!(state.is_focus_offscreen_in_scroller(), None);
+        state.focus = NodeId(3);
+        assert_eq!(
+            state.is_focus_offscreen_in_scroller(),
+            Some((NodeId(1), Rect::new(0.0, 70.0, 100.0, 170.0)))
+        );
+        state.focus = NodeId(4);
+        assert_eq!(
+            state.is_focus_offscreen_in_scroller(),
+            Some((NodeId(1), Rect::new(0.0, 200.0, 100.0, 300.0)))
+        );
+        state.focus = NodeId(1);
+        assert_eq!(state.is_focus_offscreen_in_scroller(), None);
+    }
+
+    #[test]
+    fn focus_trap_bounds() {
+        let mut classes = NodeClassSet::new();
+        let focusable = |classes: &mut NodeClassSet, f: &dyn Fn(&mut NodeBuilder)| {
+            let mut builder = NodeBuilder::new(Role::Button);
+            builder.add_action(Action::Focus);
+            f(&mut builder);
+            builder.build(classes)
+        };
+        let state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(0),
+                    node(
+                        &mut classes,
+                        Role::Window,
+                        &[NodeId(1), NodeId(8), NodeId(13)],
+                    ),
+                ),
+                (
+                    NodeId(1),
+                    node(
+                        &mut classes,
+                        Role::Dialog,
+                        &[
+                            NodeId(2),
+                            NodeId(3),
+                            NodeId(5),
+                            NodeId(6),
+                            NodeId(7),
+                            NodeId(11),
+                        ],
+                    ),
+                ),
+                (NodeId(2), node(&mut classes, Role::StaticText, &[])),
+                (NodeId(3), node(&mut classes, Role::Group, &[NodeId(4)])),
+                (NodeId(4), focusable(&mut classes, &|_| ())),
+                (NodeId(5), focusable(&mut classes, &|_| ())),
+                (
+                    NodeId(6),
+                    focusable(&mut classes, &|builder| builder.set_disabled()),
+                ),
+                (
+                    NodeId(7),
+                    focusable(&mut classes, &|builder| builder.set_hidden()),
+                ),
+                (
+                    NodeId(8),
+                    node(&mut classes, Role::AlertDialog, &[NodeId(9)]),
+                ),
+                (NodeId(9), node(&mut classes, Role::StaticText, &[])),
+                (NodeId(11), {
+                    let mut builder = NodeBuilder::new(Role::Group);
+                    builder.set_children(vec![NodeId(12)]);
+                    builder.set_disabled();
+                    builder.build(&mut classes)
+                }),
+                (NodeId(12), focusable(&mut classes, &|_| ())),
+                (NodeId(13), {
+                    let mut builder = NodeBuilder::new(Role::Group);
+                    builder.set_children(vec![NodeId(14)]);
+                    builder.set_hidden();
+                    builder.build(&mut classes)
+                }),
+                (NodeId(14), node(&mut classes, Role::Dialog, &[NodeId(15)])),
+                (NodeId(15), focusable(&mut classes, &|_| ())),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(4),
+        });
+        assert_eq!(
+            state.focus_trap_bounds(NodeId(1)),
+            Some((NodeId(4), NodeId(5)))
+        );
+        assert_eq!(
+            state.focus_trap_bounds(NodeId(3)),
+            Some((NodeId(4), NodeId(4)))
+        );
+        assert_eq!(state.focus_trap_bounds(NodeId(8)), None);
+        assert_eq!(state.focus_trap_bounds(NodeId(10)), None);
+        assert_eq!(state.focus_trap_bounds(NodeId(11)), None);
+        assert_eq!(state.focus_trap_bounds(NodeId(14)), None);
+    }
+
+    #[test]
+    fn reading_order_follows_flow_to() {
+        let mut classes = NodeClassSet::new();
+        let column = |classes: &mut NodeClassSet, flow_to: &[NodeId]| {
+            let mut builder = NodeBuilder::new(Role::Section);
+            builder.set_flow_to(flow_to);
+            builder.build(classes)
+        };
+        let state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(0),
+                    node(
+                        &mut classes,
+                        Role::Window,
+                        &[NodeId(1), NodeId(2), NodeId(3), NodeId(4)],
+                    ),
+                ),
+                (NodeId(1), column(&mut classes, &[NodeId(9), NodeId(3)])),
+                (NodeId(2), column(&mut classes, &[])),
+                (NodeId(3), column(&mut classes, &[])),
+                (NodeId(4), column(&mut classes, &[NodeId(2)])),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        });
+        assert_eq!(state.reading_order(NodeId(1)), vec![NodeId(1), NodeId(3)]);
+        assert_eq!(state.reading_order(NodeId(4)), vec![NodeId(4), NodeId(2)]);
+        assert_eq!(state.reading_order(NodeId(2)), vec![NodeId(2)]);
+        assert_eq!(state.reading_order(NodeId(9)), vec![]);
+    }
+
+    #[test]
+    fn reading_order_stops_at_cycle() {
+        let mut classes = NodeClassSet::new();
+        let column = |classes: &mut NodeClassSet, flow_to: NodeId| {
+            let mut builder = NodeBuilder::new(Role::Section);
+            builder.push_flow_to(flow_to);
+            builder.build(classes)
+        };
+        let state = TreeSnapshot::new(TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(0),
+                    node(
+                        &mut classes,
+                        Role::Window,
+                        &[NodeId(1), NodeId(2), NodeId(3)],
+                    ),
+                ),
+                (NodeId(1), column(&mut classes, NodeId(2))),
+                (NodeId(2), column(&mut classes, NodeId(3))),
+                (NodeId(3), column(&mut classes, NodeId(1))),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        });
+        assert_eq!(
+            state.reading_order(NodeId(2)),
+            vec![NodeId(2), NodeId(3), NodeId(1)]
+        );
+    }
+
+    #[test]
+    fn diff_update_is_minimal() {
+        let mut classes = NodeClassSet::new();
+        let old = initial_snapshot(&mut classes);
+        let mut new = old.clone();
+        let mut builder = NodeBuilder::new(Role::Button);
+        builder.set_name("renamed");
+        new.apply(TreeUpdate {
+            nodes: vec![
+                (NodeId(1), node(&mut classes, Role::Group, &[NodeId(4)])),
+                (NodeId(4), builder.build(&mut classes)),
+            ],
+            tree: None,
+            focus: NodeId(4),
+        });
+        let update = old.diff_update(&new);
+        assert_eq!(
+            update.nodes.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![NodeId(1), NodeId(4)]
+        );
+        assert_eq!(update.tree, None);
+        assert_eq!(update.focus, NodeId(4));
+        let mut applied = old.clone();
+        applied.apply(update);
+        assert_eq!(applied, new);
+        assert!(new.diff_update(&new).nodes.is_empty());
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn serialize_delta_round_trip() {
+        let mut classes = NodeClassSet::new();
+        let base = initial_snapshot(&mut classes);
+        let mut new = base.clone();
+        let mut tree = Tree::new(NodeId(0));
+        tree.app_name = Some("Editor".into());
+        new.apply(TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(0),
+                    node(&mut classes, Role::Window, &[NodeId(3), NodeId(5)]),
+                ),
+                (NodeId(5), node(&mut classes, Role::Button, &[])),
+            ],
+            tree: Some(tree),
+            focus: NodeId(5),
+        });
+        let delta = base.serialize_delta(&new);
+        let mut reconstructed = base.clone();
+        reconstructed.apply(TreeUpdate::from_postcard(&delta).unwrap());
+        assert_eq!(reconstructed, new);
+        assert!(!reconstructed.nodes.contains_key(&NodeId(2)));
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn postcard_keys_are_stable() {
+        // These values are part of the postcard format, so changing any of
+        // them breaks decoding of existing data.
+        let properties = [
+            (PropertyId::Children, 0),
+            (PropertyId::ActiveDescendant, 7),
+            (PropertyId::Name, 17),
+            (PropertyId::ScrollX, 32),
+            (PropertyId::TableRowCount, 45),
+            (PropertyId::ColorValue, 56),
+            (PropertyId::Overline, 59),
+            (PropertyId::CharacterLengths, 62),
+            (PropertyId::Expanded, 66),
+            (PropertyId::Invalid, 68),
+            (PropertyId::Transform, 81),
+            (PropertyId::CustomActions, 84),
+            (PropertyId::AriaRole, 85),
+            (PropertyId::CustomContent, 86),
+            (PropertyId::Owns, 87),
+            (PropertyId::ReturnFocusTo, 88),
+            (PropertyId::ReadingLevel, 89),
+            (PropertyId::AccessibleText, 90),
+            (PropertyId::AutomationId, 91),
+            (PropertyId::Caption, 92),
+            (PropertyId::ItemStatus, 93),
+        ];
+        for (id, key) in properties {
+            assert_eq!(
+                postcard::to_allocvec(&CompactKey::Property(id)).unwrap(),
+                vec![2, key],
+                "{:?}",
+                id
+            );
+        }
+        let flags = [
+            (Flag::Hovered, 0),
+            (Flag::IsSuggestion, 20),
+            (Flag::BoundsUnknown, 21),
+            (Flag::IsPrimaryContent, 22),
+            (Flag::Mixed, 23),
+        ];
+        for (flag, key) in flags {
+            assert_eq!(
+                postcard::to_allocvec(&CompactKey::Flag(flag)).unwrap(),
+                vec![1, key],
+                "{:?}",
+                flag
+            );
+        }
+        assert_eq!(
+            postcard::to_allocvec(&CompactKey::ClassField(ClassFieldId::Actions)).unwrap(),
+            vec![0, 1]
+        );
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn postcard_round_trip() {
+        let mut classes = NodeClassSet::new();
+        let mut nodes = vec![(NodeId(0), {
+            let mut builder = NodeBuilder::new(Role::Window);
+            builder.set_children((1..=20).map(NodeId).collect::<Vec<_>>());
+            builder.set_name("Window");
+            builder.build(&mut classes)
+        })];
+        for i in 1..=20 {
+            let mut builder = NodeBuilder::new(Role::Button);
+            builder.set_name(format!("Button {}", i));
+            builder.add_action(Action::Focus);
+            builder.set_default_action_verb(DefaultActionVerb::Click);
+            builder.set_bounds(Rect::new(
+                0.0,
+                i as f64 * 20.0,
+                100.0,
+                i as f64 * 20.0 + 16.0,
+            ));
+            if i % 2 == 0 {
+                builder.set_disabled();
+            }
+            nodes.push((NodeId(i), builder.build(&mut classes)));
+        }
+        let update = TreeUpdate {
+            nodes,
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(1),
+        };
+        let bytes = update.to_postcard();
+        assert_eq!(TreeUpdate::from_postcard(&bytes).unwrap(), update);
+        let json = serde_json::to_vec(&update).unwrap();
+        assert!(bytes.len() * 2 < json.len());
+    }
+}
@@ -4,10 +4,10 @@
 // the LICENSE-MIT file), at your option.
 
 pub(crate) mod tree;
-pub use tree::{ChangeHandler as TreeChangeHandler, State as TreeState, Tree};
+pub use tree::{AnnouncementFilter, ChangeHandler as TreeChangeHandler, State as TreeState, Tree};
 
 pub(crate) mod node;
-pub use node::{DetachedNode, Node, NodeState};
+pub use node::{checked_state_text, normalize_whitespace, DetachedNode, Node, NodeState};
 
 pub(crate) mod filters;
 pub use filters::{
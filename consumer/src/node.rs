@@ -8,11 +8,14 @@
 // Use of this source code is governed by a BSD-style license that can be
 // found in the LICENSE.chromium file.
 
-use std::{iter::FusedIterator, ops::Deref};
+use std::{
+    iter::FusedIterator,
+    ops::{Deref, Range},
+};
 
 use accesskit::{
-    Action, Affine, Checked, DefaultActionVerb, Live, Node as NodeData, NodeId, Point, Rect, Role,
-    TextSelection,
+    Action, Affine, AriaCurrent, Checked, CustomContent, DefaultActionVerb, Invalid, Live,
+    Node as NodeData, NodeChangeSet, NodeId, Orientation, Point, Rect, Role, TextSelection,
 };
 
 use crate::filters::FilterResult;
@@ -20,7 +23,7 @@ use crate::iterators::{
     FilteredChildren, FollowingFilteredSiblings, FollowingSiblings, LabelledBy,
     PrecedingFilteredSiblings, PrecedingSiblings,
 };
-use crate::tree::State as TreeState;
+use crate::tree::{Referrers, State as TreeState};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) struct ParentAndIndex(pub(crate) NodeId, pub(crate) usize);
@@ -51,6 +54,7 @@ impl<'a> Node<'a> {
             is_focused: self.is_focused(),
             is_root: self.is_root(),
             name: self.name(),
+            description: self.computed_description(),
             value: self.value(),
             live: self.live(),
             supports_text_ranges: self.supports_text_ranges(),
@@ -68,6 +72,14 @@ impl NodeState {
     }
 }
 
+impl NodeState {
+    /// Returns which groups of this node's own properties differ from
+    /// an older version of the same node. See [`NodeData::diff`].
+    pub fn diff(&self, old: &NodeState) -> NodeChangeSet {
+        self.data().diff(old.data())
+    }
+}
+
 impl<'a> Node<'a> {
     pub fn is_root(&self) -> bool {
         // Don't check for absence of a parent node, in case a non-root node
@@ -279,7 +291,12 @@ impl<'a> Node<'a> {
 }
 
 impl NodeState {
+    /// Returns the node's bounds, or `None` if the node has no bounds
+    /// or its bounds are unknown.
     pub fn raw_bounds(&self) -> Option<Rect> {
+        if self.data().is_bounds_unknown() {
+            return None;
+        }
         self.data().bounds()
     }
 }
@@ -317,7 +334,11 @@ impl<'a> Node<'a> {
         }
 
         for child in self.children().rev() {
-            let point = child.direct_transform().inverse() * point;
+            let transform = child.direct_transform();
+            if !transform.is_invertible() {
+                continue;
+            }
+            let point = transform.inverse() * point;
             if let Some(result) = child.hit_test(point, filter) {
                 return Some(result);
             }
@@ -362,14 +383,115 @@ impl NodeState {
         self.data().role_description().is_some()
     }
 
+    pub fn aria_role(&self) -> Option<&str> {
+        self.data().aria_role()
+    }
+
+    /// Returns the node's live setting, or the one implied by its role
+    /// if it doesn't have one: a [`Role::Status`] is a polite live region.
+    pub(crate) fn explicit_or_implied_live(&self) -> Option<Live> {
+        self.data().live().or(match self.role() {
+            Role::Status => Some(Live::Polite),
+            _ => None,
+        })
+    }
+
+    pub fn tooltip(&self) -> Option<&str> {
+        self.data().tooltip()
+    }
+
+    pub fn automation_id(&self) -> Option<&str> {
+        self.data().automation_id()
+    }
+
+    pub fn item_status(&self) -> Option<&str> {
+        self.data().item_status()
+    }
+
+    pub fn state_description(&self) -> Option<&str> {
+        self.data().state_description()
+    }
+
+    /// Returns the text that describes this node's checked state: its
+    /// [`state_description`] if set, otherwise the default phrase from
+    /// [`checked_state_text`]. Returns `None` if the node isn't checkable.
+    ///
+    /// [`state_description`]: NodeState::state_description
+    pub fn checked_state_text(&self) -> Option<&str> {
+        let checked = self.checked()?;
+        Some(
+            self.state_description()
+                .unwrap_or_else(|| checked_state_text(checked, self.data().language())),
+        )
+    }
+
+    pub fn access_key(&self) -> Option<&str> {
+        self.data().access_key()
+    }
+
+    pub fn keyboard_shortcut(&self) -> Option<&str> {
+        self.data().keyboard_shortcut()
+    }
+
     pub fn is_hidden(&self) -> bool {
         self.data().is_hidden()
     }
 
+    pub fn is_modal(&self) -> bool {
+        self.data().is_modal()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.data().is_busy()
+    }
+
+    pub fn is_primary_content(&self) -> bool {
+        self.data().is_primary_content()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.data().url()
+    }
+
+    pub fn is_linked(&self) -> bool {
+        self.data().is_linked()
+    }
+
+    /// Returns true if this node is a link, either because its role is
+    /// [`Role::Link`] or because it's an inline node marked as linked or
+    /// with a URL, as in rich text.
+    pub fn is_hyperlink(&self) -> bool {
+        self.role() == Role::Link || self.is_linked() || self.url().is_some()
+    }
+
+    pub fn custom_content(&self) -> &[CustomContent] {
+        self.data().custom_content()
+    }
+
+    pub fn color_value(&self) -> Option<u32> {
+        self.data().color_value()
+    }
+
+    pub fn active_descendant(&self) -> Option<NodeId> {
+        self.data().active_descendant()
+    }
+
+    pub fn return_focus_to(&self) -> Option<NodeId> {
+        self.data().return_focus_to()
+    }
+
     pub fn is_disabled(&self) -> bool {
         self.data().is_disabled()
     }
 
+    pub fn is_required(&self) -> bool {
+        self.data().is_required()
+    }
+
+    pub fn invalid(&self) -> Option<Invalid> {
+        self.data().invalid()
+    }
+
     pub fn is_read_only(&self) -> bool {
         let data = self.data();
         if data.is_read_only() {
@@ -387,6 +509,29 @@ impl NodeState {
         self.data().checked()
     }
 
+    /// Returns true if the node is in a partial or mixed state, either
+    /// because [`accesskit::Node::is_mixed`] is set or because its
+    /// checked state is [`Checked::Mixed`].
+    pub fn is_mixed(&self) -> bool {
+        self.data().is_mixed() || self.checked() == Some(Checked::Mixed)
+    }
+
+    pub fn aria_current(&self) -> Option<AriaCurrent> {
+        self.data().aria_current()
+    }
+
+    pub fn position_in_set(&self) -> Option<usize> {
+        self.data().position_in_set()
+    }
+
+    pub fn size_of_set(&self) -> Option<usize> {
+        self.data().size_of_set()
+    }
+
+    pub fn hierarchical_level(&self) -> Option<usize> {
+        self.data().hierarchical_level()
+    }
+
     pub fn numeric_value(&self) -> Option<f64> {
         self.data().numeric_value()
     }
@@ -399,6 +544,50 @@ impl NodeState {
         self.data().max_numeric_value()
     }
 
+    /// Returns the node's orientation if it's set, or else the default
+    /// orientation of its role as defined by ARIA, e.g. horizontal for
+    /// sliders and vertical for scroll bars.
+    ///
+    /// Orientation only determines which physical direction corresponds to
+    /// an increase in value, such as the up arrow key for a vertical slider
+    /// and the right arrow key for a horizontal one. Whatever the orientation,
+    /// [`Action::Increment`] moves the numeric value toward its maximum and
+    /// [`Action::Decrement`] toward its minimum, so platform adapters must
+    /// not swap the two for vertical controls.
+    pub fn orientation(&self) -> Option<Orientation> {
+        self.data().orientation().or(match self.role() {
+            Role::MenuBar | Role::Slider | Role::Splitter | Role::TabList | Role::Toolbar => {
+                Some(Orientation::Horizontal)
+            }
+            Role::ListBox | Role::Menu | Role::ScrollBar | Role::Tree | Role::TreeGrid => {
+                Some(Orientation::Vertical)
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns how far the numeric value is from the minimum to the maximum,
+    /// as a percentage clamped to the range 0 to 100. Returns `None` if any
+    /// of the three values is missing or isn't finite, or if the maximum
+    /// isn't greater than the minimum.
+    pub fn progress_percent(&self) -> Option<f64> {
+        let value = self.numeric_value()?;
+        let min = self.min_numeric_value()?;
+        let max = self.max_numeric_value()?;
+        if ![value, min, max].iter().all(|n| n.is_finite()) || max <= min {
+            return None;
+        }
+        Some(((value - min) / (max - min) * 100.0).clamp(0.0, 100.0))
+    }
+
+    fn progress_value_text(&self) -> Option<String> {
+        if self.role() != Role::ProgressIndicator {
+            return None;
+        }
+        self.progress_percent()
+            .map(|percent| format!("{}%", percent.round()))
+    }
+
     pub fn numeric_value_step(&self) -> Option<f64> {
         self.data().numeric_value_step()
     }
@@ -408,24 +597,7 @@ impl NodeState {
     }
 
     pub fn is_text_input(&self) -> bool {
-        matches!(
-            self.role(),
-            Role::TextInput
-                | Role::MultilineTextInput
-                | Role::SearchInput
-                | Role::DateInput
-                | Role::DateTimeInput
-                | Role::WeekInput
-                | Role::MonthInput
-                | Role::TimeInput
-                | Role::EmailInput
-                | Role::NumberInput
-                | Role::PasswordInput
-                | Role::PhoneNumberInput
-                | Role::UrlInput
-                | Role::EditableComboBox
-                | Role::SpinButton
-        )
+        self.role().is_text_input()
     }
 
     pub fn is_multiline(&self) -> bool {
@@ -502,6 +674,18 @@ impl NodeState {
     pub fn supports_decrement(&self) -> bool {
         self.supports_action(Action::Decrement)
     }
+
+    pub fn supports_set_value(&self) -> bool {
+        self.supports_action(Action::SetValue)
+    }
+
+    pub fn supports_show_context_menu(&self) -> bool {
+        self.supports_action(Action::ShowContextMenu)
+    }
+
+    pub fn supports_scroll_into_view(&self) -> bool {
+        self.supports_action(Action::ScrollIntoView)
+    }
 }
 
 fn descendant_label_filter(node: &Node) -> FilterResult {
@@ -529,30 +713,277 @@ impl<'a> Node<'a> {
         }
     }
 
+    /// Returns the node referenced by this node's `caption` property,
+    /// if it's in the tree.
+    pub fn caption(&self) -> Option<Node<'a>> {
+        self.data()
+            .caption()
+            .and_then(|id| self.tree_state.node_by_id(id))
+    }
+
+    /// Returns the nodes listed in this node's `labelled_by` property,
+    /// skipping any IDs that aren't in the tree. Unlike
+    /// [`Node::labelled_by`], this never falls back to descendants.
+    pub fn explicit_labels(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Node<'a>> + FusedIterator<Item = Node<'a>> + 'a {
+        self.nodes_by_ids(self.state.data.labelled_by())
+    }
+
+    /// Returns the nodes that this node logically owns, skipping any
+    /// IDs that aren't in the tree.
+    pub fn owned(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Node<'a>> + FusedIterator<Item = Node<'a>> + 'a {
+        self.nodes_by_ids(self.state.data.owns())
+    }
+
+    /// Returns the nodes whose contents or presence this node controls,
+    /// skipping any IDs that aren't in the tree.
+    pub fn controlled(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Node<'a>> + FusedIterator<Item = Node<'a>> + 'a {
+        self.nodes_by_ids(self.state.data.controls())
+    }
+
+    /// Returns the nodes listed in this node's `described_by` property,
+    /// skipping any IDs that aren't in the tree.
+    pub fn described_by(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Node<'a>> + FusedIterator<Item = Node<'a>> + 'a {
+        self.nodes_by_ids(self.state.data.described_by())
+    }
+
+    /// Returns the nodes listed in this node's `details` property,
+    /// skipping any IDs that aren't in the tree.
+    pub fn details(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Node<'a>> + FusedIterator<Item = Node<'a>> + 'a {
+        self.nodes_by_ids(self.state.data.details())
+    }
+
+    /// Returns the nodes listed in this node's `flow_to` property,
+    /// skipping any IDs that aren't in the tree.
+    pub fn flow_to(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Node<'a>> + FusedIterator<Item = Node<'a>> + 'a {
+        self.nodes_by_ids(self.state.data.flow_to())
+    }
+
+    /// Returns the node referenced by this node's `member_of` property,
+    /// if it's in the tree.
+    pub fn member_of(&self) -> Option<Node<'a>> {
+        self.data()
+            .member_of()
+            .and_then(|id| self.tree_state.node_by_id(id))
+    }
+
+    fn nodes_by_ids(
+        &self,
+        ids: &'a [NodeId],
+    ) -> impl DoubleEndedIterator<Item = Node<'a>> + FusedIterator<Item = Node<'a>> + 'a {
+        let tree_state = self.tree_state;
+        ids.iter().filter_map(move |id| tree_state.node_by_id(*id))
+    }
+
+    /// Returns the nodes that list this node in their `labelled_by`
+    /// property, in ascending order of ID.
+    pub fn label_for(&self) -> Vec<Node<'a>> {
+        self.referrers(|referrers| &referrers.labelled_by)
+    }
+
+    /// Returns the nodes that list this node in their `described_by`
+    /// property, in ascending order of ID.
+    pub fn description_for(&self) -> Vec<Node<'a>> {
+        self.referrers(|referrers| &referrers.described_by)
+    }
+
+    /// Returns the nodes that list this node in their `controls`
+    /// property, in ascending order of ID.
+    pub fn controlled_by(&self) -> Vec<Node<'a>> {
+        self.referrers(|referrers| &referrers.controls)
+    }
+
+    /// Returns the nodes that list this node in their `details`
+    /// property, in ascending order of ID.
+    pub fn details_for(&self) -> Vec<Node<'a>> {
+        self.referrers(|referrers| &referrers.details)
+    }
+
+    /// Returns the nodes that list this node in their `flow_to`
+    /// property, in ascending order of ID.
+    pub fn flow_from(&self) -> Vec<Node<'a>> {
+        self.referrers(|referrers| &referrers.flow_to)
+    }
+
+    fn referrers(&self, ids: fn(&'a Referrers) -> &'a [NodeId]) -> Vec<Node<'a>> {
+        self.tree_state
+            .referrers(self.id())
+            .map_or_else(Vec::new, |referrers| {
+                self.nodes_by_ids(ids(referrers)).collect()
+            })
+    }
+
+    /// Returns the node that logically owns this one, if any. A node
+    /// should have at most one owner; if there are several, the one
+    /// with the lowest ID is returned.
+    pub fn owner(&self) -> Option<Node<'a>> {
+        let tree_state = self.tree_state;
+        tree_state
+            .referrers(self.id())
+            .and_then(|referrers| referrers.owns.first())
+            .and_then(|id| tree_state.node_by_id(*id))
+    }
+
+    /// Returns [`accesskit::Node::accessible_text`] if it's set, or else
+    /// the name property if it's set. Otherwise, the name is
+    /// computed by concatenating the names of the nodes in
+    /// [`Node::labelled_by`], or if there are none, from the name or text
+    /// of the [caption], and unless
+    /// [`crate::Tree::set_preserve_whitespace`] was used to keep it, the whitespace
+    /// in the computed name is normalized with [`normalize_whitespace`].
+    /// The name property itself is never normalized, since the names
+    /// of text runs together make up the text of their container.
+    ///
+    /// [caption]: Node::caption
     pub fn name(&self) -> Option<String> {
-        if let Some(name) = &self.data().name() {
+        self.name_excluding(&mut vec![self.id()])
+    }
+
+    /// Returns the description property if it's set. Otherwise, the
+    /// description is computed from the nodes in `described_by`, in list
+    /// order: the text of each is its name, or if it has none, its
+    /// [text content], and the texts are joined with a single space.
+    /// Targets that are missing from the tree, that refer to this node,
+    /// or that appear more than once are skipped, as are empty texts.
+    /// Whitespace is normalized as for [`Node::name`].
+    ///
+    /// [text content]: crate::TreeState::text_content
+    pub fn computed_description(&self) -> Option<String> {
+        if let Some(description) = self.data().description() {
+            return Some(description.to_string());
+        }
+        let mut visited = vec![self.id()];
+        let mut texts = Vec::new();
+        for id in self.data().described_by() {
+            if visited.contains(id) {
+                continue;
+            }
+            visited.push(*id);
+            let Some(target) = self.tree_state.node_by_id(*id) else {
+                continue;
+            };
+            let text = target
+                .name()
+                .unwrap_or_else(|| self.tree_state.text_content(*id));
+            if !text.is_empty() {
+                texts.push(text);
+            }
+        }
+        if texts.is_empty() {
+            return None;
+        }
+        let description = texts.join(" ");
+        if self.tree_state.preserves_whitespace() {
+            Some(description)
+        } else {
+            Some(normalize_whitespace(&description))
+        }
+    }
+
+    /// Computes the name while skipping labels that have already been
+    /// visited, so that labels which refer back to the node they label
+    /// don't cause infinite recursion.
+    fn name_excluding(&self, visited: &mut Vec<NodeId>) -> Option<String> {
+        if let Some(text) = self.data().accessible_text() {
+            Some(text.to_string())
+        } else if let Some(name) = &self.data().name() {
             Some(name.to_string())
         } else {
-            let names = self
-                .labelled_by()
-                .filter_map(|node| node.name())
-                .collect::<Vec<String>>();
-            (!names.is_empty()).then(move || names.join(" "))
+            let mut names = Vec::new();
+            for label in self.labelled_by() {
+                if visited.contains(&label.id()) {
+                    continue;
+                }
+                visited.push(label.id());
+                names.extend(label.name_excluding(visited));
+            }
+            if names.is_empty() {
+                if let Some(caption) = self.caption() {
+                    if !visited.contains(&caption.id()) {
+                        visited.push(caption.id());
+                        let text = caption
+                            .name_excluding(visited)
+                            .unwrap_or_else(|| self.tree_state.text_content(caption.id()));
+                        if !text.is_empty() {
+                            names.push(text);
+                        }
+                    }
+                }
+            }
+            if names.is_empty() {
+                return None;
+            }
+            let name = names.join(" ");
+            if self.tree_state.preserves_whitespace() {
+                Some(name)
+            } else {
+                Some(normalize_whitespace(&name))
+            }
         }
     }
 
     pub fn value(&self) -> Option<String> {
         if let Some(value) = &self.data().value() {
             Some(value.to_string())
+        } else if let Some(color) = self.color_value() {
+            Some(color_value_text(color))
         } else if self.supports_text_ranges() && !self.is_multiline() {
             Some(self.document_range().text())
         } else {
-            None
+            self.progress_value_text()
         }
     }
 
     pub fn has_value(&self) -> bool {
-        self.data().value().is_some() || (self.supports_text_ranges() && !self.is_multiline())
+        self.data().value().is_some()
+            || self.color_value().is_some()
+            || (self.supports_text_ranges() && !self.is_multiline())
+            || self.progress_value_text().is_some()
+    }
+}
+
+fn ranges_overlap(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Collapses each run of ASCII whitespace into a single space and removes
+/// leading and trailing whitespace, the way browsers normalize accessible
+/// names. Other whitespace, such as non-breaking spaces, is left alone.
+pub fn normalize_whitespace(text: &str) -> String {
+    text.split(|c: char| c.is_ascii_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the default phrase for a checked state, such as "checked",
+/// for adapters that have to present the state as text. Only English
+/// phrasing is built in so far, so `locale`, a BCP 47 language tag,
+/// doesn't yet change the result.
+pub fn checked_state_text(state: Checked, _locale: Option<&str>) -> &'static str {
+    match state {
+        Checked::False => "unchecked",
+        Checked::True => "checked",
+        Checked::Mixed => "mixed",
+    }
+}
+
+fn color_value_text(rgba: u32) -> String {
+    if rgba & 0xff == 0xff {
+        format!("#{:06x}", rgba >> 8)
+    } else {
+        format!("#{:08x}", rgba)
     }
 }
 
@@ -604,27 +1035,329 @@ impl NodeState {
 
 impl<'a> Node<'a> {
     pub fn live(&self) -> Live {
-        self.data()
-            .live()
+        self.state
+            .explicit_or_implied_live()
             .unwrap_or_else(|| self.parent().map_or(Live::Off, |parent| parent.live()))
     }
+
+    /// Returns true if this node or any of its ancestors is disabled.
+    pub fn is_effectively_disabled(&self) -> bool {
+        self.is_disabled()
+            || self
+                .parent()
+                .map_or(false, |parent| parent.is_effectively_disabled())
+    }
+
+    /// Returns true if this node is read-only, either by itself or by
+    /// default for its role, or if any of its ancestors is explicitly
+    /// read-only, as with a text input inside a read-only form.
+    pub fn is_effectively_read_only(&self) -> bool {
+        self.is_read_only() || self.has_read_only_ancestor()
+    }
+
+    fn has_read_only_ancestor(&self) -> bool {
+        self.parent().map_or(false, |parent| {
+            parent.data().is_read_only() || parent.has_read_only_ancestor()
+        })
+    }
+
+    /// Returns the numeric value that results from moving the given number
+    /// of [`numeric_value_step`]s away from the current value, clamped to
+    /// the minimum and maximum where they're set. Platform adapters use this
+    /// to implement increment and decrement requests for nodes that support
+    /// [`Action::SetValue`] but not [`Action::Increment`] and
+    /// [`Action::Decrement`]. Returns `None` if the node doesn't support
+    /// [`Action::SetValue`], has no numeric value or step, or if it or
+    /// one of its ancestors is explicitly marked read-only or is disabled.
+    ///
+    /// [`numeric_value_step`]: NodeState::numeric_value_step
+    pub fn stepped_numeric_value(&self, steps: f64) -> Option<f64> {
+        if !self.supports_set_value()
+            || self.data().is_read_only()
+            || self.has_read_only_ancestor()
+            || self.is_effectively_disabled()
+        {
+            return None;
+        }
+        let mut value = self.numeric_value()? + self.numeric_value_step()? * steps;
+        if let Some(max) = self.max_numeric_value() {
+            value = value.min(max);
+        }
+        if let Some(min) = self.min_numeric_value() {
+            value = value.max(min);
+        }
+        Some(value)
+    }
 }
 
 impl NodeState {
+    pub fn is_table(&self) -> bool {
+        matches!(
+            self.role(),
+            Role::Table | Role::Grid | Role::TreeGrid | Role::ListGrid
+        )
+    }
+
+    pub fn is_table_cell(&self) -> bool {
+        matches!(
+            self.role(),
+            Role::Cell | Role::RowHeader | Role::ColumnHeader
+        ) && self.table_cell_row_index().is_some()
+            && self.table_cell_column_index().is_some()
+    }
+
+    pub fn table_row_count(&self) -> Option<usize> {
+        self.data().table_row_count()
+    }
+
+    pub fn table_column_count(&self) -> Option<usize> {
+        self.data().table_column_count()
+    }
+
+    pub fn table_cell_row_index(&self) -> Option<usize> {
+        self.data().table_cell_row_index()
+    }
+
+    pub fn table_cell_column_index(&self) -> Option<usize> {
+        self.data().table_cell_column_index()
+    }
+
+    pub fn table_cell_row_span(&self) -> usize {
+        self.data().table_cell_row_span().unwrap_or(1)
+    }
+
+    pub fn table_cell_column_span(&self) -> usize {
+        self.data().table_cell_column_span().unwrap_or(1)
+    }
+
+    fn table_cell_rows(&self) -> Option<Range<usize>> {
+        let start = self.table_cell_row_index()?;
+        Some(start..start + self.table_cell_row_span())
+    }
+
+    fn table_cell_columns(&self) -> Option<Range<usize>> {
+        let start = self.table_cell_column_index()?;
+        Some(start..start + self.table_cell_column_span())
+    }
+
     pub fn is_selected(&self) -> Option<bool> {
         self.data().is_selected()
     }
 
+    pub fn is_multiselectable(&self) -> bool {
+        self.data().is_multiselectable()
+    }
+
+    /// Returns true if this node is a container whose descendants can be
+    /// selected, such as a list box, tab list, or tree.
+    pub fn is_selection_container(&self) -> bool {
+        matches!(
+            self.role(),
+            Role::Grid
+                | Role::ListBox
+                | Role::ListGrid
+                | Role::RadioGroup
+                | Role::TabList
+                | Role::Tree
+                | Role::TreeGrid
+        )
+    }
+
+    /// Returns true if this node is an item that can be selected or
+    /// unselected within a selection container.
+    pub fn is_selectable_item(&self) -> bool {
+        match self.role() {
+            // TODO: tables (#29)
+            // https://www.w3.org/TR/core-aam-1.1/#mapping_state-property_table
+            // Radio buttons and radio menu items are selectable when
+            // aria-checked is True or False.
+            Role::RadioButton | Role::MenuItemRadio => {
+                matches!(self.checked(), Some(Checked::True | Checked::False))
+            }
+            // https://www.w3.org/TR/wai-aria-1.1/#aria-selected
+            // Other items are selectable when aria-selected is True or False.
+            Role::ListBoxOption
+            | Role::ListItem
+            | Role::MenuListOption
+            | Role::Tab
+            | Role::TreeItem => self.is_selected().is_some(),
+            _ => false,
+        }
+    }
+
+    /// Returns true if this node is a selectable item, as determined by
+    /// [`NodeState::is_selectable_item`], that is currently selected.
+    /// Radio buttons and radio menu items count as selected when they're
+    /// checked.
+    pub fn is_selected_item(&self) -> bool {
+        self.is_selectable_item()
+            && match self.role() {
+                Role::RadioButton | Role::MenuItemRadio => self.checked() == Some(Checked::True),
+                _ => self.is_selected() == Some(true),
+            }
+    }
+
     pub fn raw_text_selection(&self) -> Option<&TextSelection> {
         self.data().text_selection()
     }
 
+    pub fn raw_name(&self) -> Option<&str> {
+        self.data().name()
+    }
+
+    pub fn accessible_text(&self) -> Option<&str> {
+        self.data().accessible_text()
+    }
+
     pub fn raw_value(&self) -> Option<&str> {
         self.data().value()
     }
 }
 
 impl<'a> Node<'a> {
+    /// Returns the table, grid, or tree grid that contains this cell.
+    pub fn containing_table(&self) -> Option<Node<'a>> {
+        let mut current = self.parent();
+        while let Some(node) = current {
+            if node.is_table() {
+                return Some(node);
+            }
+            current = node.parent();
+        }
+        None
+    }
+
+    /// Returns the cells of this table, in tree order. Hidden subtrees
+    /// and the cells of nested tables are skipped.
+    pub fn table_cells(&self) -> Vec<Node<'a>> {
+        fn collect<'a>(node: &Node<'a>, cells: &mut Vec<Node<'a>>) {
+            for child in node.children() {
+                if child.is_hidden() || child.is_table() {
+                    continue;
+                }
+                if child.is_table_cell() {
+                    cells.push(child);
+                } else {
+                    collect(&child, cells);
+                }
+            }
+        }
+
+        let mut cells = Vec::new();
+        collect(self, &mut cells);
+        cells
+    }
+
+    /// Returns the cell of this table that covers the given zero-based
+    /// row and column, taking row and column spans into account.
+    pub fn table_cell_at(&self, row: usize, column: usize) -> Option<Node<'a>> {
+        self.table_cells().into_iter().find(|cell| {
+            cell.table_cell_rows()
+                .map_or(false, |rows| rows.contains(&row))
+                && cell
+                    .table_cell_columns()
+                    .map_or(false, |columns| columns.contains(&column))
+        })
+    }
+
+    pub fn table_row_headers(&self) -> Vec<Node<'a>> {
+        self.table_cells()
+            .into_iter()
+            .filter(|cell| cell.role() == Role::RowHeader)
+            .collect()
+    }
+
+    pub fn table_column_headers(&self) -> Vec<Node<'a>> {
+        self.table_cells()
+            .into_iter()
+            .filter(|cell| cell.role() == Role::ColumnHeader)
+            .collect()
+    }
+
+    /// Returns the row headers that apply to this cell: the node set as
+    /// [`table_row_header`] if there is one, otherwise the row header
+    /// cells of the containing table that share a row with this cell.
+    ///
+    /// [`table_row_header`]: accesskit::Node::table_row_header
+    pub fn cell_row_headers(&self) -> Vec<Node<'a>> {
+        if let Some(header) = self.data().table_row_header() {
+            return self.tree_state.node_by_id(header).into_iter().collect();
+        }
+        let Some(rows) = self.table_cell_rows() else {
+            return Vec::new();
+        };
+        self.containing_table()
+            .map(|table| table.table_row_headers())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|header| {
+                header.id() != self.id()
+                    && header
+                        .table_cell_rows()
+                        .map_or(false, |header_rows| ranges_overlap(&header_rows, &rows))
+            })
+            .collect()
+    }
+
+    /// Returns the column headers that apply to this cell: the node set
+    /// as [`table_column_header`] if there is one, otherwise the column
+    /// header cells of the containing table that share a column with
+    /// this cell.
+    ///
+    /// [`table_column_header`]: accesskit::Node::table_column_header
+    pub fn cell_column_headers(&self) -> Vec<Node<'a>> {
+        if let Some(header) = self.data().table_column_header() {
+            return self.tree_state.node_by_id(header).into_iter().collect();
+        }
+        let Some(columns) = self.table_cell_columns() else {
+            return Vec::new();
+        };
+        self.containing_table()
+            .map(|table| table.table_column_headers())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|header| {
+                header.id() != self.id()
+                    && header.table_cell_columns().map_or(false, |header_columns| {
+                        ranges_overlap(&header_columns, &columns)
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the nearest ancestor that is a selection container.
+    pub fn selection_container(&self) -> Option<Node<'a>> {
+        let mut current = self.parent();
+        while let Some(node) = current {
+            if node.is_selection_container() {
+                return Some(node);
+            }
+            current = node.parent();
+        }
+        None
+    }
+
+    /// Returns the selected items of this selection container, in tree
+    /// order, as determined by [`NodeState::is_selected_item`]. Hidden
+    /// subtrees and the items of nested selection containers are skipped.
+    pub fn selected_items(&self) -> Vec<Node<'a>> {
+        fn collect<'a>(node: &Node<'a>, items: &mut Vec<Node<'a>>) {
+            for child in node.children() {
+                if child.is_hidden() || child.is_selection_container() {
+                    continue;
+                }
+                if child.is_selected_item() {
+                    items.push(child);
+                }
+                collect(&child, items);
+            }
+        }
+
+        let mut items = Vec::new();
+        collect(self, &mut items);
+        items
+    }
+
     pub fn index_path(&self) -> Vec<usize> {
         self.relative_index_path(self.tree_state.root_id())
     }
@@ -696,6 +1429,7 @@ pub struct DetachedNode {
     pub(crate) is_focused: bool,
     pub(crate) is_root: bool,
     pub(crate) name: Option<String>,
+    pub(crate) description: Option<String>,
     pub(crate) value: Option<String>,
     pub(crate) live: Live,
     pub(crate) supports_text_ranges: bool,
@@ -714,6 +1448,10 @@ impl DetachedNode {
         self.name.clone()
     }
 
+    pub fn computed_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
     pub fn value(&self) -> Option<String> {
         self.value.clone()
     }
@@ -726,6 +1464,31 @@ impl DetachedNode {
         self.live
     }
 
+    /// Returns true if this node was disabled, or if any of its ancestors
+    /// in `current_state` is disabled. The old ancestors are gone by the
+    /// time a detached node is handled, so they're looked up on demand in
+    /// the current tree, starting from this node's old parent.
+    pub fn is_effectively_disabled(&self, current_state: &TreeState) -> bool {
+        self.is_disabled()
+            || self
+                .parent_in(current_state)
+                .map_or(false, |parent| parent.is_effectively_disabled())
+    }
+
+    /// Returns true if this node was read-only, or if any of its ancestors
+    /// in `current_state` is explicitly read-only. Ancestors are looked up
+    /// as in [`DetachedNode::is_effectively_disabled`].
+    pub fn is_effectively_read_only(&self, current_state: &TreeState) -> bool {
+        self.is_read_only()
+            || self.parent_in(current_state).map_or(false, |parent| {
+                parent.data().is_read_only() || parent.has_read_only_ancestor()
+            })
+    }
+
+    fn parent_in<'a>(&self, current_state: &'a TreeState) -> Option<Node<'a>> {
+        self.parent_id().and_then(|id| current_state.node_by_id(id))
+    }
+
     pub fn supports_text_ranges(&self) -> bool {
         self.supports_text_ranges
     }
@@ -745,7 +1508,10 @@ impl Deref for DetachedNode {
 
 #[cfg(test)]
 mod tests {
-    use accesskit::{NodeBuilder, NodeClassSet, NodeId, Point, Rect, Role, Tree, TreeUpdate};
+    use accesskit::{
+        Action, Checked, NodeBuilder, NodeClassSet, NodeId, Orientation, Point, Rect, Role, Tree,
+        TreeUpdate,
+    };
 
     use crate::tests::*;
 
@@ -977,6 +1743,310 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unknown_bounds() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2), NodeId(3), NodeId(4)]);
+                    builder.build(&mut classes)
+                }),
+                (
+                    NodeId(1),
+                    NodeBuilder::new(Role::Button).build(&mut classes),
+                ),
+                (NodeId(2), {
+                    let mut builder = NodeBuilder::new(Role::Button);
+                    builder.set_bounds(Rect::ZERO);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(3), {
+                    let mut builder = NodeBuilder::new(Role::Button);
+                    builder.set_bounds(Rect::new(10.0, 10.0, 50.0, 30.0));
+                    builder.build(&mut classes)
+                }),
+                (NodeId(4), {
+                    let mut builder = NodeBuilder::new(Role::Button);
+                    builder.set_bounds(Rect::ZERO);
+                    builder.set_bounds_unknown();
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let unset = state.node_by_id(NodeId(1)).unwrap();
+        assert!(!unset.has_bounds());
+        assert_eq!(None, unset.bounding_box());
+        let zero = state.node_by_id(NodeId(2)).unwrap();
+        assert!(zero.has_bounds());
+        assert_eq!(Some(Rect::ZERO), zero.bounding_box());
+        let measured = state.node_by_id(NodeId(3)).unwrap();
+        assert_eq!(
+            Some(Rect::new(10.0, 10.0, 50.0, 30.0)),
+            measured.bounding_box()
+        );
+        let unknown = state.node_by_id(NodeId(4)).unwrap();
+        assert!(!unknown.has_bounds());
+        assert_eq!(None, unknown.bounding_box());
+    }
+
+    #[test]
+    fn orientation() {
+        let mut classes = NodeClassSet::new();
+        let node = |classes: &mut NodeClassSet, role, orientation: Option<Orientation>| {
+            let mut builder = NodeBuilder::new(role);
+            if let Some(orientation) = orientation {
+                builder.set_orientation(orientation);
+            }
+            builder.build(classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2), NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), node(&mut classes, Role::Slider, None)),
+                (
+                    NodeId(2),
+                    node(&mut classes, Role::Slider, Some(Orientation::Vertical)),
+                ),
+                (NodeId(3), node(&mut classes, Role::ScrollBar, None)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let orientation = |id| state.node_by_id(id).unwrap().orientation();
+        assert_eq!(orientation(NodeId(1)), Some(Orientation::Horizontal));
+        assert_eq!(orientation(NodeId(2)), Some(Orientation::Vertical));
+        assert_eq!(orientation(NodeId(3)), Some(Orientation::Vertical));
+        assert_eq!(orientation(NodeId(0)), None);
+    }
+
+    #[test]
+    fn stepped_numeric_value() {
+        let mut classes = NodeClassSet::new();
+        let stepper = |classes: &mut NodeClassSet, value, read_only| {
+            let mut builder = NodeBuilder::new(Role::SpinButton);
+            builder.add_action(Action::SetValue);
+            builder.set_numeric_value(value);
+            builder.set_numeric_value_step(5.0);
+            builder.set_min_numeric_value(0.0);
+            builder.set_max_numeric_value(12.0);
+            if read_only {
+                builder.set_read_only();
+            }
+            builder.build(classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![
+                        NodeId(1),
+                        NodeId(2),
+                        NodeId(3),
+                        NodeId(4),
+                        NodeId(5),
+                    ]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), stepper(&mut classes, 5.0, false)),
+                (NodeId(2), stepper(&mut classes, 5.0, true)),
+                (
+                    NodeId(3),
+                    NodeBuilder::new(Role::SpinButton).build(&mut classes),
+                ),
+                (NodeId(4), {
+                    let mut builder = NodeBuilder::new(Role::SpinButton);
+                    builder.set_numeric_value(5.0);
+                    builder.set_numeric_value_step(5.0);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(5), {
+                    let mut builder = NodeBuilder::new(Role::Group);
+                    builder.set_disabled();
+                    builder.set_children(vec![NodeId(6)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(6), stepper(&mut classes, 5.0, false)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let node = state.node_by_id(NodeId(1)).unwrap();
+        assert_eq!(node.stepped_numeric_value(1.0), Some(10.0));
+        assert_eq!(node.stepped_numeric_value(2.0), Some(12.0));
+        assert_eq!(node.stepped_numeric_value(-1.0), Some(0.0));
+        assert_eq!(node.stepped_numeric_value(-2.0), Some(0.0));
+        let read_only = state.node_by_id(NodeId(2)).unwrap();
+        assert_eq!(read_only.stepped_numeric_value(1.0), None);
+        let empty = state.node_by_id(NodeId(3)).unwrap();
+        assert_eq!(empty.stepped_numeric_value(1.0), None);
+        let without_set_value = state.node_by_id(NodeId(4)).unwrap();
+        assert_eq!(without_set_value.stepped_numeric_value(1.0), None);
+        let in_disabled_group = state.node_by_id(NodeId(6)).unwrap();
+        assert_eq!(in_disabled_group.stepped_numeric_value(1.0), None);
+    }
+
+    #[test]
+    fn progress_percent() {
+        let mut classes = NodeClassSet::new();
+        let progress = |classes: &mut NodeClassSet, value, min: Option<f64>, max: Option<f64>| {
+            let mut builder = NodeBuilder::new(Role::ProgressIndicator);
+            builder.set_numeric_value(value);
+            if let Some(min) = min {
+                builder.set_min_numeric_value(min);
+            }
+            if let Some(max) = max {
+                builder.set_max_numeric_value(max);
+            }
+            builder.build(classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![
+                        NodeId(1),
+                        NodeId(2),
+                        NodeId(3),
+                        NodeId(4),
+                        NodeId(5),
+                        NodeId(6),
+                        NodeId(7),
+                    ]);
+                    builder.build(&mut classes)
+                }),
+                (
+                    NodeId(1),
+                    progress(&mut classes, 15.0, Some(10.0), Some(30.0)),
+                ),
+                (
+                    NodeId(2),
+                    progress(&mut classes, 10.0, Some(10.0), Some(30.0)),
+                ),
+                (
+                    NodeId(3),
+                    progress(&mut classes, 30.0, Some(10.0), Some(30.0)),
+                ),
+                (NodeId(4), progress(&mut classes, 40.0, None, Some(100.0))),
+                (
+                    NodeId(5),
+                    progress(&mut classes, 140.0, Some(0.0), Some(100.0)),
+                ),
+                (
+                    NodeId(6),
+                    progress(&mut classes, f64::NAN, Some(0.0), Some(100.0)),
+                ),
+                (
+                    NodeId(7),
+                    progress(&mut classes, 50.0, Some(0.0), Some(f64::INFINITY)),
+                ),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let node = |id| state.node_by_id(NodeId(id)).unwrap();
+        assert_eq!(node(1).progress_percent(), Some(25.0));
+        assert_eq!(node(1).value(), Some("25%".into()));
+        assert_eq!(node(1).detached().value(), Some("25%".into()));
+        assert_eq!(node(2).progress_percent(), Some(0.0));
+        assert_eq!(node(2).value(), Some("0%".into()));
+        assert_eq!(node(3).progress_percent(), Some(100.0));
+        assert_eq!(node(3).value(), Some("100%".into()));
+        assert_eq!(node(4).progress_percent(), None);
+        assert!(!node(4).has_value());
+        assert_eq!(node(5).progress_percent(), Some(100.0));
+        assert_eq!(node(6).progress_percent(), None);
+        assert!(!node(6).has_value());
+        assert_eq!(node(7).progress_percent(), None);
+    }
+
+    #[test]
+    fn count_based_progress() {
+        let mut classes = NodeClassSet::new();
+        let progress = |classes: &mut NodeClassSet, current, total| {
+            let mut builder = NodeBuilder::new(Role::ProgressIndicator);
+            builder.set_progress(current, total, format!("{} of {} MB", current, total));
+            builder.build(classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), progress(&mut classes, 450.0, 1000.0)),
+                (NodeId(2), progress(&mut classes, 0.0, 0.0)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let node = |id| state.node_by_id(NodeId(id)).unwrap();
+        assert_eq!(node(1).progress_percent(), Some(45.0));
+        assert_eq!(node(1).value(), Some("450 of 1000 MB".into()));
+        assert_eq!(node(2).progress_percent(), None);
+        assert_eq!(node(2).value(), Some("0 of 0 MB".into()));
+    }
+
+    #[test]
+    fn color_value() {
+        let mut classes = NodeClassSet::new();
+        let color_well = |classes: &mut NodeClassSet, color, value: Option<&str>| {
+            let mut builder = NodeBuilder::new(Role::ColorWell);
+            builder.set_color_value(color);
+            if let Some(value) = value {
+                builder.set_value(value);
+            }
+            builder.build(classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2), NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), color_well(&mut classes, 0xff8000ff, None)),
+                (NodeId(2), color_well(&mut classes, 0xff800080, None)),
+                (
+                    NodeId(3),
+                    color_well(&mut classes, 0xff8000ff, Some("orange")),
+                ),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let opaque = state.node_by_id(NodeId(1)).unwrap();
+        assert_eq!(Some(0xff8000ff), opaque.color_value());
+        assert!(opaque.has_value());
+        assert_eq!(Some("#ff8000".into()), opaque.value());
+        let translucent = state.node_by_id(NodeId(2)).unwrap();
+        assert_eq!(Some("#ff800080".into()), translucent.value());
+        let named = state.node_by_id(NodeId(3)).unwrap();
+        assert_eq!(Some(0xff8000ff), named.color_value());
+        assert_eq!(Some("orange".into()), named.value());
+        assert_eq!(Some("orange".into()), named.detached().value());
+    }
+
     #[test]
     fn node_at_point() {
         let tree = test_tree();
@@ -1078,6 +2148,535 @@ mod tests {
         );
     }
 
+    #[test]
+    fn owned_and_owner() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::Button);
+                    builder.set_owns(vec![NodeId(2), NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(2), NodeBuilder::new(Role::Menu).build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let mut tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let button = state.node_by_id(NodeId(1)).unwrap();
+        assert_eq!(button.data().owns(), &[NodeId(2), NodeId(3)]);
+        assert_eq!(
+            button.owned().map(|node| node.id()).collect::<Vec<_>>(),
+            vec![NodeId(2)]
+        );
+        let menu = state.node_by_id(NodeId(2)).unwrap();
+        assert_eq!(menu.owner().map(|node| node.id()), Some(NodeId(1)));
+        assert_eq!(menu.parent_id(), Some(NodeId(0)));
+        assert!(button.owner().is_none());
+
+        let mut builder = NodeBuilder::new(Role::Window);
+        builder.set_children(vec![NodeId(1), NodeId(2)]);
+        builder.set_owns(vec![NodeId(2)]);
+        tree.update(TreeUpdate {
+            nodes: vec![(NodeId(0), builder.build(&mut classes))],
+            tree: None,
+            focus: NodeId(0),
+        });
+        let menu = tree.state().node_by_id(NodeId(2)).unwrap();
+        assert_eq!(menu.owner().map(|node| node.id()), Some(NodeId(0)));
+    }
+
+    #[test]
+    fn reverse_relations() {
+        let mut classes = NodeClassSet::new();
+        let column = |classes: &mut NodeClassSet, f: &dyn Fn(&mut NodeBuilder)| {
+            let mut builder = NodeBuilder::new(Role::Section);
+            f(&mut builder);
+            builder.build(classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2), NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+                (
+                    NodeId(1),
+                    column(&mut classes, &|builder| {
+                        builder.set_flow_to(vec![NodeId(3)]);
+                        builder.set_details(vec![NodeId(3), NodeId(9)]);
+                    }),
+                ),
+                (
+                    NodeId(2),
+                    column(&mut classes, &|builder| {
+                        builder.set_flow_to(vec![NodeId(3)]);
+                        builder.set_member_of(NodeId(3));
+                    }),
+                ),
+                (NodeId(3), column(&mut classes, &|_| ())),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let mut tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let ids = |nodes: Vec<super::Node>| nodes.iter().map(|node| node.id()).collect::<Vec<_>>();
+        let first = state.node_by_id(NodeId(1)).unwrap();
+        assert_eq!(ids(first.details().collect()), vec![NodeId(3)]);
+        let second = state.node_by_id(NodeId(2)).unwrap();
+        assert_eq!(second.member_of().map(|node| node.id()), Some(NodeId(3)));
+        let last = state.node_by_id(NodeId(3)).unwrap();
+        assert_eq!(ids(last.flow_from()), vec![NodeId(1), NodeId(2)]);
+        assert_eq!(ids(last.details_for()), vec![NodeId(1)]);
+        assert!(last.label_for().is_empty());
+
+        tree.update(TreeUpdate {
+            nodes: vec![(
+                NodeId(2),
+                column(&mut classes, &|builder| {
+                    builder.set_labelled_by(vec![NodeId(3)]);
+                }),
+            )],
+            tree: None,
+            focus: NodeId(0),
+        });
+        let last = tree.state().node_by_id(NodeId(3)).unwrap();
+        assert_eq!(ids(last.flow_from()), vec![NodeId(1)]);
+        assert_eq!(ids(last.label_for()), vec![NodeId(2)]);
+
+        tree.update(TreeUpdate {
+            nodes: vec![(NodeId(0), {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![NodeId(2), NodeId(3)]);
+                builder.build(&mut classes)
+            })],
+            tree: None,
+            focus: NodeId(0),
+        });
+        let state = tree.state();
+        let last = state.node_by_id(NodeId(3)).unwrap();
+        assert!(last.flow_from().is_empty());
+        assert!(last.details_for().is_empty());
+        assert_eq!(ids(last.label_for()), vec![NodeId(2)]);
+        assert!(state.referrers(NodeId(9)).is_none());
+    }
+
+    fn whitespace_tree(preserve_whitespace: bool) -> crate::Tree {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2), NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::StaticText);
+                    builder.set_name("  Shipping\n\n address ");
+                    builder.build(&mut classes)
+                }),
+                (NodeId(2), {
+                    let mut builder = NodeBuilder::new(Role::StaticText);
+                    builder.set_name("\t(required)\n");
+                    builder.build(&mut classes)
+                }),
+                (NodeId(3), {
+                    let mut builder = NodeBuilder::new(Role::TextInput);
+                    builder.set_labelled_by(vec![NodeId(1), NodeId(2)]);
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let mut tree = crate::Tree::new(update, false);
+        tree.set_preserve_whitespace(preserve_whitespace);
+        tree
+    }
+
+    #[test]
+    fn computed_name_whitespace_is_normalized() {
+        let tree = whitespace_tree(false);
+        let input = tree.state().node_by_id(NodeId(3)).unwrap();
+        assert_eq!(input.name().as_deref(), Some("Shipping address (required)"));
+        let label = tree.state().node_by_id(NodeId(1)).unwrap();
+        assert_eq!(label.name().as_deref(), Some("  Shipping\n\n address "));
+        assert_eq!(crate::normalize_whitespace("a\u{a0} b \r\n"), "a\u{a0} b");
+    }
+
+    #[test]
+    fn whitespace_can_be_preserved() {
+        let tree = whitespace_tree(true);
+        let input = tree.state().node_by_id(NodeId(3)).unwrap();
+        assert_eq!(
+            input.name().as_deref(),
+            Some("  Shipping\n\n address  \t(required)\n")
+        );
+    }
+
+    fn table_tree() -> crate::Tree {
+        let mut classes = NodeClassSet::new();
+        let parent = |role, children: &[NodeId]| {
+            let mut builder = NodeBuilder::new(role);
+            builder.set_children(children);
+            builder
+        };
+        let cell = |role, row, column| {
+            let mut builder = NodeBuilder::new(role);
+            builder.set_table_cell_row_index(row);
+            builder.set_table_cell_column_index(column);
+            builder
+        };
+        let mut grid = parent(Role::Grid, &[NodeId(2), NodeId(3), NodeId(4), NodeId(5)]);
+        grid.set_table_row_count(4);
+        grid.set_table_column_count(2);
+        let mut spanning_cell = cell(Role::Cell, 2, 0);
+        spanning_cell.set_table_cell_column_span(2);
+        let mut hidden_row = parent(Role::Row, &[NodeId(15)]);
+        hidden_row.set_hidden();
+        let nodes = vec![
+            (NodeId(0), parent(Role::Window, &[NodeId(1)])),
+            (NodeId(1), grid),
+            (NodeId(2), parent(Role::Row, &[NodeId(10), NodeId(11)])),
+            (NodeId(3), parent(Role::Row, &[NodeId(12), NodeId(13)])),
+            (NodeId(4), parent(Role::Row, &[NodeId(14)])),
+            (NodeId(5), hidden_row),
+            (NodeId(10), cell(Role::ColumnHeader, 0, 0)),
+            (NodeId(11), cell(Role::ColumnHeader, 0, 1)),
+            (NodeId(12), cell(Role::RowHeader, 1, 0)),
+            (NodeId(13), cell(Role::Cell, 1, 1)),
+            (NodeId(14), spanning_cell),
+            (NodeId(15), cell(Role::Cell, 3, 0)),
+        ];
+        crate::Tree::new(
+            TreeUpdate {
+                nodes: nodes
+                    .into_iter()
+                    .map(|(id, builder)| (id, builder.build(&mut classes)))
+                    .collect(),
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(0),
+            },
+            false,
+        )
+    }
+
+    #[test]
+    fn table_cell_at() {
+        let tree = table_tree();
+        let state = tree.state();
+        let grid = state.node_by_id(NodeId(1)).unwrap();
+        assert!(grid.is_table());
+        assert_eq!(grid.table_row_count(), Some(4));
+        let cell_id = |row, column| grid.table_cell_at(row, column).map(|cell| cell.id());
+        assert_eq!(cell_id(0, 1), Some(NodeId(11)));
+        assert_eq!(cell_id(1, 1), Some(NodeId(13)));
+        assert_eq!(cell_id(2, 0), Some(NodeId(14)));
+        assert_eq!(cell_id(2, 1), Some(NodeId(14)));
+        assert_eq!(cell_id(3, 0), None);
+        assert_eq!(cell_id(0, 2), None);
+        let cell = state.node_by_id(NodeId(13)).unwrap();
+        assert_eq!(
+            cell.containing_table().map(|table| table.id()),
+            Some(NodeId(1))
+        );
+    }
+
+    #[test]
+    fn table_headers() {
+        let tree = table_tree();
+        let state = tree.state();
+        let ids = |nodes: Vec<crate::Node>| nodes.iter().map(|node| node.id()).collect::<Vec<_>>();
+        let grid = state.node_by_id(NodeId(1)).unwrap();
+        assert_eq!(ids(grid.table_row_headers()), vec![NodeId(12)]);
+        assert_eq!(
+            ids(grid.table_column_headers()),
+            vec![NodeId(10), NodeId(11)]
+        );
+        let cell = state.node_by_id(NodeId(13)).unwrap();
+        assert_eq!(ids(cell.cell_row_headers()), vec![NodeId(12)]);
+        assert_eq!(ids(cell.cell_column_headers()), vec![NodeId(11)]);
+        let spanning_cell = state.node_by_id(NodeId(14)).unwrap();
+        assert_eq!(ids(spanning_cell.cell_row_headers()), vec![]);
+        assert_eq!(
+            ids(spanning_cell.cell_column_headers()),
+            vec![NodeId(10), NodeId(11)]
+        );
+    }
+
+    #[test]
+    fn effectively_disabled() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::Group);
+                    builder.set_children(vec![NodeId(2)]);
+                    builder.set_disabled();
+                    builder.build(&mut classes)
+                }),
+                (
+                    NodeId(2),
+                    NodeBuilder::new(Role::Button).build(&mut classes),
+                ),
+                (
+                    NodeId(3),
+                    NodeBuilder::new(Role::Button).build(&mut classes),
+                ),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let node = |id| state.node_by_id(NodeId(id)).unwrap();
+        assert!(node(1).is_effectively_disabled());
+        assert!(!node(2).is_disabled());
+        assert!(node(2).is_effectively_disabled());
+        assert!(node(2).detached().is_effectively_disabled(state));
+        assert!(!node(3).is_effectively_disabled());
+    }
+
+    #[test]
+    fn effectively_read_only() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(3), NodeId(4)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::Form);
+                    builder.set_children(vec![NodeId(2)]);
+                    builder.set_read_only();
+                    builder.build(&mut classes)
+                }),
+                (
+                    NodeId(2),
+                    NodeBuilder::new(Role::TextInput).build(&mut classes),
+                ),
+                (NodeId(3), {
+                    let mut builder = NodeBuilder::new(Role::TextInput);
+                    builder.set_read_only();
+                    builder.build(&mut classes)
+                }),
+                (
+                    NodeId(4),
+                    NodeBuilder::new(Role::TextInput).build(&mut classes),
+                ),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let node = |id| state.node_by_id(NodeId(id)).unwrap();
+        assert!(!node(2).is_read_only());
+        assert!(node(2).is_effectively_read_only());
+        assert!(node(2).detached().is_effectively_read_only(state));
+        assert!(node(3).is_effectively_read_only());
+        assert!(!node(4).is_effectively_read_only());
+    }
+
+    #[test]
+    fn name_from_labelled_by_cycle() {
+        const LABEL: &str = "Volume";
+
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2), NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::Slider);
+                    builder.push_labelled_by(NodeId(2));
+                    builder.build(&mut classes)
+                }),
+                (NodeId(2), {
+                    let mut builder = NodeBuilder::new(Role::Group);
+                    builder.set_labelled_by(vec![NodeId(1), NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(3), {
+                    let mut builder = NodeBuilder::new(Role::StaticText);
+                    builder.set_name(LABEL);
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        assert_eq!(
+            Some(LABEL.into()),
+            tree.state().node_by_id(NodeId(1)).unwrap().name()
+        );
+        assert_eq!(
+            Some(LABEL.into()),
+            tree.state().node_by_id(NodeId(2)).unwrap().name()
+        );
+    }
+
+    #[test]
+    fn accessible_text_overrides_name() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::Button);
+                    builder.set_name("x");
+                    builder.set_accessible_text("Close");
+                    builder.build(&mut classes)
+                }),
+                (NodeId(2), {
+                    let mut builder = NodeBuilder::new(Role::Button);
+                    builder.push_child(NodeId(3));
+                    builder.set_accessible_text("Play video");
+                    builder.build(&mut classes)
+                }),
+                (NodeId(3), {
+                    let mut builder = NodeBuilder::new(Role::Image);
+                    builder.set_name("Play");
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let close = state.node_by_id(NodeId(1)).unwrap();
+        assert_eq!(close.name(), Some("Close".into()));
+        assert_eq!(close.data().name(), Some("x"));
+        assert_eq!(
+            state.node_by_id(NodeId(2)).unwrap().name(),
+            Some("Play video".into())
+        );
+    }
+
+    #[test]
+    fn name_from_caption() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(4)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::Table);
+                    builder.set_caption(NodeId(2));
+                    builder.push_child(NodeId(2));
+                    builder.build(&mut classes)
+                }),
+                (NodeId(2), {
+                    let mut builder = NodeBuilder::new(Role::Caption);
+                    builder.push_child(NodeId(3));
+                    builder.build(&mut classes)
+                }),
+                (NodeId(3), {
+                    let mut builder = NodeBuilder::new(Role::StaticText);
+                    builder.set_name("Quarterly  results");
+                    builder.build(&mut classes)
+                }),
+                (NodeId(4), {
+                    let mut builder = NodeBuilder::new(Role::Figure);
+                    builder.set_name("Chart");
+                    builder.set_caption(NodeId(2));
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let table = state.node_by_id(NodeId(1)).unwrap();
+        assert_eq!(table.caption().unwrap().id(), NodeId(2));
+        assert_eq!(table.name(), Some("Quarterly results".into()));
+        let figure = state.node_by_id(NodeId(4)).unwrap();
+        assert_eq!(figure.name(), Some("Chart".into()));
+    }
+
+    #[test]
+    fn description_from_described_by() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2), NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::TextInput);
+                    builder.set_described_by(vec![
+                        NodeId(3),
+                        NodeId(1),
+                        NodeId(2),
+                        NodeId(3),
+                        NodeId(9),
+                    ]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(2), {
+                    let mut builder = NodeBuilder::new(Role::Paragraph);
+                    builder.push_child(NodeId(4));
+                    builder.build(&mut classes)
+                }),
+                (NodeId(3), {
+                    let mut builder = NodeBuilder::new(Role::StaticText);
+                    builder.set_name("Required.");
+                    builder.build(&mut classes)
+                }),
+                (NodeId(4), {
+                    let mut builder = NodeBuilder::new(Role::StaticText);
+                    builder.set_name("At least  8 characters.");
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(1),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let input = state.node_by_id(NodeId(1)).unwrap();
+        assert_eq!(
+            input.computed_description(),
+            Some("Required. At least 8 characters.".into())
+        );
+        assert_eq!(
+            input.detached().computed_description(),
+            input.computed_description()
+        );
+        assert_eq!(state.root().computed_description(), None);
+    }
+
     #[test]
     fn name_from_descendant_label() {
         const BUTTON_LABEL: &str = "Play";
@@ -1130,4 +2729,124 @@ mod tests {
             tree.state().node_by_id(NodeId(3)).unwrap().name()
         );
     }
+
+    #[test]
+    fn selected_items() {
+        let mut classes = NodeClassSet::new();
+        let option = |classes: &mut NodeClassSet, selected: bool| {
+            let mut builder = NodeBuilder::new(Role::ListBoxOption);
+            builder.set_selected(selected);
+            builder.build(classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(6)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::ListBox);
+                    builder.set_multiselectable();
+                    builder.set_children(vec![NodeId(2), NodeId(3), NodeId(4), NodeId(5)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(2), option(&mut classes, true)),
+                (NodeId(3), option(&mut classes, false)),
+                (NodeId(4), option(&mut classes, true)),
+                (NodeId(5), {
+                    let mut builder = NodeBuilder::new(Role::ListBox);
+                    builder.push_child(NodeId(7));
+                    builder.build(&mut classes)
+                }),
+                (NodeId(6), {
+                    let mut builder = NodeBuilder::new(Role::RadioGroup);
+                    builder.set_children(vec![NodeId(8), NodeId(9)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(7), option(&mut classes, true)),
+                (NodeId(8), {
+                    let mut builder = NodeBuilder::new(Role::RadioButton);
+                    builder.set_checked(Checked::False);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(9), {
+                    let mut builder = NodeBuilder::new(Role::RadioButton);
+                    builder.set_checked(Checked::True);
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let selected = |id| {
+            state
+                .node_by_id(id)
+                .unwrap()
+                .selected_items()
+                .iter()
+                .map(|item| item.id())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(selected(NodeId(1)), vec![NodeId(2), NodeId(4)]);
+        assert_eq!(selected(NodeId(5)), vec![NodeId(7)]);
+        assert_eq!(selected(NodeId(6)), vec![NodeId(9)]);
+        let unselected = state.node_by_id(NodeId(3)).unwrap();
+        assert!(unselected.is_selectable_item());
+        assert!(!unselected.is_selected_item());
+        assert!(!state.root().is_selectable_item());
+        let list = state.node_by_id(NodeId(1)).unwrap();
+        assert!(list.is_multiselectable());
+        assert!(!state.node_by_id(NodeId(5)).unwrap().is_multiselectable());
+        assert_eq!(
+            state
+                .node_by_id(NodeId(7))
+                .unwrap()
+                .selection_container()
+                .map(|container| container.id()),
+            Some(NodeId(5))
+        );
+        assert!(state.root().selection_container().is_none());
+    }
+
+    #[test]
+    fn checked_state_text() {
+        assert_eq!(super::checked_state_text(Checked::False, None), "unchecked");
+        assert_eq!(super::checked_state_text(Checked::True, None), "checked");
+        assert_eq!(
+            super::checked_state_text(Checked::Mixed, Some("en")),
+            "mixed"
+        );
+
+        let mut classes = NodeClassSet::new();
+        let check_box = |classes: &mut NodeClassSet, description: Option<&str>| {
+            let mut builder = NodeBuilder::new(Role::CheckBox);
+            builder.set_checked(Checked::Mixed);
+            if let Some(description) = description {
+                builder.set_state_description(description);
+            }
+            builder.build(classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), check_box(&mut classes, None)),
+                (NodeId(2), check_box(&mut classes, Some("some selected"))),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = crate::Tree::new(update, false);
+        let state = tree.state();
+        let text = |id| state.node_by_id(id).unwrap().state().checked_state_text();
+        assert_eq!(text(NodeId(1)), Some("mixed"));
+        assert_eq!(text(NodeId(2)), Some("some selected"));
+        assert_eq!(text(NodeId(0)), None);
+    }
 }
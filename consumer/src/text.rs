@@ -573,31 +573,9 @@ impl<'a> Range<'a> {
     pub fn bounding_boxes(&self) -> Vec<Rect> {
         let mut result = Vec::new();
         self.walk(|node| {
-            let mut rect = match node.data().bounds() {
-                Some(rect) => rect,
-                None => {
-                    return Some(Vec::new());
-                }
-            };
-            let positions = match node.data().character_positions() {
-                Some(positions) => positions,
-                None => {
-                    return Some(Vec::new());
-                }
-            };
-            let widths = match node.data().character_widths() {
-                Some(widths) => widths,
-                None => {
-                    return Some(Vec::new());
-                }
-            };
-            let direction = match node.data().text_direction() {
-                Some(direction) => direction,
-                None => {
-                    return Some(Vec::new());
-                }
-            };
-            let character_lengths = node.data().character_lengths();
+            if node.data().is_bounds_unknown() {
+                return Some(Vec::new());
+            }
             let start_index = if node.id() == self.start.node.id() {
                 self.start.character_index
             } else {
@@ -606,47 +584,14 @@ impl<'a> Range<'a> {
             let end_index = if node.id() == self.end.node.id() {
                 self.end.character_index
             } else {
-                character_lengths.len()
+                node.data().character_lengths().len()
             };
-            if start_index != 0 || end_index != character_lengths.len() {
-                let pixel_start = if start_index < character_lengths.len() {
-                    positions[start_index]
-                } else {
-                    positions[start_index - 1] + widths[start_index - 1]
-                };
-                let pixel_end = if end_index == start_index {
-                    pixel_start
-                } else {
-                    positions[end_index - 1] + widths[end_index - 1]
-                };
-                let pixel_start = f64::from(pixel_start);
-                let pixel_end = f64::from(pixel_end);
-                match direction {
-                    TextDirection::LeftToRight => {
-                        let orig_left = rect.x0;
-                        rect.x0 = orig_left + pixel_start;
-                        rect.x1 = orig_left + pixel_end;
-                    }
-                    TextDirection::RightToLeft => {
-                        let orig_right = rect.x1;
-                        rect.x1 = orig_right - pixel_start;
-                        rect.x0 = orig_right - pixel_end;
-                    }
-                    // Note: The following directions assume that the rectangle,
-                    // in the node's coordinate space, is y-down. TBD: Will we
-                    // ever encounter a case where this isn't true?
-                    TextDirection::TopToBottom => {
-                        let orig_top = rect.y0;
-                        rect.y0 = orig_top + pixel_start;
-                        rect.y1 = orig_top + pixel_end;
-                    }
-                    TextDirection::BottomToTop => {
-                        let orig_bottom = rect.y1;
-                        rect.y1 = orig_bottom - pixel_start;
-                        rect.y0 = orig_bottom - pixel_end;
-                    }
+            let rect = match node.data().character_range_bounds(start_index, end_index) {
+                Some(rect) => rect,
+                None => {
+                    return Some(Vec::new());
                 }
-            }
+            };
             result.push(node.transform().transform_rect_bbox(rect));
             None
         })
@@ -774,7 +719,7 @@ fn text_node_filter(root_id: NodeId, node: &Node) -> FilterResult {
 fn character_index_at_point(node: &Node, point: Point) -> usize {
     // We know the node has a bounding rectangle because it was returned
     // by a hit test.
-    let rect = node.data().bounds().unwrap();
+    let rect = node.raw_bounds().unwrap();
     let character_lengths = node.data().character_lengths();
     let positions = match node.data().character_positions() {
         Some(positions) => positions,
@@ -983,23 +928,38 @@ impl<'a> Node<'a> {
     }
 
     pub fn text_position_from_global_utf16_index(&self, index: usize) -> Option<Position> {
+        self.text_position_from_global_index(index, char::len_utf16)
+    }
+
+    /// Returns the position at the given index, counted in Unicode scalar
+    /// values, within the text of this node. An index inside a character
+    /// resolves to the start of that character.
+    pub fn text_position_from_global_usv_index(&self, index: usize) -> Option<Position<'_>> {
+        self.text_position_from_global_index(index, |_| 1)
+    }
+
+    fn text_position_from_global_index(
+        &self,
+        index: usize,
+        unit_length: impl Fn(char) -> usize,
+    ) -> Option<Position<'_>> {
         let mut total_length = 0usize;
         for node in self.inline_text_boxes() {
             let node_text = node.data().value().unwrap();
-            let node_text_length = node_text.chars().map(char::len_utf16).sum::<usize>();
+            let node_text_length = node_text.chars().map(&unit_length).sum::<usize>();
             let new_total_length = total_length + node_text_length;
             if index >= total_length && index < new_total_length {
                 let index = index - total_length;
                 let mut utf8_length = 0usize;
-                let mut utf16_length = 0usize;
+                let mut unit_length_so_far = 0usize;
                 for (character_index, utf8_char_length) in
                     node.data().character_lengths().iter().enumerate()
                 {
                     let new_utf8_length = utf8_length + (*utf8_char_length as usize);
                     let char_str = &node_text[utf8_length..new_utf8_length];
-                    let utf16_char_length = char_str.chars().map(char::len_utf16).sum::<usize>();
-                    let new_utf16_length = utf16_length + utf16_char_length;
-                    if index >= utf16_length && index < new_utf16_length {
+                    let char_length = char_str.chars().map(&unit_length).sum::<usize>();
+                    let new_unit_length = unit_length_so_far + char_length;
+                    if index >= unit_length_so_far && index < new_unit_length {
                         return Some(Position {
                             root_node: *self,
                             inner: InnerPosition {
@@ -1009,7 +969,7 @@ impl<'a> Node<'a> {
                         });
                     }
                     utf8_length = new_utf8_length;
-                    utf16_length = new_utf16_length;
+                    unit_length_so_far = new_unit_length;
                 }
                 panic!("index out of range");
             }
@@ -1839,4 +1799,37 @@ mod tests {
 
         assert!(node.text_position_from_global_utf16_index(98).is_none());
     }
+
+    #[test]
+    fn text_position_from_global_usv_index() {
+        let tree = main_multiline_tree(None);
+        let state = tree.state();
+        let node = state.node_by_id(NodeId(1)).unwrap();
+
+        {
+            let pos = node.text_position_from_global_usv_index(0).unwrap();
+            assert!(pos.is_document_start());
+        }
+
+        {
+            let pos = node.text_position_from_global_usv_index(94).unwrap();
+            let mut range = pos.to_degenerate_range();
+            range.set_end(pos.forward_to_character_end());
+            assert_eq!(range.text(), "\u{1f60a}");
+        }
+
+        {
+            let pos = node.text_position_from_global_usv_index(95).unwrap();
+            let mut range = pos.to_degenerate_range();
+            range.set_end(pos.forward_to_character_end());
+            assert_eq!(range.text(), "\n");
+        }
+
+        {
+            let pos = node.text_position_from_global_usv_index(96).unwrap();
+            assert!(pos.is_document_end());
+        }
+
+        assert!(node.text_position_from_global_usv_index(97).is_none());
+    }
 }
@@ -3,7 +3,7 @@
 // the LICENSE-APACHE file) or the MIT license (found in
 // the LICENSE-MIT file), at your option.
 
-use accesskit::{Live, Node as NodeData, NodeId, Tree as TreeData, TreeUpdate};
+use accesskit::{Live, Node as NodeData, NodeId, Role, Tree as TreeData, TreeUpdate};
 use std::collections::{HashMap, HashSet};
 
 use crate::node::{DetachedNode, Node, NodeState, ParentAndIndex};
@@ -14,6 +14,84 @@ pub struct State {
     pub(crate) data: TreeData,
     focus: NodeId,
     is_host_focused: bool,
+    max_announcements: Option<usize>,
+    preserve_whitespace: bool,
+    /// The reverse index of the relation properties, so that each relation
+    /// can be followed backwards without scanning the tree. It's updated
+    /// only for the nodes that an update adds, changes or removes.
+    referrers: HashMap<NodeId, Referrers>,
+}
+
+/// The nodes that refer to a node through each relation property,
+/// in ascending order of ID.
+#[derive(Clone, Default)]
+pub(crate) struct Referrers {
+    pub(crate) labelled_by: Vec<NodeId>,
+    pub(crate) described_by: Vec<NodeId>,
+    pub(crate) controls: Vec<NodeId>,
+    pub(crate) details: Vec<NodeId>,
+    pub(crate) flow_to: Vec<NodeId>,
+    pub(crate) owns: Vec<NodeId>,
+}
+
+type Relation = (
+    fn(&NodeData) -> &[NodeId],
+    fn(&mut Referrers) -> &mut Vec<NodeId>,
+);
+
+const RELATIONS: [Relation; 6] = [
+    (NodeData::labelled_by, |referrers| {
+        &mut referrers.labelled_by
+    }),
+    (NodeData::described_by, |referrers| {
+        &mut referrers.described_by
+    }),
+    (NodeData::controls, |referrers| &mut referrers.controls),
+    (NodeData::details, |referrers| &mut referrers.details),
+    (NodeData::flow_to, |referrers| &mut referrers.flow_to),
+    (NodeData::owns, |referrers| &mut referrers.owns),
+];
+
+impl Referrers {
+    fn is_empty(&self) -> bool {
+        self.labelled_by.is_empty()
+            && self.described_by.is_empty()
+            && self.controls.is_empty()
+            && self.details.is_empty()
+            && self.flow_to.is_empty()
+            && self.owns.is_empty()
+    }
+}
+
+/// Adds the relations of the given node to the reverse index,
+/// keeping each list of referrers sorted.
+fn index_referrer(index: &mut HashMap<NodeId, Referrers>, id: NodeId, data: &NodeData) {
+    for (targets, referrers) in RELATIONS {
+        for target in targets(data) {
+            let list = referrers(index.entry(*target).or_default());
+            if let Err(position) = list.binary_search_by_key(&id.0, |id| id.0) {
+                list.insert(position, id);
+            }
+        }
+    }
+}
+
+/// Removes the relations of the given node from the reverse index.
+fn unindex_referrer(index: &mut HashMap<NodeId, Referrers>, id: NodeId, data: &NodeData) {
+    for (targets, referrers) in RELATIONS {
+        for target in targets(data) {
+            let Some(entry) = index.get_mut(target) else {
+                continue;
+            };
+            let list = referrers(entry);
+            if let Ok(position) = list.binary_search_by_key(&id.0, |id| id.0) {
+                list.remove(position);
+            }
+            if entry.is_empty() {
+                index.remove(target);
+            }
+        }
+    }
 }
 
 struct InternalFocusChange {
@@ -35,6 +113,78 @@ impl State {
         assert!(self.nodes.contains_key(&self.focus));
     }
 
+    /// Checks that an update can be applied to this state, returning a
+    /// description of the first problem found.
+    #[cfg_attr(
+        not(all(feature = "debug-validation", debug_assertions)),
+        allow(dead_code)
+    )]
+    fn validate_update(&self, update: &TreeUpdate) -> Result<(), String> {
+        let in_update = update
+            .nodes
+            .iter()
+            .map(|(id, _)| *id)
+            .collect::<HashSet<_>>();
+        let mut parents = HashMap::new();
+        for (id, node) in &update.nodes {
+            let character_lengths = node.character_lengths();
+            if node.role() == Role::InlineTextBox || !character_lengths.is_empty() {
+                let text_length = node.value().map_or(0, str::len);
+                let sum = character_lengths
+                    .iter()
+                    .map(|length| usize::from(*length))
+                    .sum::<usize>();
+                if sum != text_length {
+                    return Err(format!(
+                        "node {:?} has character lengths summing to {} bytes, but its value is {} bytes long",
+                        id, sum, text_length
+                    ));
+                }
+            }
+            let mut seen = HashSet::new();
+            for child in node.children() {
+                if !seen.insert(child) {
+                    return Err(format!(
+                        "node {:?} lists child {:?} more than once",
+                        id, child
+                    ));
+                }
+                if !in_update.contains(child) && !self.nodes.contains_key(child) {
+                    return Err(format!(
+                        "node {:?} has child {:?}, which is neither in the tree nor in the update",
+                        id, child
+                    ));
+                }
+                if let Some(other) = parents.insert(*child, *id) {
+                    return Err(format!(
+                        "node {:?} is a child of both {:?} and {:?}",
+                        child, other, id
+                    ));
+                }
+            }
+        }
+        let root = update
+            .tree
+            .as_ref()
+            .map_or(self.data.root, |tree| tree.root);
+        if !in_update.contains(&root) && !self.nodes.contains_key(&root) {
+            return Err(format!(
+                "the root {:?} is neither in the tree nor in the update",
+                root
+            ));
+        }
+        if let Some(parent) = parents.get(&root) {
+            return Err(format!("the root {:?} is a child of {:?}", root, parent));
+        }
+        if !in_update.contains(&update.focus) && !self.nodes.contains_key(&update.focus) {
+            return Err(format!(
+                "the focus {:?} is neither in the tree nor in the update",
+                update.focus
+            ));
+        }
+        Ok(())
+    }
+
     fn update(
         &mut self,
         update: TreeUpdate,
@@ -52,6 +202,11 @@ impl State {
             }
         }
 
+        #[cfg(all(feature = "debug-validation", debug_assertions))]
+        if let Err(error) = self.validate_update(&update) {
+            panic!("invalid TreeUpdate: {}", error);
+        }
+
         let mut orphans = HashSet::new();
         let old_focus_id = self.is_host_focused.then_some(self.focus);
         let old_root_id = self.data.root;
@@ -66,14 +221,23 @@ impl State {
         let root = self.data.root;
         let mut pending_nodes: HashMap<NodeId, _> = HashMap::new();
         let mut pending_children = HashMap::new();
+        // A child that moves to a new parent must not be treated as
+        // an orphan when its old parent is processed after the new one.
+        let new_child_ids = update
+            .nodes
+            .iter()
+            .flat_map(|(_, node)| node.children().iter().copied())
+            .collect::<HashSet<_>>();
 
         fn add_node(
             nodes: &mut HashMap<NodeId, NodeState>,
+            referrers: &mut HashMap<NodeId, Referrers>,
             changes: &mut Option<&mut InternalChanges>,
             parent_and_index: Option<ParentAndIndex>,
             id: NodeId,
             data: NodeData,
         ) {
+            index_referrer(referrers, id, &data);
             let state = NodeState {
                 id,
                 parent_and_index,
@@ -100,6 +264,7 @@ impl State {
                 } else if let Some(child_data) = pending_nodes.remove(child_id) {
                     add_node(
                         &mut self.nodes,
+                        &mut self.referrers,
                         &mut changes,
                         Some(parent_and_index),
                         *child_id,
@@ -116,21 +281,31 @@ impl State {
                     node_state.parent_and_index = None;
                 }
                 for child_id in node_state.data.children().iter() {
-                    if !seen_child_ids.contains(child_id) {
+                    if !new_child_ids.contains(child_id) {
                         orphans.insert(*child_id);
                     }
                 }
+                unindex_referrer(&mut self.referrers, node_id, &node_state.data);
+                index_referrer(&mut self.referrers, node_id, &node_data);
                 node_state.data = node_data;
             } else if let Some(parent_and_index) = pending_children.remove(&node_id) {
                 add_node(
                     &mut self.nodes,
+                    &mut self.referrers,
                     &mut changes,
                     Some(parent_and_index),
                     node_id,
                     node_data,
                 );
             } else if node_id == root {
-                add_node(&mut self.nodes, &mut changes, None, node_id, node_data);
+                add_node(
+                    &mut self.nodes,
+                    &mut self.referrers,
+                    &mut changes,
+                    None,
+                    node_id,
+                    node_data,
+                );
             } else {
                 pending_nodes.insert(node_id, node_data);
             }
@@ -178,12 +353,14 @@ impl State {
 
             for id in to_remove {
                 if let Some(old_node_state) = self.nodes.remove(&id) {
+                    unindex_referrer(&mut self.referrers, id, &old_node_state.data);
                     if let Some(changes) = &mut changes {
                         let old_node = DetachedNode {
                             state: old_node_state,
                             is_focused: old_focus_id == Some(id),
                             is_root: old_root_id == id,
                             name: None,
+                            description: None,
                             value: None,
                             live: Live::Off,
                             supports_text_ranges: false,
@@ -197,6 +374,10 @@ impl State {
         self.validate_global();
     }
 
+    pub(crate) fn referrers(&self, id: NodeId) -> Option<&Referrers> {
+        self.referrers.get(&id)
+    }
+
     fn update_host_focus_state(
         &mut self,
         is_host_focused: bool,
@@ -270,6 +451,111 @@ impl State {
     pub fn toolkit_version(&self) -> Option<String> {
         self.data.toolkit_version.clone()
     }
+
+    pub fn max_announcements(&self) -> Option<usize> {
+        self.max_announcements
+    }
+
+    pub fn preserves_whitespace(&self) -> bool {
+        self.preserve_whitespace
+    }
+
+    /// Whether the application as a whole is busy, for example while it
+    /// is starting up or loading a document, so the tree may be incomplete.
+    /// This is the busy state of the root node; platform adapters that have
+    /// an application object report it as that object's busy state.
+    pub fn is_busy(&self) -> bool {
+        self.root().is_busy()
+    }
+
+    /// Returns the politeness of the live region that the given node belongs
+    /// to, which is the nearest live setting other than [`Live::Off`]
+    /// on the node or one of its ancestors. Returns [`Live::Off`] if there
+    /// is no such live region, or if the node isn't in the tree.
+    pub fn resolved_live(&self, id: NodeId) -> Live {
+        let mut current = self.node_by_id(id);
+        while let Some(node) = current {
+            match node.state().explicit_or_implied_live() {
+                Some(Live::Off) | None => current = node.parent(),
+                Some(live) => return live,
+            }
+        }
+        Live::Off
+    }
+
+    /// Returns the visible text of the subtree rooted at the given node.
+    /// Hidden subtrees are skipped. Text comes from the name of each leaf,
+    /// or its value if it has no name, and the content of line-breaking
+    /// objects is separated from the surrounding text by a line break.
+    pub fn text_content(&self, id: NodeId) -> String {
+        fn traverse(state: &State, id: NodeId, text: &mut String, pending_break: &mut bool) {
+            let Some(node) = state.nodes.get(&id) else {
+                return;
+            };
+            let data = &node.data;
+            if data.is_hidden() {
+                return;
+            }
+            let is_line_breaking_object = data.is_line_breaking_object();
+            if is_line_breaking_object {
+                *pending_break = true;
+            }
+            let children = data.children();
+            if children.is_empty() {
+                if let Some(leaf_text) = data.name().or_else(|| data.value()) {
+                    if !leaf_text.is_empty() {
+                        if *pending_break && !text.is_empty() {
+                            text.push('\n');
+                        }
+                        *pending_break = false;
+                        text.push_str(leaf_text);
+                    }
+                }
+            } else {
+                for child_id in children.iter() {
+                    traverse(state, *child_id, text, pending_break);
+                }
+            }
+            if is_line_breaking_object {
+                *pending_break = true;
+            }
+        }
+
+        let mut text = String::new();
+        traverse(self, id, &mut text, &mut false);
+        text
+    }
+
+    /// Returns the nodes whose [role requires a name] but whose computed
+    /// [name] is missing or blank, in depth-first order from the root.
+    /// Hidden subtrees are skipped, so decorative images that are hidden
+    /// aren't reported. This is meant for auditing trees.
+    ///
+    /// [role requires a name]: accesskit::Role::requires_name
+    /// [name]: Node::name
+    pub fn unnamed_required(&self) -> Vec<NodeId> {
+        let mut result = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![self.root_id()];
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            let Some(node) = self.node_by_id(id) else {
+                continue;
+            };
+            if node.is_hidden() {
+                continue;
+            }
+            if node.role().requires_name()
+                && node.name().map_or(true, |name| name.trim().is_empty())
+            {
+                result.push(id);
+            }
+            stack.extend(node.child_ids().rev());
+        }
+        result
+    }
 }
 
 pub trait ChangeHandler {
@@ -287,10 +573,39 @@ pub trait ChangeHandler {
     ///
     /// * [`DetachedNode::name`]
     /// * [`DetachedNode::live`]
+    /// * [`DetachedNode::is_effectively_disabled`]
+    /// * [`DetachedNode::is_effectively_read_only`]
     /// * [`DetachedNode::supports_text_ranges`]
     fn node_removed(&mut self, node: &DetachedNode, current_state: &State);
 }
 
+/// Decides which live region announcements an adapter makes while
+/// processing a single update. Announcements that repeat the text of
+/// an earlier one are dropped, as are announcements beyond the limit
+/// set with [`Tree::set_max_announcements`]. Adapters should use
+/// a new filter for each update.
+#[derive(Default)]
+pub struct AnnouncementFilter {
+    announced: Vec<String>,
+}
+
+impl AnnouncementFilter {
+    /// Returns whether the given node should announce the given text,
+    /// and if so, records the announcement.
+    pub fn allow(&mut self, node: &Node, text: &str) -> bool {
+        if self.announced.iter().any(|announced| announced == text) {
+            return false;
+        }
+        if let Some(max) = node.tree_state.max_announcements() {
+            if self.announced.len() >= max {
+                return false;
+            }
+        }
+        self.announced.push(text.into());
+        true
+    }
+}
+
 pub struct Tree {
     state: State,
 }
@@ -302,6 +617,9 @@ impl Tree {
             data: initial_state.tree.take().unwrap(),
             focus: initial_state.focus,
             is_host_focused,
+            max_announcements: None,
+            preserve_whitespace: false,
+            referrers: HashMap::new(),
         };
         state.update(initial_state, is_host_focused, None);
         Self { state }
@@ -311,6 +629,25 @@ impl Tree {
         self.state.update(update, self.state.is_host_focused, None);
     }
 
+    /// Sets the maximum number of live region announcements that platform
+    /// adapters will make in response to a single update. Announcements
+    /// beyond this limit are dropped, which keeps a bulk change to many
+    /// live regions from flooding the screen reader. If this is `None`,
+    /// which is the default, there is no limit. Either way, an announcement
+    /// with the same text as an earlier one in the same update is dropped.
+    pub fn set_max_announcements(&mut self, value: Option<usize>) {
+        self.state.max_announcements = value;
+    }
+
+    /// Sets whether names that are computed from other nodes, such as names
+    /// concatenated from several labels, keep their whitespace exactly
+    /// as provided. By default, like browsers do when computing accessible
+    /// names, runs of ASCII whitespace are collapsed into a single space
+    /// and leading and trailing whitespace is removed.
+    pub fn set_preserve_whitespace(&mut self, value: bool) {
+        self.state.preserve_whitespace = value;
+    }
+
     pub fn update_and_process_changes(
         &mut self,
         update: TreeUpdate,
@@ -385,7 +722,7 @@ impl Tree {
 
 #[cfg(test)]
 mod tests {
-    use accesskit::{NodeBuilder, NodeClassSet, NodeId, Role, Tree, TreeUpdate};
+    use accesskit::{Live, NodeBuilder, NodeClassSet, NodeId, Role, Tree, TreeUpdate};
 
     #[test]
     fn init_tree_with_root_node() {
@@ -404,6 +741,110 @@ mod tests {
         assert!(tree.state().root().parent().is_none());
     }
 
+    fn window_with_dangling_child(classes: &mut NodeClassSet) -> TreeUpdate {
+        let mut builder = NodeBuilder::new(Role::Window);
+        builder.set_children(vec![NodeId(1)]);
+        TreeUpdate {
+            nodes: vec![(NodeId(0), builder.build(classes))],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        }
+    }
+
+    #[test]
+    fn validate_update() {
+        let mut classes = NodeClassSet::new();
+        let tree = super::Tree::new(
+            TreeUpdate {
+                nodes: vec![(
+                    NodeId(0),
+                    NodeBuilder::new(Role::Window).build(&mut classes),
+                )],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(0),
+            },
+            false,
+        );
+        let valid = TreeUpdate {
+            nodes: vec![(
+                NodeId(0),
+                NodeBuilder::new(Role::Window).build(&mut classes),
+            )],
+            tree: None,
+            focus: NodeId(0),
+        };
+        assert_eq!(tree.state().validate_update(&valid), Ok(()));
+        assert_eq!(
+            tree.state()
+                .validate_update(&window_with_dangling_child(&mut classes)),
+            Err(
+                "node NodeId(0) has child NodeId(1), which is neither in the tree nor in the update"
+                    .into()
+            )
+        );
+        let mut moved_focus = valid;
+        moved_focus.focus = NodeId(2);
+        assert_eq!(
+            tree.state().validate_update(&moved_focus),
+            Err("the focus NodeId(2) is neither in the tree nor in the update".into())
+        );
+    }
+
+    fn window_with_text(value: &str, character_lengths: &[u8]) -> TreeUpdate {
+        let mut classes = NodeClassSet::new();
+        TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::InlineTextBox);
+                    builder.set_value(value);
+                    builder.set_character_lengths(character_lengths);
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        }
+    }
+
+    #[test]
+    fn validate_character_lengths() {
+        let tree = super::Tree::new(window_with_text("", &[]), false);
+        let state = tree.state();
+        assert_eq!(
+            state.validate_update(&window_with_text("na\u{ef}ve", &[1, 1, 2, 1, 1])),
+            Ok(())
+        );
+        assert_eq!(
+            state.validate_update(&window_with_text("na\u{ef}ve", &[1, 1, 1, 1, 1])),
+            Err(
+                "node NodeId(1) has character lengths summing to 5 bytes, but its value is 6 bytes long"
+                    .into()
+            )
+        );
+        assert_eq!(
+            state.validate_update(&window_with_text("text", &[])),
+            Err(
+                "node NodeId(1) has character lengths summing to 0 bytes, but its value is 4 bytes long"
+                    .into()
+            )
+        );
+    }
+
+    #[cfg(all(feature = "debug-validation", debug_assertions))]
+    #[test]
+    #[should_panic(
+        expected = "invalid TreeUpdate: node NodeId(0) has child NodeId(1), which is neither in the tree nor in the update"
+    )]
+    fn debug_validation_rejects_dangling_child() {
+        let mut classes = NodeClassSet::new();
+        super::Tree::new(window_with_dangling_child(&mut classes), false);
+    }
+
     #[test]
     fn root_node_has_children() {
         let mut classes = NodeClassSet::new();
@@ -439,6 +880,55 @@ mod tests {
         assert_eq!(2, state.root().children().count());
     }
 
+    #[test]
+    fn move_child_to_earlier_parent() {
+        let mut classes = NodeClassSet::new();
+        let mut node = |role, children: &[NodeId]| {
+            let mut builder = NodeBuilder::new(role);
+            builder.set_children(children);
+            builder.build(&mut classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), node(Role::Window, &[NodeId(1), NodeId(2)])),
+                (NodeId(1), node(Role::Group, &[NodeId(3), NodeId(4)])),
+                (NodeId(2), node(Role::Group, &[NodeId(5)])),
+                (NodeId(3), node(Role::Button, &[])),
+                (NodeId(4), node(Role::Button, &[])),
+                (NodeId(5), node(Role::Button, &[])),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let mut tree = super::Tree::new(update, false);
+        // The new parent comes before the old one in the update.
+        tree.update(TreeUpdate {
+            nodes: vec![
+                (
+                    NodeId(2),
+                    node(Role::Group, &[NodeId(4), NodeId(5), NodeId(3)]),
+                ),
+                (NodeId(1), node(Role::Group, &[])),
+            ],
+            tree: None,
+            focus: NodeId(0),
+        });
+        let state = tree.state();
+        let child_ids = |id| {
+            state
+                .node_by_id(id)
+                .unwrap()
+                .children()
+                .map(|child| child.id())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(child_ids(NodeId(1)), vec![]);
+        assert_eq!(child_ids(NodeId(2)), vec![NodeId(4), NodeId(5), NodeId(3)]);
+        let moved = state.node_by_id(NodeId(3)).unwrap();
+        assert_eq!(moved.parent_id(), Some(NodeId(2)));
+        assert_eq!(moved.parent_and_index().map(|(_, index)| index), Some(2));
+    }
+
     #[test]
     fn add_child_to_root_node() {
         let mut classes = NodeClassSet::new();
@@ -775,4 +1265,152 @@ mod tests {
             tree.state().node_by_id(NodeId(1)).unwrap().name()
         );
     }
+
+    #[test]
+    fn text_content() {
+        let mut classes = NodeClassSet::new();
+        let text = |classes: &mut NodeClassSet, name| {
+            let mut builder = NodeBuilder::new(Role::StaticText);
+            builder.set_name(name);
+            builder.build(classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Document);
+                    builder.set_children(vec![NodeId(1), NodeId(7)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::Paragraph);
+                    builder.set_is_line_breaking_object();
+                    builder.set_children(vec![
+                        NodeId(2),
+                        NodeId(3),
+                        NodeId(5),
+                        NodeId(6),
+                        NodeId(8),
+                    ]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(2), text(&mut classes, "Hello ")),
+                (NodeId(3), {
+                    let mut builder = NodeBuilder::new(Role::GenericContainer);
+                    builder.set_hidden();
+                    builder.set_children(vec![NodeId(4)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(4), text(&mut classes, "secret ")),
+                (NodeId(5), text(&mut classes, "world")),
+                (NodeId(6), {
+                    let mut builder = NodeBuilder::new(Role::LineBreak);
+                    builder.set_is_line_breaking_object();
+                    builder.build(&mut classes)
+                }),
+                (NodeId(8), text(&mut classes, "again")),
+                (NodeId(7), {
+                    let mut builder = NodeBuilder::new(Role::Paragraph);
+                    builder.set_is_line_breaking_object();
+                    builder.set_children(vec![NodeId(9)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(9), text(&mut classes, "Second paragraph")),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = super::Tree::new(update, false);
+        assert_eq!(
+            "Hello world\nagain\nSecond paragraph",
+            tree.state().text_content(NodeId(0))
+        );
+        assert_eq!("Hello world\nagain", tree.state().text_content(NodeId(1)));
+        assert_eq!("", tree.state().text_content(NodeId(3)));
+    }
+
+    #[test]
+    fn unnamed_required() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2), NodeId(3), NodeId(5)]);
+                    builder.build(&mut classes)
+                }),
+                (
+                    NodeId(1),
+                    NodeBuilder::new(Role::Button).build(&mut classes),
+                ),
+                (NodeId(2), {
+                    let mut builder = NodeBuilder::new(Role::Image);
+                    builder.set_hidden();
+                    builder.build(&mut classes)
+                }),
+                (NodeId(3), {
+                    let mut builder = NodeBuilder::new(Role::Button);
+                    builder.push_child(NodeId(4));
+                    builder.build(&mut classes)
+                }),
+                (NodeId(4), {
+                    let mut builder = NodeBuilder::new(Role::StaticText);
+                    builder.set_name("Save");
+                    builder.build(&mut classes)
+                }),
+                (NodeId(5), {
+                    let mut builder = NodeBuilder::new(Role::Image);
+                    builder.set_name(" ");
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = super::Tree::new(update, false);
+        assert_eq!(tree.state().unnamed_required(), vec![NodeId(1), NodeId(5)]);
+    }
+
+    #[test]
+    fn resolved_live() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(4)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::Status);
+                    builder.set_live(Live::Polite);
+                    builder.set_children(vec![NodeId(2)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(2), {
+                    let mut builder = NodeBuilder::new(Role::Group);
+                    builder.set_live(Live::Off);
+                    builder.set_children(vec![NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+                (
+                    NodeId(3),
+                    NodeBuilder::new(Role::StaticText).build(&mut classes),
+                ),
+                (NodeId(4), {
+                    let mut builder = NodeBuilder::new(Role::Alert);
+                    builder.set_live(Live::Assertive);
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = super::Tree::new(update, false);
+        let state = tree.state();
+        assert_eq!(Live::Assertive, state.resolved_live(NodeId(4)));
+        assert_eq!(Live::Polite, state.resolved_live(NodeId(1)));
+        assert_eq!(Live::Polite, state.resolved_live(NodeId(3)));
+        assert_eq!(Live::Off, state.resolved_live(NodeId(0)));
+        assert_eq!(Live::Off, state.resolved_live(NodeId(5)));
+    }
 }
@@ -48,24 +48,32 @@ impl Adapter {
         }
     }
 
+    /// Set the maximum number of live region announcements made in response
+    /// to a single update, or `None`, the default, for no limit. Refer to
+    /// [`accesskit_consumer::Tree::set_max_announcements`] for details.
+    pub fn set_max_announcements(&self, value: Option<usize>) {
+        let mut tree = self.context.tree.borrow_mut();
+        tree.set_max_announcements(value);
+    }
+
     /// Apply the provided update to the tree.
     ///
     /// The caller must call [`QueuedEvents::raise`] on the return value.
     pub fn update(&self, update: TreeUpdate) -> QueuedEvents {
-        let mut event_generator = EventGenerator::new(self.context.clone());
+        let mut event_generator = EventGenerator::new();
         let mut tree = self.context.tree.borrow_mut();
         tree.update_and_process_changes(update, &mut event_generator);
-        event_generator.into_result()
+        event_generator.into_result(self.context.clone())
     }
 
     /// Update the tree state based on whether the window is focused.
     ///
     /// The caller must call [`QueuedEvents::raise`] on the return value.
     pub fn update_view_focus_state(&self, is_focused: bool) -> QueuedEvents {
-        let mut event_generator = EventGenerator::new(self.context.clone());
+        let mut event_generator = EventGenerator::new();
         let mut tree = self.context.tree.borrow_mut();
         tree.update_host_focus_state_and_process_changes(is_focused, &mut event_generator);
-        event_generator.into_result()
+        event_generator.into_result(self.context.clone())
     }
 
     pub fn view_children(&self) -> *mut NSArray<NSObject> {
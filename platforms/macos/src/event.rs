@@ -4,10 +4,12 @@
 // the LICENSE-MIT file), at your option.
 
 use accesskit::{Live, NodeId, Role};
-use accesskit_consumer::{DetachedNode, FilterResult, Node, TreeChangeHandler, TreeState};
+use accesskit_consumer::{
+    AnnouncementFilter, DetachedNode, FilterResult, Node, TreeChangeHandler, TreeState,
+};
 use icrate::{
     AppKit::*,
-    Foundation::{NSMutableDictionary, NSNumber, NSString},
+    Foundation::{NSArray, NSMutableDictionary, NSNumber, NSString},
 };
 use objc2::runtime::{AnyObject, ProtocolObject};
 use std::{collections::HashSet, rc::Rc};
@@ -26,6 +28,7 @@ pub(crate) enum QueuedEvent {
         notification: &'static NSAccessibilityNotificationName,
     },
     NodeDestroyed(NodeId),
+    LayoutChanged(Vec<NodeId>),
     Announcement {
         text: String,
         priority: NSAccessibilityPriorityLevel,
@@ -63,6 +66,42 @@ impl QueuedEvent {
                     };
                 }
             }
+            Self::LayoutChanged(node_ids) => {
+                let view = match context.view.load() {
+                    Some(view) => view,
+                    None => {
+                        return;
+                    }
+                };
+
+                let window = match view.window() {
+                    Some(window) => window,
+                    None => {
+                        return;
+                    }
+                };
+
+                let elements = node_ids
+                    .into_iter()
+                    .map(|node_id| context.get_or_create_platform_node(node_id))
+                    .collect::<Vec<_>>();
+                let elements = NSArray::from_vec(elements);
+                let mut user_info = NSMutableDictionary::<_, AnyObject>::new();
+                unsafe {
+                    user_info.setObject_forKey(
+                        &*elements,
+                        ProtocolObject::from_ref(NSAccessibilityUIElementsKey),
+                    )
+                };
+
+                unsafe {
+                    NSAccessibilityPostNotificationWithUserInfo(
+                        &window,
+                        NSAccessibilityLayoutChangedNotification,
+                        Some(&**user_info),
+                    )
+                };
+            }
             Self::Announcement { text, priority } => {
                 let view = match context.view.load() {
                     Some(view) => view,
@@ -128,24 +167,72 @@ impl QueuedEvents {
 }
 
 pub(crate) struct EventGenerator {
-    context: Rc<Context>,
     events: Vec<QueuedEvent>,
     text_changed: HashSet<NodeId>,
+    layout_changed: Vec<NodeId>,
+    selection_changed: Vec<NodeId>,
+    announcements: AnnouncementFilter,
 }
 
 impl EventGenerator {
-    pub(crate) fn new(context: Rc<Context>) -> Self {
+    pub(crate) fn new() -> Self {
         Self {
-            context,
             events: Vec::new(),
             text_changed: HashSet::new(),
+            layout_changed: Vec::new(),
+            selection_changed: Vec::new(),
+            announcements: AnnouncementFilter::default(),
+        }
+    }
+
+    fn into_events(mut self) -> Vec<QueuedEvent> {
+        for node_id in self.selection_changed {
+            self.events.push(QueuedEvent::Generic {
+                node_id,
+                notification: unsafe { NSAccessibilitySelectedChildrenChangedNotification },
+            });
+        }
+        if !self.layout_changed.is_empty() {
+            self.events
+                .push(QueuedEvent::LayoutChanged(self.layout_changed));
         }
+        self.events
     }
 
-    pub(crate) fn into_result(self) -> QueuedEvents {
+    pub(crate) fn into_result(self, context: Rc<Context>) -> QueuedEvents {
         QueuedEvents {
-            context: self.context,
-            events: self.events,
+            context,
+            events: self.into_events(),
+        }
+    }
+
+    fn insert_layout_change(&mut self, node: &Node) {
+        let node = if filter(node) == FilterResult::Include {
+            Some(*node)
+        } else {
+            node.filtered_parent(&filter)
+        };
+        if let Some(node) = node {
+            if !self.layout_changed.contains(&node.id()) {
+                self.layout_changed.push(node.id());
+            }
+        }
+    }
+
+    fn insert_selection_change(&mut self, node: &Node) {
+        if let Some(container) = node.selection_container() {
+            if filter(&container) == FilterResult::Include
+                && !self.selection_changed.contains(&container.id())
+            {
+                self.selection_changed.push(container.id());
+            }
+        }
+    }
+
+    fn announce(&mut self, node: &Node) {
+        if self.announcements.allow(node, &node.name().unwrap()) {
+            self.events
+                .push(QueuedEvent::live_region_announcement(node));
         }
     }
 
@@ -198,12 +285,14 @@ impl EventGenerator {
 impl TreeChangeHandler for EventGenerator {
     fn node_added(&mut self, node: &Node) {
         self.insert_text_change_if_needed(node);
+        if node.is_selected_item() {
+            self.insert_selection_change(node);
+        }
         if filter(node) != FilterResult::Include {
             return;
         }
         if node.name().is_some() && node.live() != Live::Off {
-            self.events
-                .push(QueuedEvent::live_region_announcement(node));
+            self.announce(node);
         }
     }
 
@@ -211,6 +300,16 @@ impl TreeChangeHandler for EventGenerator {
         if old_node.raw_value() != new_node.raw_value() {
             self.insert_text_change_if_needed(new_node);
         }
+        if !old_node
+            .state()
+            .child_ids()
+            .eq(new_node.state().child_ids())
+        {
+            self.insert_layout_change(new_node);
+        }
+        if old_node.is_selected_item() != new_node.is_selected_item() {
+            self.insert_selection_change(new_node);
+        }
         if filter(new_node) != FilterResult::Include {
             return;
         }
@@ -244,8 +343,7 @@ impl TreeChangeHandler for EventGenerator {
                 || new_node.live() != old_node.live()
                 || filter_detached(old_node) != FilterResult::Include)
         {
-            self.events
-                .push(QueuedEvent::live_region_announcement(new_node));
+            self.announce(new_node);
         }
     }
 
@@ -271,3 +369,118 @@ impl TreeChangeHandler for EventGenerator {
         self.events.push(QueuedEvent::NodeDestroyed(node.id()));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use accesskit::{NodeBuilder, NodeClassSet, NodeId, Role, Tree, TreeUpdate};
+    use accesskit_consumer::Tree as ConsumerTree;
+    use icrate::AppKit::NSAccessibilitySelectedChildrenChangedNotification;
+
+    use super::{EventGenerator, QueuedEvent};
+
+    fn list(classes: &mut NodeClassSet, children: Vec<NodeId>) -> TreeUpdate {
+        let mut nodes = vec![
+            (NodeId(0), {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![NodeId(1)]);
+                builder.build(classes)
+            }),
+            (NodeId(1), {
+                let mut builder = NodeBuilder::new(Role::List);
+                builder.set_children(children.clone());
+                builder.build(classes)
+            }),
+        ];
+        for id in children {
+            let mut builder = NodeBuilder::new(Role::ListItem);
+            builder.set_name(format!("Item {}", id.0));
+            nodes.push((id, builder.build(classes)));
+        }
+        TreeUpdate {
+            nodes,
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        }
+    }
+
+    #[test]
+    fn structural_update_queues_layout_change() {
+        let mut classes = NodeClassSet::new();
+        let mut tree = ConsumerTree::new(list(&mut classes, vec![NodeId(2), NodeId(3)]), false);
+        let mut layout_changes = |update| {
+            let mut generator = EventGenerator::new();
+            tree.update_and_process_changes(update, &mut generator);
+            generator
+                .into_events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    QueuedEvent::LayoutChanged(node_ids) => Some(node_ids),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            layout_changes(list(&mut classes, vec![NodeId(3), NodeId(4)])),
+            vec![vec![NodeId(1)]]
+        );
+        assert!(layout_changes(list(&mut classes, vec![NodeId(3), NodeId(4)])).is_empty());
+    }
+
+    #[test]
+    fn moving_selection_queues_selected_children_change() {
+        let mut classes = NodeClassSet::new();
+        let mut list_box = |selected: NodeId| {
+            let mut nodes = vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::ListBox);
+                    builder.set_children(vec![NodeId(2), NodeId(3)]);
+                    builder.build(&mut classes)
+                }),
+            ];
+            for id in [NodeId(2), NodeId(3)] {
+                let mut builder = NodeBuilder::new(Role::ListBoxOption);
+                builder.set_name(format!("Option {}", id.0));
+                builder.set_selected(id == selected);
+                nodes.push((id, builder.build(&mut classes)));
+            }
+            TreeUpdate {
+                nodes,
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(1),
+            }
+        };
+        let mut tree = ConsumerTree::new(list_box(NodeId(2)), false);
+        let mut generator = EventGenerator::new();
+        tree.update_and_process_changes(list_box(NodeId(3)), &mut generator);
+        let changed = generator
+            .into_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                QueuedEvent::Generic {
+                    node_id,
+                    notification,
+                } if notification
+                    == unsafe { NSAccessibilitySelectedChildrenChangedNotification } =>
+                {
+                    Some(node_id)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(changed, vec![NodeId(1)]);
+        let selected = tree
+            .state()
+            .node_by_id(NodeId(1))
+            .unwrap()
+            .selected_items()
+            .into_iter()
+            .map(|node| node.id())
+            .collect::<Vec<_>>();
+        assert_eq!(selected, vec![NodeId(3)]);
+    }
+}
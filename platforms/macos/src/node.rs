@@ -10,17 +10,20 @@
 
 #![allow(non_upper_case_globals)]
 
-use accesskit::{Action, ActionData, ActionRequest, Checked, NodeId, Role, TextSelection};
+use accesskit::{
+    Action, ActionData, ActionRequest, Checked, Invalid, NodeId, Orientation, Role, TextSelection,
+};
 use accesskit_consumer::{DetachedNode, FilterResult, Node, NodeState};
 use icrate::{
+    Accessibility::{AXCustomContent, AXCustomContentProvider},
     AppKit::*,
     Foundation::{
-        ns_string, NSArray, NSCopying, NSInteger, NSNumber, NSObject, NSPoint, NSRange, NSRect,
-        NSString,
+        ns_string, NSArray, NSCopying, NSInteger, NSNumber, NSObject, NSObjectProtocol, NSPoint,
+        NSRange, NSRect, NSString,
     },
 };
 use objc2::{
-    declare_class, msg_send_id,
+    declare_class, msg_send, msg_send_id,
     mutability::InteriorMutable,
     rc::Id,
     runtime::{AnyObject, Sel},
@@ -228,6 +231,16 @@ fn ns_role(node_state: &NodeState) -> &'static NSAccessibilityRole {
     }
 }
 
+fn ns_sub_role(node_state: &NodeState) -> Option<&'static NSAccessibilitySubrole> {
+    unsafe {
+        match node_state.role() {
+            Role::PasswordInput => Some(NSAccessibilitySecureTextFieldSubrole),
+            Role::SearchInput => Some(NSAccessibilitySearchFieldSubrole),
+            _ => None,
+        }
+    }
+}
+
 pub(crate) fn can_be_focused(node: &Node) -> bool {
     filter(node) == FilterResult::Include && node.role() != Role::Window
 }
@@ -271,6 +284,10 @@ impl<'a> NodeWrapper<'a> {
         }
     }
 
+    pub(crate) fn role_description(&self) -> Option<String> {
+        self.node_state().role_description()
+    }
+
     fn node_value(&self) -> Option<String> {
         match self {
             Self::Node(node) => node.value(),
@@ -288,14 +305,65 @@ impl<'a> NodeWrapper<'a> {
             // In this case, macOS wants the text to be the value, not title.
             return None;
         }
+        if let (Self::Node(node), Some(_)) = (self, self.title_ui_element()) {
+            // The first label is exposed as the title UI element, so only
+            // the others contribute to the title.
+            let names = node
+                .explicit_labels()
+                .skip(1)
+                .filter_map(|label| label.name())
+                .collect::<Vec<_>>();
+            return (!names.is_empty()).then(|| names.join(" "));
+        }
         self.name()
     }
 
+    /// The first node in `labelled_by`, unless the node has its own name
+    /// or accessible text.
+    pub(crate) fn title_ui_element(&self) -> Option<NodeId> {
+        match self {
+            Self::Node(node) if node.raw_name().is_none() && node.accessible_text().is_none() => {
+                node.explicit_labels().next().map(|label| label.id())
+            }
+            _ => None,
+        }
+    }
+
+    /// The tooltip, unless it would just repeat the name or description.
+    pub(crate) fn help(&self) -> Option<String> {
+        let tooltip = self.node_state().tooltip()?;
+        let description = match self {
+            Self::Node(node) => node.computed_description(),
+            Self::DetachedNode(node) => node.computed_description(),
+        };
+        if self.name().as_deref() == Some(tooltip) || description.as_deref() == Some(tooltip) {
+            return None;
+        }
+        Some(tooltip.to_string())
+    }
+
+    /// The value of the `AXInvalid` attribute, which VoiceOver announces
+    /// for form fields that fail validation.
+    pub(crate) fn invalid(&self) -> Option<&'static str> {
+        self.node_state().invalid().map(|invalid| match invalid {
+            Invalid::True => "true",
+            Invalid::Grammar => "grammar",
+            Invalid::Spelling => "spelling",
+        })
+    }
+
     pub(crate) fn value(&self) -> Option<Value> {
         let state = self.node_state();
         if let Some(checked) = state.checked() {
             return Some(Value::Bool(checked != Checked::False));
         }
+        if state.role() == Role::ProgressIndicator {
+            // Keep the value of a progress indicator numeric; its text,
+            // such as a percentage, is the value description.
+            if let Some(value) = state.numeric_value() {
+                return Some(Value::Number(value));
+            }
+        }
         if let Some(value) = self.node_value() {
             return Some(Value::String(value));
         }
@@ -310,6 +378,19 @@ impl<'a> NodeWrapper<'a> {
         None
     }
 
+    pub(crate) fn value_description(&self) -> Option<String> {
+        let state = self.node_state();
+        if let Some(text) = state.checked_state_text() {
+            // The value is only a boolean, so this is what distinguishes
+            // the mixed state.
+            Some(text.to_string())
+        } else if state.role() == Role::ProgressIndicator && state.numeric_value().is_some() {
+            self.node_value()
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn supports_text_ranges(&self) -> bool {
         match self {
             Self::Node(node) => node.supports_text_ranges(),
@@ -320,6 +401,42 @@ impl<'a> NodeWrapper<'a> {
     pub(crate) fn raw_text_selection(&self) -> Option<&TextSelection> {
         self.node_state().raw_text_selection()
     }
+
+    /// Returns the request for an increment or decrement. If the node doesn't
+    /// support the action directly but supports setting its value, falls back
+    /// to setting the value one step away from the current one.
+    pub(crate) fn step_request(&self, action: Action) -> Option<ActionRequest> {
+        let state = self.node_state();
+        let (supported, steps) = match action {
+            Action::Increment => (state.supports_increment(), 1.0),
+            _ => (state.supports_decrement(), -1.0),
+        };
+        if supported {
+            return Some(ActionRequest {
+                action,
+                target: state.id(),
+                data: None,
+            });
+        }
+        let Self::Node(node) = self else {
+            return None;
+        };
+        node.stepped_numeric_value(steps)
+            .map(|value| ActionRequest {
+                action: Action::SetValue,
+                target: state.id(),
+                data: Some(ActionData::NumericValue(value)),
+            })
+    }
+
+    pub(crate) fn scroll_into_view_request(&self) -> Option<ActionRequest> {
+        let state = self.node_state();
+        state.supports_scroll_into_view().then(|| ActionRequest {
+            action: Action::ScrollIntoView,
+            target: state.id(),
+            data: None,
+        })
+    }
 }
 
 pub(crate) struct PlatformNodeIvars {
@@ -327,6 +444,20 @@ pub(crate) struct PlatformNodeIvars {
     node_id: NodeId,
 }
 
+fn ax_custom_content(node_state: &NodeState) -> Id<NSArray<AXCustomContent>> {
+    let items = node_state
+        .custom_content()
+        .iter()
+        .map(|item| unsafe {
+            AXCustomContent::customContentWithLabel_value(
+                &NSString::from_str(&item.label),
+                &NSString::from_str(&item.value),
+            )
+        })
+        .collect::<Vec<_>>();
+    NSArray::from_vec(items)
+}
+
 declare_class!(
     pub(crate) struct PlatformNode;
 
@@ -357,11 +488,26 @@ declare_class!(
             .flatten()
         }
 
+        #[method_id(accessibilityWindow)]
+        fn window(&self) -> Option<Id<AnyObject>> {
+            self.window_internal()
+        }
+
+        #[method_id(accessibilityTopLevelUIElement)]
+        fn top_level_element(&self) -> Option<Id<AnyObject>> {
+            self.window_internal()
+        }
+
         #[method_id(accessibilityChildren)]
         fn children(&self) -> Option<Id<NSArray<PlatformNode>>> {
             self.children_internal()
         }
 
+        #[method_id(accessibilitySelectedChildren)]
+        fn selected_children(&self) -> Option<Id<NSArray<PlatformNode>>> {
+            self.selected_children_internal()
+        }
+
         #[method_id(accessibilityChildrenInNavigationOrder)]
         fn children_in_navigation_order(&self) -> Option<Id<NSArray<PlatformNode>>> {
             // For now, we assume the children are in navigation order.
@@ -399,10 +545,17 @@ declare_class!(
                 .copy()
         }
 
+        #[method_id(accessibilitySubrole)]
+        fn sub_role(&self) -> Option<Id<NSAccessibilitySubrole>> {
+            self.resolve(|node| ns_sub_role(node.state()).map(|sub_role| sub_role.copy()))
+                .flatten()
+        }
+
         #[method_id(accessibilityRoleDescription)]
         fn role_description(&self) -> Option<Id<NSString>> {
             self.resolve(|node| {
-                if let Some(role_description) = node.role_description() {
+                let wrapper = NodeWrapper::Node(node);
+                if let Some(role_description) = wrapper.role_description() {
                     Some(NSString::from_str(&role_description))
                 } else {
                     unsafe { msg_send_id![super(self), accessibilityRoleDescription] }
@@ -420,6 +573,28 @@ declare_class!(
             .flatten()
         }
 
+        #[method_id(accessibilityTitleUIElement)]
+        fn title_ui_element(&self) -> Option<Id<AnyObject>> {
+            self.resolve_with_context(|node, context| {
+                let wrapper = NodeWrapper::Node(node);
+                wrapper.title_ui_element().map(|id| {
+                    Id::into_super(Id::into_super(Id::into_super(
+                        context.get_or_create_platform_node(id),
+                    )))
+                })
+            })
+            .flatten()
+        }
+
+        #[method_id(accessibilityHelp)]
+        fn help(&self) -> Option<Id<NSString>> {
+            self.resolve(|node| {
+                let wrapper = NodeWrapper::Node(node);
+                wrapper.help().map(|help| NSString::from_str(&help))
+            })
+            .flatten()
+        }
+
         #[method_id(accessibilityValue)]
         fn value(&self) -> Option<Id<NSObject>> {
             self.resolve(|node| {
@@ -439,12 +614,77 @@ declare_class!(
             .flatten()
         }
 
+        #[method_id(accessibilityValueDescription)]
+        fn value_description(&self) -> Option<Id<NSString>> {
+            self.resolve(|node| {
+                let wrapper = NodeWrapper::Node(node);
+                wrapper
+                    .value_description()
+                    .map(|description| NSString::from_str(&description))
+            })
+            .flatten()
+        }
+
         #[method(setAccessibilityValue:)]
         fn set_value(&self, _value: &NSObject) {
             // This isn't yet implemented. See the comment on this selector
             // in `is_selector_allowed`.
         }
 
+        #[method(accessibilityOrientation)]
+        fn orientation(&self) -> NSAccessibilityOrientation {
+            self.resolve(|node| match node.orientation() {
+                Some(Orientation::Horizontal) => NSAccessibilityOrientationHorizontal,
+                Some(Orientation::Vertical) => NSAccessibilityOrientationVertical,
+                None => NSAccessibilityOrientationUnknown,
+            })
+            .unwrap_or(NSAccessibilityOrientationUnknown)
+        }
+
+        #[method(isAccessibilityRequired)]
+        fn is_required(&self) -> bool {
+            self.resolve(|node| node.is_required()).unwrap_or(false)
+        }
+
+        // There's no method in the `NSAccessibility` protocol for `AXInvalid`,
+        // so it has to be handled through the older attribute-based API.
+        #[method_id(accessibilityAttributeValue:)]
+        fn attribute_value(&self, attribute: &NSString) -> Option<Id<AnyObject>> {
+            if attribute == ns_string!("AXInvalid") {
+                self.resolve(|node| {
+                    let wrapper = NodeWrapper::Node(node);
+                    wrapper
+                        .invalid()
+                        .map(|invalid| Id::into_super(Id::into_super(NSString::from_str(invalid))))
+                })
+                .flatten()
+            } else {
+                unsafe { msg_send_id![super(self), accessibilityAttributeValue: attribute] }
+            }
+        }
+
+        // Likewise, `AXScrollToVisible` is only available as an action name
+        // in the older API.
+        #[method_id(accessibilityActionNames)]
+        fn action_names(&self) -> Id<NSArray<NSString>> {
+            let names: Option<Id<NSArray<NSString>>> =
+                unsafe { msg_send_id![super(self), accessibilityActionNames] };
+            let mut names = names.map_or_else(Vec::new, |names| names.to_vec_retained());
+            if self.resolve(|node| node.supports_scroll_into_view()).unwrap_or(false) {
+                names.push(NSString::from_str("AXScrollToVisible"));
+            }
+            NSArray::from_vec(names)
+        }
+
+        #[method(accessibilityPerformAction:)]
+        fn perform_action(&self, action: &NSString) {
+            if action == ns_string!("AXScrollToVisible") {
+                self.scroll_into_view_internal();
+            } else {
+                unsafe { msg_send![super(self), accessibilityPerformAction: action] }
+            }
+        }
+
         #[method_id(accessibilityMinValue)]
         fn min_value(&self) -> Option<Id<NSNumber>> {
             self.resolve(|node| {
@@ -515,32 +755,26 @@ declare_class!(
 
         #[method(accessibilityPerformIncrement)]
         fn increment(&self) -> bool {
-            self.resolve_with_context(|node, context| {
-                let supports_increment = node.supports_increment();
-                if supports_increment {
-                    context.do_action(ActionRequest {
-                        action: Action::Increment,
-                        target: node.id(),
-                        data: None,
-                    });
-                }
-                supports_increment
-            })
-            .unwrap_or(false)
+            self.step_internal(Action::Increment)
         }
 
         #[method(accessibilityPerformDecrement)]
         fn decrement(&self) -> bool {
+            self.step_internal(Action::Decrement)
+        }
+
+        #[method(accessibilityPerformShowMenu)]
+        fn show_menu(&self) -> bool {
             self.resolve_with_context(|node, context| {
-                let supports_decrement = node.supports_decrement();
-                if supports_decrement {
+                let supports_show_context_menu = node.supports_show_context_menu();
+                if supports_show_context_menu {
                     context.do_action(ActionRequest {
-                        action: Action::Decrement,
+                        action: Action::ShowContextMenu,
                         target: node.id(),
                         data: None,
                     });
                 }
-                supports_decrement
+                supports_show_context_menu
             })
             .unwrap_or(false)
         }
@@ -725,10 +959,20 @@ declare_class!(
                     return node.is_clickable();
                 }
                 if selector == sel!(accessibilityPerformIncrement) {
-                    return node.supports_increment();
+                    return node.supports_increment() || node.stepped_numeric_value(1.0).is_some();
                 }
                 if selector == sel!(accessibilityPerformDecrement) {
-                    return node.supports_decrement();
+                    return node.supports_decrement()
+                        || node.stepped_numeric_value(-1.0).is_some();
+                }
+                if selector == sel!(accessibilityPerformShowMenu) {
+                    return node.supports_show_context_menu();
+                }
+                if selector == sel!(accessibilityOrientation) {
+                    return node.orientation().is_some();
+                }
+                if selector == sel!(accessibilitySelectedChildren) {
+                    return node.is_selection_container();
                 }
                 if selector == sel!(accessibilityNumberOfCharacters)
                     || selector == sel!(accessibilitySelectedText)
@@ -749,26 +993,45 @@ declare_class!(
                     // and it's not clear if VoiceOver ever actually uses it,
                     // but it must be allowed for editable text in order to get
                     // the expected VoiceOver behavior.
-                    return node.supports_text_ranges() && !node.is_read_only();
+                    return node.supports_text_ranges() && !node.is_effectively_read_only();
                 }
                 selector == sel!(accessibilityParent)
+                    || selector == sel!(accessibilityWindow)
+                    || selector == sel!(accessibilityTopLevelUIElement)
                     || selector == sel!(accessibilityChildren)
                     || selector == sel!(accessibilityChildrenInNavigationOrder)
                     || selector == sel!(accessibilityFrame)
                     || selector == sel!(accessibilityRole)
+                    || selector == sel!(accessibilitySubrole)
                     || selector == sel!(accessibilityRoleDescription)
                     || selector == sel!(accessibilityTitle)
+                    || selector == sel!(accessibilityHelp)
+                    || selector == sel!(accessibilityTitleUIElement)
                     || selector == sel!(accessibilityValue)
+                    || selector == sel!(accessibilityValueDescription)
                     || selector == sel!(accessibilityMinValue)
                     || selector == sel!(accessibilityMaxValue)
+                    || selector == sel!(isAccessibilityRequired)
                     || selector == sel!(isAccessibilityElement)
                     || selector == sel!(isAccessibilityFocused)
                     || selector == sel!(accessibilityNotifiesWhenDestroyed)
                     || selector == sel!(isAccessibilitySelectorAllowed:)
+                    || (selector == sel!(accessibilityCustomContent)
+                        && !node.custom_content().is_empty())
             })
             .unwrap_or(false)
         }
     }
+
+    unsafe impl NSObjectProtocol for PlatformNode {}
+
+    unsafe impl AXCustomContentProvider for PlatformNode {
+        #[method_id(accessibilityCustomContent)]
+        fn custom_content(&self) -> Id<NSArray<AXCustomContent>> {
+            self.resolve(|node| ax_custom_content(node.state()))
+                .unwrap_or_default()
+        }
+    }
 );
 
 impl PlatformNode {
@@ -805,4 +1068,391 @@ impl PlatformNode {
             NSArray::from_vec(platform_nodes)
         })
     }
+
+    pub(crate) fn selected_children_internal(&self) -> Option<Id<NSArray<PlatformNode>>> {
+        self.resolve_with_context(|node, context| {
+            let platform_nodes = node
+                .selected_items()
+                .into_iter()
+                .filter(|item| filter(item) == FilterResult::Include)
+                .map(|item| context.get_or_create_platform_node(item.id()))
+                .collect::<Vec<Id<PlatformNode>>>();
+            NSArray::from_vec(platform_nodes)
+        })
+    }
+
+    fn step_internal(&self, action: Action) -> bool {
+        self.resolve_with_context(|node, context| {
+            let Some(request) = NodeWrapper::Node(node).step_request(action) else {
+                return false;
+            };
+            context.do_action(request);
+            true
+        })
+        .unwrap_or(false)
+    }
+
+    fn scroll_into_view_internal(&self) -> bool {
+        self.resolve_with_context(|node, context| {
+            let Some(request) = NodeWrapper::Node(node).scroll_into_view_request() else {
+                return false;
+            };
+            context.do_action(request);
+            true
+        })
+        .unwrap_or(false)
+    }
+
+    fn window_internal(&self) -> Option<Id<AnyObject>> {
+        self.resolve_with_context(|_, context| {
+            let view = context.view.load()?;
+            let window = view.window()?;
+            Some(Id::into_super(Id::into_super(Id::into_super(window))))
+        })
+        .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use accesskit::{
+        Action, ActionData, ActionRequest, CustomContent, Invalid, NodeBuilder, NodeClassSet,
+        NodeId, Role, Tree, TreeUpdate,
+    };
+    use accesskit_consumer::Tree as ConsumerTree;
+    use icrate::AppKit::*;
+
+    use super::{ax_custom_content, ns_role, ns_sub_role, NodeWrapper};
+
+    fn roles(role: Role) -> (String, Option<String>) {
+        let mut classes = NodeClassSet::new();
+        let tree = ConsumerTree::new(
+            TreeUpdate {
+                nodes: vec![(NodeId(0), NodeBuilder::new(role).build(&mut classes))],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(0),
+            },
+            false,
+        );
+        let state = tree.state().root();
+        let state = state.state();
+        (
+            ns_role(state).to_string(),
+            ns_sub_role(state).map(|sub_role| sub_role.to_string()),
+        )
+    }
+
+    #[test]
+    fn required_and_invalid() {
+        let mut classes = NodeClassSet::new();
+        let field = |classes: &mut NodeClassSet, invalid: Option<Invalid>| {
+            let mut builder = NodeBuilder::new(Role::TextInput);
+            builder.set_required();
+            if let Some(invalid) = invalid {
+                builder.set_invalid(invalid);
+            }
+            builder.build(classes)
+        };
+        let tree = ConsumerTree::new(
+            TreeUpdate {
+                nodes: vec![
+                    (NodeId(0), {
+                        let mut builder = NodeBuilder::new(Role::Window);
+                        builder.set_children(vec![NodeId(1), NodeId(2), NodeId(3)]);
+                        builder.build(&mut classes)
+                    }),
+                    (NodeId(1), field(&mut classes, None)),
+                    (NodeId(2), field(&mut classes, Some(Invalid::True))),
+                    (NodeId(3), field(&mut classes, Some(Invalid::Spelling))),
+                ],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(0),
+            },
+            false,
+        );
+        let state = tree.state();
+        let invalid = |id| NodeWrapper::Node(&state.node_by_id(id).unwrap()).invalid();
+        assert!(state.node_by_id(NodeId(1)).unwrap().is_required());
+        assert_eq!(invalid(NodeId(1)), None);
+        assert_eq!(invalid(NodeId(2)), Some("true"));
+        assert_eq!(invalid(NodeId(3)), Some("spelling"));
+        assert_eq!(invalid(NodeId(0)), None);
+    }
+
+    #[test]
+    fn protected_input_is_secure_text_field() {
+        let (role, sub_role) = roles(Role::PasswordInput);
+        assert_eq!(role, unsafe { NSAccessibilityTextFieldRole }.to_string());
+        assert_eq!(
+            sub_role,
+            Some(unsafe { NSAccessibilitySecureTextFieldSubrole }.to_string())
+        );
+    }
+
+    #[test]
+    fn search_input_is_search_field() {
+        let (role, sub_role) = roles(Role::SearchInput);
+        assert_eq!(role, unsafe { NSAccessibilityTextFieldRole }.to_string());
+        assert_eq!(
+            sub_role,
+            Some(unsafe { NSAccessibilitySearchFieldSubrole }.to_string())
+        );
+    }
+
+    #[test]
+    fn plain_input_has_no_sub_role() {
+        let (_, sub_role) = roles(Role::TextInput);
+        assert_eq!(sub_role, None);
+    }
+
+    #[test]
+    fn custom_content_in_order() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::ListItem);
+        builder.push_custom_content(CustomContent {
+            label: "Status".into(),
+            value: "unread".into(),
+        });
+        builder.push_custom_content(CustomContent {
+            label: "Sender".into(),
+            value: "Alice".into(),
+        });
+        let tree = ConsumerTree::new(
+            TreeUpdate {
+                nodes: vec![(NodeId(0), builder.build(&mut classes))],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(0),
+            },
+            false,
+        );
+        let root = tree.state().root();
+        let items = ax_custom_content(root.state())
+            .iter()
+            .map(|item| unsafe { (item.label().to_string(), item.value().to_string()) })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                ("Status".into(), "unread".into()),
+                ("Sender".into(), "Alice".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn first_label_is_title_ui_element() {
+        let mut classes = NodeClassSet::new();
+        let label = |classes: &mut NodeClassSet, name: &str| {
+            let mut builder = NodeBuilder::new(Role::StaticText);
+            builder.set_name(name);
+            builder.build(classes)
+        };
+        let tree = ConsumerTree::new(
+            TreeUpdate {
+                nodes: vec![
+                    (NodeId(0), {
+                        let mut builder = NodeBuilder::new(Role::Window);
+                        builder.set_children(vec![NodeId(1), NodeId(2), NodeId(3)]);
+                        builder.build(&mut classes)
+                    }),
+                    (NodeId(1), label(&mut classes, "Amount")),
+                    (NodeId(2), label(&mut classes, "(USD)")),
+                    (NodeId(3), {
+                        let mut builder = NodeBuilder::new(Role::TextInput);
+                        builder.set_labelled_by(vec![NodeId(1), NodeId(2)]);
+                        builder.build(&mut classes)
+                    }),
+                ],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(3),
+            },
+            false,
+        );
+        let state = tree.state();
+        let input = state.node_by_id(NodeId(3)).unwrap();
+        let wrapper = NodeWrapper::Node(&input);
+        assert_eq!(wrapper.title_ui_element(), Some(NodeId(1)));
+        assert_eq!(wrapper.title(), Some("(USD)".into()));
+    }
+
+    #[test]
+    fn accessible_text_suppresses_title_ui_element() {
+        let mut classes = NodeClassSet::new();
+        let tree = ConsumerTree::new(
+            TreeUpdate {
+                nodes: vec![
+                    (NodeId(0), {
+                        let mut builder = NodeBuilder::new(Role::Window);
+                        builder.set_children(vec![NodeId(1), NodeId(2)]);
+                        builder.build(&mut classes)
+                    }),
+                    (NodeId(1), {
+                        let mut builder = NodeBuilder::new(Role::StaticText);
+                        builder.set_name("Amount");
+                        builder.build(&mut classes)
+                    }),
+                    (NodeId(2), {
+                        let mut builder = NodeBuilder::new(Role::TextInput);
+                        builder.set_labelled_by(vec![NodeId(1)]);
+                        builder.set_accessible_text("Amount in US dollars");
+                        builder.build(&mut classes)
+                    }),
+                ],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(2),
+            },
+            false,
+        );
+        let state = tree.state();
+        let input = state.node_by_id(NodeId(2)).unwrap();
+        let wrapper = NodeWrapper::Node(&input);
+        assert_eq!(wrapper.title_ui_element(), None);
+        assert_eq!(wrapper.title(), Some("Amount in US dollars".into()));
+    }
+
+    #[test]
+    fn tooltip_is_help() {
+        let mut classes = NodeClassSet::new();
+        let button = |classes: &mut NodeClassSet, name: &str| {
+            let mut builder = NodeBuilder::new(Role::Button);
+            builder.set_name(name);
+            builder.set_tooltip("Save the document");
+            builder.build(classes)
+        };
+        let tree = ConsumerTree::new(
+            TreeUpdate {
+                nodes: vec![
+                    (NodeId(0), {
+                        let mut builder = NodeBuilder::new(Role::Window);
+                        builder.set_children(vec![NodeId(1), NodeId(2)]);
+                        builder.build(&mut classes)
+                    }),
+                    (NodeId(1), button(&mut classes, "Save")),
+                    (NodeId(2), button(&mut classes, "Save the document")),
+                ],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(0),
+            },
+            false,
+        );
+        let state = tree.state();
+        let help = |id| NodeWrapper::Node(&state.node_by_id(id).unwrap()).help();
+        assert_eq!(help(NodeId(1)), Some("Save the document".into()));
+        assert_eq!(help(NodeId(2)), None);
+    }
+
+    #[test]
+    fn increment_and_decrement_requests() {
+        let mut classes = NodeClassSet::new();
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![NodeId(1), NodeId(2), NodeId(3)]);
+        let mut stepper = NodeBuilder::new(Role::SpinButton);
+        stepper.set_numeric_value(3.0);
+        stepper.add_action(Action::Increment);
+        stepper.add_action(Action::Decrement);
+        let mut slider = NodeBuilder::new(Role::Slider);
+        slider.set_numeric_value(3.0);
+        slider.set_numeric_value_step(2.0);
+        slider.add_action(Action::SetValue);
+        let mut display_only = NodeBuilder::new(Role::Slider);
+        display_only.set_numeric_value(3.0);
+        display_only.set_numeric_value_step(2.0);
+        let tree = ConsumerTree::new(
+            TreeUpdate {
+                nodes: vec![
+                    (NodeId(0), window.build(&mut classes)),
+                    (NodeId(1), stepper.build(&mut classes)),
+                    (NodeId(2), slider.build(&mut classes)),
+                    (NodeId(3), display_only.build(&mut classes)),
+                ],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(0),
+            },
+            false,
+        );
+        let state = tree.state();
+        let step =
+            |id, action| NodeWrapper::Node(&state.node_by_id(id).unwrap()).step_request(action);
+        let request = |target, action, data| ActionRequest {
+            action,
+            target: NodeId(target),
+            data,
+        };
+        assert_eq!(
+            step(NodeId(1), Action::Increment),
+            Some(request(1, Action::Increment, None))
+        );
+        assert_eq!(
+            step(NodeId(1), Action::Decrement),
+            Some(request(1, Action::Decrement, None))
+        );
+        assert_eq!(
+            step(NodeId(2), Action::Increment),
+            Some(request(
+                2,
+                Action::SetValue,
+                Some(ActionData::NumericValue(5.0))
+            ))
+        );
+        assert_eq!(step(NodeId(0), Action::Decrement), None);
+        // Nodes that don't declare SetValue aren't sent one.
+        assert_eq!(step(NodeId(3), Action::Increment), None);
+    }
+
+    #[test]
+    fn role_description_overrides_default() {
+        let mut classes = NodeClassSet::new();
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![NodeId(1)]);
+        let mut swatch = NodeBuilder::new(Role::Button);
+        swatch.set_role_description("color swatch");
+        let tree = ConsumerTree::new(
+            TreeUpdate {
+                nodes: vec![
+                    (NodeId(0), window.build(&mut classes)),
+                    (NodeId(1), swatch.build(&mut classes)),
+                ],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(0),
+            },
+            false,
+        );
+        let state = tree.state();
+        let role_description =
+            |id| NodeWrapper::Node(&state.node_by_id(id).unwrap()).role_description();
+        assert_eq!(role_description(NodeId(1)), Some("color swatch".into()));
+        assert_eq!(role_description(NodeId(0)), None);
+    }
+
+    #[test]
+    fn scroll_to_visible_request() {
+        let mut classes = NodeClassSet::new();
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![NodeId(1)]);
+        let mut item = NodeBuilder::new(Role::ListItem);
+        item.add_action(Action::ScrollIntoView);
+        let tree = ConsumerTree::new(
+            TreeUpdate {
+                nodes: vec![
+                    (NodeId(0), window.build(&mut classes)),
+                    (NodeId(1), item.build(&mut classes)),
+                ],
+                tree: Some(Tree::new(NodeId(0))),
+                focus: NodeId(0),
+            },
+            false,
+        );
+        let state = tree.state();
+        let request =
+            |id| NodeWrapper::Node(&state.node_by_id(id).unwrap()).scroll_into_view_request();
+        assert_eq!(
+            request(NodeId(1)),
+            Some(ActionRequest {
+                action: Action::ScrollIntoView,
+                target: NodeId(1),
+                data: None,
+            })
+        );
+        assert_eq!(request(NodeId(0)), None);
+    }
 }
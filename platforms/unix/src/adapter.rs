@@ -8,66 +8,92 @@ use crate::{
         interfaces::{Event, ObjectEvent, WindowEvent},
         ObjectId,
     },
-    context::{AppContext, Context},
+    context::{is_any_tree_busy, AppContext, ConnectionStatus, Context},
     filters::{filter, filter_detached},
-    node::NodeWrapper,
+    node::{effective_focus, NodeWrapper},
     util::WindowBounds,
 };
 use accesskit::{ActionHandler, NodeId, Rect, Role, TreeUpdate};
-use accesskit_consumer::{DetachedNode, FilterResult, Node, Tree, TreeChangeHandler, TreeState};
+use accesskit_consumer::{
+    AnnouncementFilter, DetachedNode, FilterResult, Node, Tree, TreeChangeHandler, TreeState,
+};
 #[cfg(not(feature = "tokio"))]
 use async_channel::Sender;
 use atspi::{InterfaceSet, Live, State};
 use once_cell::sync::Lazy;
-use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
-    Arc, Mutex, Weak,
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
 };
 #[cfg(feature = "tokio")]
 use tokio::sync::mpsc::UnboundedSender as Sender;
 
 struct AdapterChangeHandler<'a> {
     adapter: &'a AdapterImpl,
+    announcements: AnnouncementFilter,
 }
 
-impl AdapterChangeHandler<'_> {
+impl<'a> AdapterChangeHandler<'a> {
+    fn new(adapter: &'a AdapterImpl) -> Self {
+        Self {
+            adapter,
+            announcements: AnnouncementFilter::default(),
+        }
+    }
+
     fn add_node(&mut self, node: &Node) {
         let role = node.role();
         let is_root = node.is_root();
-        let node = NodeWrapper::Node {
+        let wrapper = NodeWrapper::Node {
             adapter: self.adapter.id,
             node,
         };
-        let interfaces = node.interfaces();
+        let interfaces = wrapper.interfaces();
         self.adapter.register_interfaces(node.id(), interfaces);
         if is_root && role == Role::Window {
             let adapter_index = AppContext::read().adapter_index(self.adapter.id).unwrap();
             self.adapter.window_created(adapter_index, node.id());
         }
+        self.announce(node);
+    }
 
-        let live = node.live();
-        if live != Live::None {
-            if let Some(name) = node.name() {
-                self.adapter.emit_object_event(
-                    ObjectId::Node {
-                        adapter: self.adapter.id,
-                        node: node.id(),
-                    },
-                    ObjectEvent::Announcement(name, live),
-                );
-            }
+    fn announce(&mut self, node: &Node) {
+        let wrapper = NodeWrapper::Node {
+            adapter: self.adapter.id,
+            node,
+        };
+        let live = wrapper.live();
+        if live == Live::None {
+            return;
+        }
+        let Some(name) = node.name() else {
+            return;
+        };
+        if self.announcements.allow(node, &name) {
+            self.adapter.emit_object_event(
+                ObjectId::Node {
+                    adapter: self.adapter.id,
+                    node: node.id(),
+                },
+                ObjectEvent::Announcement(name, live),
+            );
         }
     }
 
-    fn remove_node(&mut self, node: &DetachedNode) {
+    fn remove_node(&mut self, node: &DetachedNode, current_state: &TreeState) {
         let role = node.role();
         let is_root = node.is_root();
         let node = NodeWrapper::DetachedNode {
             adapter: self.adapter.id,
             node,
+            current_state,
         };
         if is_root && role == Role::Window {
-            self.adapter.window_destroyed(node.id());
+            let adapter_index = AppContext::read().adapter_index(self.adapter.id).unwrap();
+            self.adapter.window_destroyed(adapter_index, node.id());
         }
         self.adapter.emit_object_event(
             ObjectId::Node {
@@ -77,7 +103,23 @@ impl AdapterChangeHandler<'_> {
             ObjectEvent::StateChanged(State::Defunct, true),
         );
         self.adapter
-            .unregister_interfaces(node.id(), node.interfaces());
+            .unregister_interfaces(node.id(), self.adapter.registered_interfaces(node.id()));
+    }
+
+    /// Brings the interfaces registered for a node in line with its
+    /// current state.
+    fn refresh_interfaces(&mut self, node: &Node) {
+        let wrapper = NodeWrapper::Node {
+            adapter: self.adapter.id,
+            node,
+        };
+        let old_interfaces = self.adapter.registered_interfaces(node.id());
+        let new_interfaces = wrapper.interfaces();
+        let kept_interfaces = old_interfaces & new_interfaces;
+        self.adapter
+            .unregister_interfaces(node.id(), old_interfaces ^ kept_interfaces);
+        self.adapter
+            .register_interfaces(node.id(), new_interfaces ^ kept_interfaces);
     }
 }
 
@@ -95,26 +137,48 @@ impl TreeChangeHandler for AdapterChangeHandler<'_> {
             if filter_new == FilterResult::Include {
                 self.add_node(new_node);
             } else if filter_old == FilterResult::Include {
-                self.remove_node(old_node);
+                self.remove_node(old_node, new_node.tree_state);
             }
         } else if filter_new == FilterResult::Include {
             let old_wrapper = NodeWrapper::DetachedNode {
                 adapter: self.adapter.id,
                 node: old_node,
+                current_state: new_node.tree_state,
             };
             let new_wrapper = NodeWrapper::Node {
                 adapter: self.adapter.id,
                 node: new_node,
             };
-            let old_interfaces = old_wrapper.interfaces();
-            let new_interfaces = new_wrapper.interfaces();
-            let kept_interfaces = old_interfaces & new_interfaces;
-            self.adapter
-                .unregister_interfaces(new_wrapper.id(), old_interfaces ^ kept_interfaces);
-            self.adapter
-                .register_interfaces(new_node.id(), new_interfaces ^ kept_interfaces);
+            self.refresh_interfaces(new_node);
+            // Whether a text container implements Hypertext depends on
+            // the roles of its children.
+            if (old_node.role() == Role::Link) != (new_node.role() == Role::Link) {
+                if let Some(parent) = new_node.filtered_parent(&filter) {
+                    self.refresh_interfaces(&parent);
+                }
+            }
             let bounds = *self.adapter.context.read_root_window_bounds();
             new_wrapper.notify_changes(&bounds, self.adapter, &old_wrapper);
+            if new_node.name() != old_node.name() {
+                self.announce(new_node);
+            }
+        }
+        if old_node.is_focused() && new_node.is_focused() {
+            let old_target = self.adapter.focus_target();
+            let new_target = effective_focus(*new_node).id();
+            if Some(new_target) != old_target {
+                self.adapter.emit_object_event(
+                    ObjectId::Node {
+                        adapter: self.adapter.id,
+                        node: new_node.id(),
+                    },
+                    ObjectEvent::ActiveDescendantChanged(ObjectId::Node {
+                        adapter: self.adapter.id,
+                        node: new_target,
+                    }),
+                );
+                self.adapter.emit_focus_change(Some(new_target));
+            }
         }
     }
 
@@ -137,35 +201,13 @@ impl TreeChangeHandler for AdapterChangeHandler<'_> {
                 });
             }
         }
-        if let Some(node) = new_node.map(|node| NodeWrapper::Node {
-            adapter: self.adapter.id,
-            node,
-        }) {
-            self.adapter.emit_object_event(
-                ObjectId::Node {
-                    adapter: self.adapter.id,
-                    node: node.id(),
-                },
-                ObjectEvent::StateChanged(State::Focused, true),
-            );
-        }
-        if let Some(node) = old_node.map(|node| NodeWrapper::DetachedNode {
-            adapter: self.adapter.id,
-            node,
-        }) {
-            self.adapter.emit_object_event(
-                ObjectId::Node {
-                    adapter: self.adapter.id,
-                    node: node.id(),
-                },
-                ObjectEvent::StateChanged(State::Focused, false),
-            );
-        }
+        self.adapter
+            .emit_focus_change(new_node.map(|node| effective_focus(*node).id()));
     }
 
-    fn node_removed(&mut self, node: &DetachedNode, _: &TreeState) {
+    fn node_removed(&mut self, node: &DetachedNode, current_state: &TreeState) {
         if filter_detached(node) == FilterResult::Include {
-            self.remove_node(node);
+            self.remove_node(node, current_state);
         }
     }
 }
@@ -174,6 +216,8 @@ pub(crate) struct AdapterImpl {
     id: usize,
     messages: Sender<Message>,
     context: Arc<Context>,
+    interfaces: Mutex<HashMap<NodeId, InterfaceSet>>,
+    focus_target: Mutex<Option<NodeId>>,
 }
 
 impl AdapterImpl {
@@ -186,6 +230,7 @@ impl AdapterImpl {
         action_handler: Box<dyn ActionHandler + Send>,
     ) -> Self {
         let tree = Tree::new(initial_state, is_window_focused);
+        let focus_target = tree.state().focus().map(|node| effective_focus(node).id());
         let context = {
             let mut app_context = AppContext::write();
             let context = Context::new(tree, action_handler, root_window_bounds);
@@ -196,6 +241,8 @@ impl AdapterImpl {
             id,
             messages,
             context,
+            interfaces: Mutex::new(HashMap::new()),
+            focus_target: Mutex::new(focus_target),
         }
     }
 
@@ -253,7 +300,24 @@ impl AdapterImpl {
         let _ = self.messages.send(message);
     }
 
+    /// Returns the interfaces that are currently registered for a node.
+    fn registered_interfaces(&self, id: NodeId) -> InterfaceSet {
+        self.interfaces
+            .lock()
+            .unwrap()
+            .get(&id)
+            .copied()
+            .unwrap_or_else(InterfaceSet::empty)
+    }
+
     fn register_interfaces(&self, id: NodeId, new_interfaces: InterfaceSet) {
+        {
+            let mut interfaces = self.interfaces.lock().unwrap();
+            let registered = interfaces.entry(id).or_insert_with(InterfaceSet::empty);
+            for interface in new_interfaces.iter() {
+                registered.insert(interface);
+            }
+        }
         self.send_message(Message::RegisterInterfaces {
             adapter_id: self.id,
             context: Arc::downgrade(&self.context),
@@ -263,6 +327,15 @@ impl AdapterImpl {
     }
 
     fn unregister_interfaces(&self, id: NodeId, old_interfaces: InterfaceSet) {
+        {
+            let mut interfaces = self.interfaces.lock().unwrap();
+            if let Some(registered) = interfaces.get_mut(&id) {
+                *registered = *registered ^ (*registered & old_interfaces);
+                if *registered == InterfaceSet::empty() {
+                    interfaces.remove(&id);
+                }
+            }
+        }
         self.send_message(Message::UnregisterInterfaces {
             adapter_id: self.id,
             node_id: id,
@@ -279,18 +352,71 @@ impl AdapterImpl {
         *old_bounds = bounds;
     }
 
+    fn set_max_announcements(&self, value: Option<usize>) {
+        let mut tree = self.context.tree.write().unwrap();
+        tree.set_max_announcements(value);
+    }
+
     fn update(&self, update: TreeUpdate) {
-        let mut handler = AdapterChangeHandler { adapter: self };
+        // The application's busy state is shared by all of its trees,
+        // so it only changes if no other tree is busy. The other trees
+        // are checked before this one is locked for writing.
+        let other_contexts = AppContext::read().other_contexts(self.id);
+        let is_other_tree_busy = is_any_tree_busy(other_contexts);
+        let mut handler = AdapterChangeHandler::new(self);
         let mut tree = self.context.tree.write().unwrap();
+        let was_busy = is_other_tree_busy || tree.state().is_busy();
         tree.update_and_process_changes(update, &mut handler);
+        let is_busy = is_other_tree_busy || tree.state().is_busy();
+        if is_busy != was_busy {
+            self.emit_object_event(
+                ObjectId::Root,
+                ObjectEvent::StateChanged(State::Busy, is_busy),
+            );
+        }
     }
 
     fn update_window_focus_state(&self, is_focused: bool) {
-        let mut handler = AdapterChangeHandler { adapter: self };
+        let mut handler = AdapterChangeHandler::new(self);
         let mut tree = self.context.tree.write().unwrap();
         tree.update_host_focus_state_and_process_changes(is_focused, &mut handler);
     }
 
+    /// Returns the node that was last reported as focused, which is
+    /// the effective focus rather than the focused node itself.
+    fn focus_target(&self) -> Option<NodeId> {
+        *self.focus_target.lock().unwrap()
+    }
+
+    /// Reports the focus moving from the last reported target to the new
+    /// one. Targets are resolved through [`effective_focus`], so
+    /// the old target can't be recomputed from the old tree once it has
+    /// been replaced.
+    fn emit_focus_change(&self, new_target: Option<NodeId>) {
+        let old_target = std::mem::replace(&mut *self.focus_target.lock().unwrap(), new_target);
+        if old_target == new_target {
+            return;
+        }
+        if let Some(node) = new_target {
+            self.emit_object_event(
+                ObjectId::Node {
+                    adapter: self.id,
+                    node,
+                },
+                ObjectEvent::StateChanged(State::Focused, true),
+            );
+        }
+        if let Some(node) = old_target {
+            self.emit_object_event(
+                ObjectId::Node {
+                    adapter: self.id,
+                    node,
+                },
+                ObjectEvent::StateChanged(State::Focused, false),
+            );
+        }
+    }
+
     fn window_created(&self, adapter_index: usize, window: NodeId) {
         self.emit_object_event(
             ObjectId::Root,
@@ -347,13 +473,16 @@ impl AdapterImpl {
         );
     }
 
-    fn window_destroyed(&self, window: NodeId) {
+    fn window_destroyed(&self, adapter_index: usize, window: NodeId) {
         self.emit_object_event(
             ObjectId::Root,
-            ObjectEvent::ChildRemoved(ObjectId::Node {
-                adapter: self.id,
-                node: window,
-            }),
+            ObjectEvent::ChildRemoved(
+                adapter_index,
+                ObjectId::Node {
+                    adapter: self.id,
+                    node: window,
+                },
+            ),
         );
     }
 }
@@ -370,14 +499,22 @@ fn root_window(current_state: &TreeState) -> Option<Node> {
 
 impl Drop for AdapterImpl {
     fn drop(&mut self) {
-        AppContext::write().remove_adapter(self.id);
+        let adapter_index = {
+            let mut app_context = AppContext::write();
+            let (Ok(index) | Err(index)) = app_context.adapter_index(self.id);
+            app_context.remove_adapter(self.id);
+            index
+        };
         let root_id = self.context.read_tree().state().root_id();
         self.emit_object_event(
             ObjectId::Root,
-            ObjectEvent::ChildRemoved(ObjectId::Node {
-                adapter: self.id,
-                node: root_id,
-            }),
+            ObjectEvent::ChildRemoved(
+                adapter_index,
+                ObjectId::Node {
+                    adapter: self.id,
+                    node: root_id,
+                },
+            ),
         );
     }
 }
@@ -392,6 +529,7 @@ pub struct Adapter {
     r#impl: LazyAdapter,
     is_window_focused: Arc<AtomicBool>,
     root_window_bounds: Arc<Mutex<WindowBounds>>,
+    max_announcements: Arc<Mutex<Option<usize>>>,
 }
 
 impl Adapter {
@@ -404,19 +542,23 @@ impl Adapter {
         let messages = AppContext::read().messages.clone();
         let is_window_focused = Arc::new(AtomicBool::new(false));
         let root_window_bounds = Arc::new(Mutex::new(Default::default()));
+        let max_announcements = Arc::new(Mutex::new(None));
         let r#impl: LazyAdapter = Arc::new(Lazy::new(Box::new({
             let messages = messages.clone();
             let is_window_focused = Arc::clone(&is_window_focused);
             let root_window_bounds = Arc::clone(&root_window_bounds);
+            let max_announcements = Arc::clone(&max_announcements);
             move || {
-                AdapterImpl::new(
+                let r#impl = AdapterImpl::new(
                     id,
                     messages,
                     source(),
                     is_window_focused.load(Ordering::Relaxed),
                     *root_window_bounds.lock().unwrap(),
                     action_handler,
-                )
+                );
+                r#impl.set_max_announcements(*max_announcements.lock().unwrap());
+                r#impl
             }
         })));
         let adapter = Self {
@@ -425,6 +567,7 @@ impl Adapter {
             r#impl: r#impl.clone(),
             is_window_focused,
             root_window_bounds,
+            max_announcements,
         };
         adapter.send_message(Message::AddAdapter {
             id,
@@ -440,6 +583,13 @@ impl Adapter {
         let _ = self.messages.send(message);
     }
 
+    /// Returns the state of the application's connection to the AT-SPI bus,
+    /// including the bus address or the error that prevented connecting.
+    /// The connection is shared by all adapters in the process.
+    pub fn connection_status(&self) -> ConnectionStatus {
+        AppContext::read().connection_status.clone()
+    }
+
     pub fn set_root_window_bounds(&self, outer: Rect, inner: Rect) {
         let new_bounds = WindowBounds::new(outer, inner);
         {
@@ -451,6 +601,19 @@ impl Adapter {
         }
     }
 
+    /// Set the maximum number of live region announcements made in response
+    /// to a single update, or `None`, the default, for no limit. Refer to
+    /// [`accesskit_consumer::Tree::set_max_announcements`] for details.
+    pub fn set_max_announcements(&self, value: Option<usize>) {
+        {
+            let mut max_announcements = self.max_announcements.lock().unwrap();
+            *max_announcements = value;
+        }
+        if let Some(r#impl) = Lazy::get(&self.r#impl) {
+            r#impl.set_max_announcements(value);
+        }
+    }
+
     /// If and only if the tree has been initialized, call the provided function
     /// and apply the resulting update.
     pub fn update_if_active(&self, update_factory: impl FnOnce() -> TreeUpdate) {
@@ -495,3 +658,539 @@ pub(crate) enum Message {
     },
     EmitEvent(Event),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atspi::interfaces::Property;
+    use accesskit::{ActionRequest, NodeBuilder, NodeClassSet, Tree};
+    use atspi::Interface;
+
+    struct NullActionHandler;
+
+    impl ActionHandler for NullActionHandler {
+        fn do_action(&mut self, _request: ActionRequest) {}
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    type Receiver = async_channel::Receiver<Message>;
+    #[cfg(feature = "tokio")]
+    type Receiver = tokio::sync::mpsc::UnboundedReceiver<Message>;
+
+    /// Creates a focused adapter for the given tree, along with the receiving
+    /// end of the channel that it sends its messages to.
+    fn test_adapter(initial_state: TreeUpdate) -> (AdapterImpl, Receiver) {
+        #[cfg(not(feature = "tokio"))]
+        let (messages, receiver) = async_channel::unbounded();
+        #[cfg(feature = "tokio")]
+        let (messages, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let adapter = AdapterImpl::new(
+            NEXT_ADAPTER_ID.fetch_add(1, Ordering::SeqCst),
+            messages,
+            initial_state,
+            true,
+            WindowBounds::default(),
+            Box::new(NullActionHandler),
+        );
+        (adapter, receiver)
+    }
+
+    /// Takes the object events that have been sent so far, ignoring
+    /// any other message.
+    fn object_events(receiver: &mut Receiver) -> Vec<(ObjectId, ObjectEvent)> {
+        std::iter::from_fn(|| receiver.try_recv().ok())
+            .filter_map(|message| match message {
+                Message::EmitEvent(Event::Object { target, event }) => Some((target, event)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    const WINDOW_ID: NodeId = NodeId(0);
+    const GRID_ID: NodeId = NodeId(1);
+    const CELL_1_ID: NodeId = NodeId(2);
+    const CELL_2_ID: NodeId = NodeId(3);
+
+    fn grid(classes: &mut NodeClassSet, active_descendant: NodeId) -> accesskit::Node {
+        let mut builder = NodeBuilder::new(Role::Grid);
+        builder.set_children(vec![CELL_1_ID, CELL_2_ID]);
+        builder.set_active_descendant(active_descendant);
+        builder.add_action(accesskit::Action::Focus);
+        builder.build(classes)
+    }
+
+    #[test]
+    fn focus_follows_active_descendant() {
+        let mut classes = NodeClassSet::new();
+        let cell = |classes: &mut NodeClassSet, name: &str| {
+            let mut builder = NodeBuilder::new(Role::Cell);
+            builder.set_name(name);
+            builder.build(classes)
+        };
+        let initial_state = TreeUpdate {
+            nodes: vec![
+                (WINDOW_ID, {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![GRID_ID]);
+                    builder.build(&mut classes)
+                }),
+                (GRID_ID, grid(&mut classes, CELL_1_ID)),
+                (CELL_1_ID, cell(&mut classes, "A1")),
+                (CELL_2_ID, cell(&mut classes, "A2")),
+            ],
+            tree: Some(Tree::new(WINDOW_ID)),
+            focus: GRID_ID,
+        };
+        let (adapter, mut receiver) = test_adapter(initial_state);
+        {
+            let tree = adapter.context.read_tree();
+            let cell_1 = tree.state().node_by_id(CELL_1_ID).unwrap();
+            let wrapper = NodeWrapper::Node {
+                adapter: adapter.id,
+                node: &cell_1,
+            };
+            assert!(wrapper.state(true).contains(State::Focused));
+        }
+
+        adapter.update(TreeUpdate {
+            nodes: vec![(GRID_ID, grid(&mut classes, CELL_2_ID))],
+            tree: None,
+            focus: GRID_ID,
+        });
+
+        let mut focus_events = Vec::new();
+        for event in object_events(&mut receiver) {
+            if let (
+                ObjectId::Node { node, .. },
+                ObjectEvent::StateChanged(State::Focused, is_focused),
+            ) = event
+            {
+                focus_events.push((node, is_focused));
+            }
+        }
+        assert_eq!(focus_events, vec![(CELL_2_ID, true), (CELL_1_ID, false)]);
+    }
+
+    #[test]
+    fn focus_change_unfocuses_nested_active_descendant() {
+        const ROW_1_ID: NodeId = NodeId(4);
+        const ROW_2_ID: NodeId = NodeId(5);
+        let mut classes = NodeClassSet::new();
+        let container = |classes: &mut NodeClassSet,
+                         role: Role,
+                         children: Vec<NodeId>,
+                         active_descendant: NodeId| {
+            let mut builder = NodeBuilder::new(role);
+            builder.set_children(children);
+            builder.set_active_descendant(active_descendant);
+            builder.add_action(accesskit::Action::Focus);
+            builder.build(classes)
+        };
+        let initial_state = TreeUpdate {
+            nodes: vec![
+                (WINDOW_ID, {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![GRID_ID]);
+                    builder.build(&mut classes)
+                }),
+                (
+                    GRID_ID,
+                    container(&mut classes, Role::Grid, vec![ROW_1_ID, ROW_2_ID], ROW_1_ID),
+                ),
+                (
+                    ROW_1_ID,
+                    container(&mut classes, Role::Row, vec![CELL_1_ID], CELL_1_ID),
+                ),
+                (
+                    ROW_2_ID,
+                    container(&mut classes, Role::Row, vec![CELL_2_ID], CELL_2_ID),
+                ),
+                (CELL_1_ID, NodeBuilder::new(Role::Cell).build(&mut classes)),
+                (CELL_2_ID, NodeBuilder::new(Role::Cell).build(&mut classes)),
+            ],
+            tree: Some(Tree::new(WINDOW_ID)),
+            focus: GRID_ID,
+        };
+        let (adapter, mut receiver) = test_adapter(initial_state);
+        let focus_change = |event| match event {
+            (
+                ObjectId::Node { node, .. },
+                ObjectEvent::StateChanged(State::Focused, is_focused),
+            ) => Some((node, is_focused)),
+            _ => None,
+        };
+
+        adapter.update(TreeUpdate {
+            nodes: vec![(
+                GRID_ID,
+                container(&mut classes, Role::Grid, vec![ROW_1_ID, ROW_2_ID], ROW_2_ID),
+            )],
+            tree: None,
+            focus: GRID_ID,
+        });
+        assert_eq!(
+            object_events(&mut receiver)
+                .into_iter()
+                .filter_map(focus_change)
+                .collect::<Vec<_>>(),
+            vec![(CELL_2_ID, true), (CELL_1_ID, false)]
+        );
+
+        adapter.update(TreeUpdate {
+            nodes: vec![],
+            tree: None,
+            focus: WINDOW_ID,
+        });
+        assert_eq!(
+            object_events(&mut receiver)
+                .into_iter()
+                .filter_map(focus_change)
+                .collect::<Vec<_>>(),
+            vec![(WINDOW_ID, true), (CELL_2_ID, false)]
+        );
+    }
+
+    #[test]
+    fn bulk_live_region_update_is_capped() {
+        let mut classes = NodeClassSet::new();
+        let status_ids = (1..=10).map(NodeId).collect::<Vec<_>>();
+        let window = |classes: &mut NodeClassSet, children: &[NodeId]| {
+            let mut builder = NodeBuilder::new(Role::Window);
+            builder.set_children(children);
+            builder.build(classes)
+        };
+        let initial_state = TreeUpdate {
+            nodes: vec![(WINDOW_ID, window(&mut classes, &[]))],
+            tree: Some(Tree::new(WINDOW_ID)),
+            focus: WINDOW_ID,
+        };
+        let (adapter, mut receiver) = test_adapter(initial_state);
+        adapter.set_max_announcements(Some(3));
+
+        let mut nodes = vec![(WINDOW_ID, window(&mut classes, &status_ids))];
+        for (i, id) in status_ids.iter().enumerate() {
+            let mut builder = NodeBuilder::new(Role::Status);
+            // The first two statuses announce the same text.
+            builder.set_name(format!("Status {}", i.max(1)));
+            builder.set_live(accesskit::Live::Polite);
+            nodes.push((*id, builder.build(&mut classes)));
+        }
+        adapter.update(TreeUpdate {
+            nodes,
+            tree: None,
+            focus: WINDOW_ID,
+        });
+
+        let mut announcements = Vec::new();
+        for event in object_events(&mut receiver) {
+            if let (_, ObjectEvent::Announcement(text, _)) = event {
+                announcements.push(text);
+            }
+        }
+        assert_eq!(announcements.len(), 3);
+        announcements.sort();
+        announcements.dedup();
+        assert_eq!(announcements.len(), 3);
+    }
+
+    #[test]
+    fn application_busy_state() {
+        let mut classes = NodeClassSet::new();
+        let update = |classes: &mut NodeClassSet, is_busy: bool| {
+            let mut root = NodeBuilder::new(Role::Window);
+            if is_busy {
+                root.set_busy();
+            }
+            TreeUpdate {
+                nodes: vec![(WINDOW_ID, root.build(classes))],
+                tree: Some(Tree::new(WINDOW_ID)),
+                focus: WINDOW_ID,
+            }
+        };
+        let (first, mut first_receiver) = test_adapter(update(&mut classes, true));
+        let (second, mut second_receiver) = test_adapter(update(&mut classes, true));
+        let contexts = || [first.context.clone(), second.context.clone()];
+        assert!(is_any_tree_busy(contexts()));
+        let busy_change = |event| match event {
+            (ObjectId::Root, ObjectEvent::StateChanged(State::Busy, is_busy)) => Some(is_busy),
+            _ => None,
+        };
+        object_events(&mut first_receiver);
+        object_events(&mut second_receiver);
+
+        // The application stays busy while the other tree is.
+        second.update(update(&mut classes, false));
+        assert!(is_any_tree_busy(contexts()));
+        assert_eq!(
+            object_events(&mut second_receiver)
+                .into_iter()
+                .filter_map(busy_change)
+                .collect::<Vec<_>>(),
+            vec![]
+        );
+
+        first.update(update(&mut classes, false));
+        assert!(!is_any_tree_busy(contexts()));
+        assert_eq!(
+            object_events(&mut first_receiver)
+                .into_iter()
+                .filter_map(busy_change)
+                .collect::<Vec<_>>(),
+            vec![false]
+        );
+    }
+
+    #[test]
+    fn status_is_polite_by_default() {
+        let mut classes = NodeClassSet::new();
+        let window = |classes: &mut NodeClassSet| {
+            let mut builder = NodeBuilder::new(Role::Window);
+            builder.set_children(vec![NodeId(1)]);
+            builder.build(classes)
+        };
+        let status = |classes: &mut NodeClassSet, name: &str| {
+            let mut builder = NodeBuilder::new(Role::Status);
+            builder.set_name(name);
+            builder.build(classes)
+        };
+        let initial_state = TreeUpdate {
+            nodes: vec![
+                (WINDOW_ID, window(&mut classes)),
+                (NodeId(1), status(&mut classes, "Ready")),
+            ],
+            tree: Some(Tree::new(WINDOW_ID)),
+            focus: WINDOW_ID,
+        };
+        let (adapter, mut receiver) = test_adapter(initial_state);
+        object_events(&mut receiver);
+
+        adapter.update(TreeUpdate {
+            nodes: vec![(NodeId(1), status(&mut classes, "Saved"))],
+            tree: None,
+            focus: WINDOW_ID,
+        });
+
+        let mut announcements = Vec::new();
+        for event in object_events(&mut receiver) {
+            if let (_, ObjectEvent::Announcement(text, live)) = event {
+                announcements.push((text, live));
+            }
+        }
+        assert_eq!(announcements, vec![("Saved".into(), Live::Polite)]);
+    }
+
+    #[test]
+    fn children_changed_signals() {
+        let mut classes = NodeClassSet::new();
+        let window = |classes: &mut NodeClassSet, children: &[NodeId]| {
+            let mut builder = NodeBuilder::new(Role::Window);
+            builder.set_children(children);
+            builder.build(classes)
+        };
+        let button = |classes: &mut NodeClassSet| NodeBuilder::new(Role::Button).build(classes);
+        let initial_state = TreeUpdate {
+            nodes: vec![
+                (
+                    WINDOW_ID,
+                    window(&mut classes, &[NodeId(1), NodeId(2), NodeId(3)]),
+                ),
+                (NodeId(1), button(&mut classes)),
+                (NodeId(2), button(&mut classes)),
+                (NodeId(3), button(&mut classes)),
+            ],
+            tree: Some(Tree::new(WINDOW_ID)),
+            focus: WINDOW_ID,
+        };
+        let (adapter, mut receiver) = test_adapter(initial_state);
+        let children_change = |event| match event {
+            (
+                ObjectId::Node {
+                    node: WINDOW_ID, ..
+                },
+                ObjectEvent::ChildAdded(index, ObjectId::Node { node, .. }),
+            ) => Some(("add", index, node)),
+            (
+                ObjectId::Node {
+                    node: WINDOW_ID, ..
+                },
+                ObjectEvent::ChildRemoved(index, ObjectId::Node { node, .. }),
+            ) => Some(("remove", index, node)),
+            _ => None,
+        };
+        object_events(&mut receiver);
+
+        adapter.update(TreeUpdate {
+            nodes: vec![
+                (
+                    WINDOW_ID,
+                    window(&mut classes, &[NodeId(1), NodeId(3), NodeId(4)]),
+                ),
+                (NodeId(4), button(&mut classes)),
+            ],
+            tree: None,
+            focus: WINDOW_ID,
+        });
+        assert_eq!(
+            object_events(&mut receiver)
+                .into_iter()
+                .filter_map(children_change)
+                .collect::<Vec<_>>(),
+            vec![("remove", 1, NodeId(2)), ("add", 2, NodeId(4))]
+        );
+
+        adapter.update(TreeUpdate {
+            nodes: vec![
+                (
+                    WINDOW_ID,
+                    window(&mut classes, &[NodeId(5), NodeId(3), NodeId(4)]),
+                ),
+                (NodeId(5), button(&mut classes)),
+            ],
+            tree: None,
+            focus: WINDOW_ID,
+        });
+        assert_eq!(
+            object_events(&mut receiver)
+                .into_iter()
+                .filter_map(children_change)
+                .collect::<Vec<_>>(),
+            vec![("remove", 0, NodeId(1)), ("add", 0, NodeId(5))]
+        );
+    }
+
+    #[test]
+    fn hypertext_follows_link_children() {
+        const PARAGRAPH_ID: NodeId = NodeId(1);
+        const CHILD_ID: NodeId = NodeId(2);
+        let mut classes = NodeClassSet::new();
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![PARAGRAPH_ID]);
+        let mut paragraph = NodeBuilder::new(Role::Paragraph);
+        paragraph.set_children(vec![CHILD_ID]);
+        let initial_state = TreeUpdate {
+            nodes: vec![
+                (WINDOW_ID, window.build(&mut classes)),
+                (PARAGRAPH_ID, paragraph.build(&mut classes)),
+                (
+                    CHILD_ID,
+                    NodeBuilder::new(Role::StaticText).build(&mut classes),
+                ),
+            ],
+            tree: Some(Tree::new(WINDOW_ID)),
+            focus: WINDOW_ID,
+        };
+        let (adapter, _receiver) = test_adapter(initial_state);
+        adapter.register_tree();
+        let has_hypertext = || {
+            adapter
+                .registered_interfaces(PARAGRAPH_ID)
+                .contains(Interface::Hypertext)
+        };
+        assert!(!has_hypertext());
+
+        adapter.update(TreeUpdate {
+            nodes: vec![(CHILD_ID, NodeBuilder::new(Role::Link).build(&mut classes))],
+            tree: None,
+            focus: WINDOW_ID,
+        });
+        assert!(has_hypertext());
+
+        adapter.update(TreeUpdate {
+            nodes: vec![(
+                CHILD_ID,
+                NodeBuilder::new(Role::StaticText).build(&mut classes),
+            )],
+            tree: None,
+            focus: WINDOW_ID,
+        });
+        assert!(!has_hypertext());
+    }
+
+    #[test]
+    fn value_text_changes() {
+        const SLIDER_ID: NodeId = NodeId(1);
+        let mut classes = NodeClassSet::new();
+        let slider = |classes: &mut NodeClassSet, value: f64, text: &str| {
+            let mut builder = NodeBuilder::new(Role::Slider);
+            builder.set_numeric_value(value);
+            builder.set_value(text);
+            builder.build(classes)
+        };
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![SLIDER_ID]);
+        let initial_state = TreeUpdate {
+            nodes: vec![
+                (WINDOW_ID, window.build(&mut classes)),
+                (SLIDER_ID, slider(&mut classes, 1.0, "Low")),
+            ],
+            tree: Some(Tree::new(WINDOW_ID)),
+            focus: WINDOW_ID,
+        };
+        let (adapter, mut receiver) = test_adapter(initial_state);
+        object_events(&mut receiver);
+        let mut update = |value, text| {
+            adapter.update(TreeUpdate {
+                nodes: vec![(SLIDER_ID, slider(&mut classes, value, text))],
+                tree: None,
+                focus: WINDOW_ID,
+            });
+            object_events(&mut receiver)
+                .into_iter()
+                .filter_map(|event| match event {
+                    (_, ObjectEvent::PropertyChanged(Property::Value(value))) => {
+                        Some(value.to_string())
+                    }
+                    (_, ObjectEvent::PropertyChanged(Property::ValueText(text))) => Some(text),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(update(1.0, "Quiet"), vec!["Quiet".to_string()]);
+        assert_eq!(update(2.0, "Medium"), vec!["2".to_string()]);
+        assert!(update(2.0, "Medium").is_empty());
+    }
+
+    #[test]
+    fn naming_a_group_changes_its_role() {
+        const GROUP_ID: NodeId = NodeId(1);
+        let mut classes = NodeClassSet::new();
+        let group = |classes: &mut NodeClassSet, name: Option<&str>| {
+            let mut builder = NodeBuilder::new(Role::Group);
+            if let Some(name) = name {
+                builder.set_name(name);
+            }
+            builder.build(classes)
+        };
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![GROUP_ID]);
+        let initial_state = TreeUpdate {
+            nodes: vec![
+                (WINDOW_ID, window.build(&mut classes)),
+                (GROUP_ID, group(&mut classes, None)),
+            ],
+            tree: Some(Tree::new(WINDOW_ID)),
+            focus: WINDOW_ID,
+        };
+        let (adapter, mut receiver) = test_adapter(initial_state);
+        object_events(&mut receiver);
+        let mut update = |name| {
+            adapter.update(TreeUpdate {
+                nodes: vec![(GROUP_ID, group(&mut classes, name))],
+                tree: None,
+                focus: WINDOW_ID,
+            });
+            object_events(&mut receiver)
+                .into_iter()
+                .filter_map(|event| match event {
+                    (_, ObjectEvent::PropertyChanged(Property::Role(role))) => Some(role),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(update(Some("Shipping address")), vec![atspi::Role::Panel]);
+        assert!(update(Some("Billing address")).is_empty());
+        assert_eq!(update(None), vec![atspi::Role::Section]);
+    }
+}
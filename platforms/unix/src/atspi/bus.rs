@@ -24,6 +24,7 @@ use zbus::{
 };
 
 pub(crate) struct Bus {
+    address: String,
     conn: Connection,
     _task: Task<()>,
     socket_proxy: SocketProxy<'static>,
@@ -38,8 +39,7 @@ impl Bus {
             Ok(address) if !address.is_empty() => address,
             _ => BusProxy::new(session_bus).await?.get_address().await?,
         };
-        let address: Address = address.as_str().try_into()?;
-        let conn = ConnectionBuilder::address(address)?
+        let conn = ConnectionBuilder::address(Address::try_from(address.as_str())?)?
             .internal_executor(false)
             .build()
             .await?;
@@ -53,7 +53,8 @@ impl Bus {
             "accesskit_atspi_bus_task",
         );
         let socket_proxy = SocketProxy::new(&conn).await?;
-        let mut bus = Bus {
+        let bus = Bus {
+            address,
             conn,
             _task,
             socket_proxy,
@@ -62,11 +63,15 @@ impl Bus {
         Ok(bus)
     }
 
+    pub(crate) fn address(&self) -> &str {
+        &self.address
+    }
+
     fn unique_name(&self) -> &OwnedUniqueName {
         self.conn.unique_name().unwrap()
     }
 
-    async fn register_root_node(&mut self) -> Result<()> {
+    pub(crate) async fn register_root_node(&self) -> Result<()> {
         let node = PlatformRootNode::new();
         let path = ObjectId::Root.path();
 
@@ -96,6 +101,32 @@ impl Bus {
         Ok(())
     }
 
+    /// Remove the application object and ask the registry to forget it,
+    /// so that it disappears from assistive technologies right away rather
+    /// than lingering until the registry notices the connection is gone.
+    pub(crate) async fn unregister_root_node(&self) -> Result<()> {
+        let path = ObjectId::Root.path();
+        let app_node_removed = self
+            .unregister_interface::<ApplicationInterface>(&path)
+            .await?;
+        self.unregister_interface::<AccessibleInterface<PlatformRootNode>>(&path)
+            .await?;
+
+        if app_node_removed {
+            map_or_ignoring_broken_pipe(
+                self.socket_proxy
+                    .unembed(&(self.unique_name().as_str(), path.into()))
+                    .await,
+                (),
+                |_| (),
+            )?;
+            let mut app_context = AppContext::write();
+            app_context.desktop_address = None;
+        }
+
+        Ok(())
+    }
+
     pub(crate) async fn register_interfaces(
         &self,
         adapter_id: usize,
@@ -132,6 +163,27 @@ impl Bus {
             )
             .await?;
         }
+        if new_interfaces.contains(Interface::EditableText) {
+            self.register_interface(
+                &path,
+                EditableTextInterface::new(PlatformNode::new(context.clone(), adapter_id, node_id)),
+            )
+            .await?;
+        }
+        if new_interfaces.contains(Interface::Hyperlink) {
+            self.register_interface(
+                &path,
+                HyperlinkInterface::new(PlatformNode::new(context.clone(), adapter_id, node_id)),
+            )
+            .await?;
+        }
+        if new_interfaces.contains(Interface::Hypertext) {
+            self.register_interface(
+                &path,
+                HypertextInterface::new(PlatformNode::new(context.clone(), adapter_id, node_id)),
+            )
+            .await?;
+        }
         if new_interfaces.contains(Interface::Value) {
             self.register_interface(
                 &path,
@@ -176,6 +228,18 @@ impl Bus {
             self.unregister_interface::<ComponentInterface>(&path)
                 .await?;
         }
+        if old_interfaces.contains(Interface::EditableText) {
+            self.unregister_interface::<EditableTextInterface>(&path)
+                .await?;
+        }
+        if old_interfaces.contains(Interface::Hyperlink) {
+            self.unregister_interface::<HyperlinkInterface>(&path)
+                .await?;
+        }
+        if old_interfaces.contains(Interface::Hypertext) {
+            self.unregister_interface::<HypertextInterface>(&path)
+                .await?;
+        }
         if old_interfaces.contains(Interface::Value) {
             self.unregister_interface::<ValueInterface>(&path).await?;
         }
@@ -204,7 +268,7 @@ impl Bus {
             ObjectEvent::ActiveDescendantChanged(_) => "ActiveDescendantChanged",
             ObjectEvent::Announcement(_, _) => "Announcement",
             ObjectEvent::BoundsChanged(_) => "BoundsChanged",
-            ObjectEvent::ChildAdded(_, _) | ObjectEvent::ChildRemoved(_) => "ChildrenChanged",
+            ObjectEvent::ChildAdded(_, _) | ObjectEvent::ChildRemoved(_, _) => "ChildrenChanged",
             ObjectEvent::PropertyChanged(_) => "PropertyChange",
             ObjectEvent::StateChanged(_, _) => "StateChanged",
         };
@@ -270,14 +334,14 @@ impl Bus {
                 )
                 .await
             }
-            ObjectEvent::ChildRemoved(child) => {
+            ObjectEvent::ChildRemoved(index, child) => {
                 self.emit_event(
                     target,
                     interface,
                     signal,
                     EventBody {
                         kind: "remove",
-                        detail1: -1,
+                        detail1: index as i32,
                         detail2: 0,
                         any_data: child.to_address(self.unique_name().clone()).into(),
                         properties,
@@ -296,7 +360,7 @@ impl Bus {
                             Property::Description(_) => "accessible-description",
                             Property::Parent(_) => "accessible-parent",
                             Property::Role(_) => "accessible-role",
-                            Property::Value(_) => "accessible-value",
+                            Property::Value(_) | Property::ValueText(_) => "accessible-value",
                         },
                         detail1: 0,
                         detail2: 0,
@@ -308,6 +372,7 @@ impl Bus {
                             }
                             Property::Role(value) => Value::U32(value as u32),
                             Property::Value(value) => Value::F64(value),
+                            Property::ValueText(value) => Str::from(value).into(),
                         },
                         properties,
                     },
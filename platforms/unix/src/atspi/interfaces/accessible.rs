@@ -7,7 +7,8 @@ use crate::{
     atspi::{ObjectId, OwnedObjectAddress},
     PlatformNode, PlatformRootNode,
 };
-use atspi::{Interface, InterfaceSet, Role, StateSet};
+use atspi::{Interface, InterfaceSet, RelationType, Role, StateSet};
+use std::collections::HashMap;
 use zbus::{fdo, names::OwnedUniqueName, MessageHeader};
 
 pub(crate) struct AccessibleInterface<T> {
@@ -101,6 +102,25 @@ impl AccessibleInterface<PlatformNode> {
     fn get_interfaces(&self) -> fdo::Result<InterfaceSet> {
         self.node.interfaces()
     }
+
+    fn get_attributes(&self) -> fdo::Result<HashMap<String, String>> {
+        self.node.attributes()
+    }
+
+    fn get_relation_set(&self) -> fdo::Result<Vec<(RelationType, Vec<OwnedObjectAddress>)>> {
+        Ok(self
+            .node
+            .relation_set()?
+            .into_iter()
+            .map(|(relation, targets)| {
+                let targets = targets
+                    .into_iter()
+                    .map(|target| target.to_address(self.bus_name.clone()))
+                    .collect();
+                (relation, targets)
+            })
+            .collect())
+    }
 }
 
 #[dbus_interface(name = "org.a11y.atspi.Accessible")]
@@ -168,8 +188,8 @@ impl AccessibleInterface<PlatformRootNode> {
         Role::Application
     }
 
-    fn get_state(&self) -> StateSet {
-        StateSet::empty()
+    fn get_state(&self) -> fdo::Result<StateSet> {
+        self.node.state()
     }
 
     fn get_application(
@@ -182,4 +202,8 @@ impl AccessibleInterface<PlatformRootNode> {
     fn get_interfaces(&self) -> InterfaceSet {
         InterfaceSet::new(Interface::Accessible | Interface::Application)
     }
+
+    fn get_attributes(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
 }
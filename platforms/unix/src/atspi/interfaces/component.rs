@@ -7,7 +7,7 @@ use crate::{
     atspi::{OwnedObjectAddress, Rect},
     PlatformNode,
 };
-use atspi::{CoordType, Layer};
+use atspi::{CoordType, Layer, ScrollType};
 use zbus::{fdo, MessageHeader};
 
 pub(crate) struct ComponentInterface {
@@ -49,6 +49,10 @@ impl ComponentInterface {
         self.node.grab_focus()
     }
 
+    fn scroll_to(&self, scroll_type: ScrollType) -> fdo::Result<bool> {
+        self.node.scroll_to(scroll_type)
+    }
+
     fn scroll_to_point(&self, coord_type: CoordType, x: i32, y: i32) -> fdo::Result<bool> {
         self.node.scroll_to_point(coord_type, x, y)
     }
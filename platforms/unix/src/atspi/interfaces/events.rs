@@ -24,6 +24,7 @@ pub(crate) enum Property {
     Parent(ObjectId),
     Role(Role),
     Value(f64),
+    ValueText(String),
 }
 
 #[allow(clippy::enum_variant_names)]
@@ -32,7 +33,7 @@ pub(crate) enum ObjectEvent {
     Announcement(String, Live),
     BoundsChanged(Rect),
     ChildAdded(usize, ObjectId),
-    ChildRemoved(ObjectId),
+    ChildRemoved(usize, ObjectId),
     PropertyChanged(Property),
     StateChanged(State, bool),
 }
@@ -0,0 +1,53 @@
+// Copyright 2024 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use crate::{atspi::OwnedObjectAddress, PlatformNode};
+use zbus::{fdo, MessageHeader};
+
+pub(crate) struct HyperlinkInterface {
+    node: PlatformNode,
+}
+
+impl HyperlinkInterface {
+    pub(crate) fn new(node: PlatformNode) -> Self {
+        Self { node }
+    }
+}
+
+#[dbus_interface(name = "org.a11y.atspi.Hyperlink")]
+impl HyperlinkInterface {
+    #[dbus_interface(property)]
+    fn n_anchors(&self) -> i16 {
+        1
+    }
+
+    #[dbus_interface(property)]
+    fn start_index(&self) -> fdo::Result<i32> {
+        self.node.hyperlink_start_index()
+    }
+
+    #[dbus_interface(property)]
+    fn end_index(&self) -> fdo::Result<i32> {
+        self.node.hyperlink_end_index()
+    }
+
+    fn get_object(
+        &self,
+        #[zbus(header)] hdr: MessageHeader<'_>,
+        index: i32,
+    ) -> fdo::Result<(OwnedObjectAddress,)> {
+        let object = self.node.hyperlink_object(index)?;
+        super::object_address(hdr.destination()?, object)
+    }
+
+    #[dbus_interface(name = "GetURI")]
+    fn get_uri(&self, index: i32) -> fdo::Result<String> {
+        self.node.hyperlink_uri(index)
+    }
+
+    fn is_valid(&self) -> fdo::Result<bool> {
+        self.node.hyperlink_is_valid()
+    }
+}
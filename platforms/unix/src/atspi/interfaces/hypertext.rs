@@ -0,0 +1,37 @@
+// Copyright 2024 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use crate::{atspi::OwnedObjectAddress, PlatformNode};
+use zbus::{fdo, MessageHeader};
+
+pub(crate) struct HypertextInterface {
+    node: PlatformNode,
+}
+
+impl HypertextInterface {
+    pub(crate) fn new(node: PlatformNode) -> Self {
+        Self { node }
+    }
+}
+
+#[dbus_interface(name = "org.a11y.atspi.Hypertext")]
+impl HypertextInterface {
+    fn get_n_links(&self) -> fdo::Result<i32> {
+        self.node.n_links()
+    }
+
+    fn get_link(
+        &self,
+        #[zbus(header)] hdr: MessageHeader<'_>,
+        link_index: i32,
+    ) -> fdo::Result<(OwnedObjectAddress,)> {
+        let link = self.node.get_link(link_index)?;
+        super::object_address(hdr.destination()?, link)
+    }
+
+    fn get_link_index(&self, character_index: i32) -> fdo::Result<i32> {
+        self.node.get_link_index(character_index)
+    }
+}
@@ -7,7 +7,10 @@ mod accessible;
 mod action;
 mod application;
 mod component;
+mod editable_text;
 mod events;
+mod hyperlink;
+mod hypertext;
 mod value;
 
 use crate::atspi::{ObjectId, OwnedObjectAddress};
@@ -35,5 +38,8 @@ pub(crate) use accessible::*;
 pub(crate) use action::*;
 pub(crate) use application::*;
 pub(crate) use component::*;
+pub(crate) use editable_text::*;
 pub(crate) use events::*;
+pub(crate) use hyperlink::*;
+pub(crate) use hypertext::*;
 pub(crate) use value::*;
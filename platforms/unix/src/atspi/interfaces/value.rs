@@ -42,4 +42,9 @@ impl ValueInterface {
     fn set_current_value(&mut self, value: f64) -> fdo::Result<()> {
         self.node.set_current_value(value)
     }
+
+    #[dbus_interface(property)]
+    fn text(&self) -> fdo::Result<String> {
+        self.node.value_text()
+    }
 }
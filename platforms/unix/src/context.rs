@@ -71,8 +71,41 @@ impl AdapterAndContext {
     }
 }
 
+/// The application is busy while any of its trees is.
+pub(crate) fn is_any_tree_busy(contexts: impl IntoIterator<Item = Arc<Context>>) -> bool {
+    contexts
+        .into_iter()
+        .any(|context| context.read_tree().state().is_busy())
+}
+
 static APP_CONTEXT: OnceCell<Arc<RwLock<AppContext>>> = OnceCell::new();
 
+/// The state of the application's connection to the AT-SPI bus,
+/// as returned by [`Adapter::connection_status`]. This is meant for
+/// diagnosing why assistive technologies can't see the application.
+///
+/// [`Adapter::connection_status`]: crate::Adapter::connection_status
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// The connection hasn't been attempted yet.
+    Pending,
+    /// The desktop reports that accessibility is disabled,
+    /// so no connection was made.
+    Disabled,
+    /// Connected to the accessibility bus at the given address.
+    Connected(String),
+    /// Connecting to the session bus or the accessibility bus failed
+    /// with the given error.
+    Failed(String),
+}
+
+fn connection_status(result: Result<&str, &zbus::Error>) -> ConnectionStatus {
+    match result {
+        Ok(address) => ConnectionStatus::Connected(address.into()),
+        Err(error) => ConnectionStatus::Failed(error.to_string()),
+    }
+}
+
 pub(crate) struct AppContext {
     pub(crate) messages: Sender<Message>,
     pub(crate) name: Option<String>,
@@ -80,6 +113,7 @@ pub(crate) struct AppContext {
     pub(crate) toolkit_version: Option<String>,
     pub(crate) id: Option<i32>,
     pub(crate) desktop_address: Option<OwnedObjectAddress>,
+    pub(crate) connection_status: ConnectionStatus,
     pub(crate) adapters: Vec<AdapterAndContext>,
 }
 
@@ -94,10 +128,25 @@ impl AppContext {
             thread::spawn(|| {
                 let executor = Executor::new();
                 block_on(executor.run(async {
-                    if let Ok(session_bus) = ConnectionBuilder::session() {
-                        if let Ok(session_bus) = session_bus.internal_executor(false).build().await
-                        {
-                            run_event_loop(&executor, session_bus, rx).await.unwrap();
+                    let session_bus = async {
+                        ConnectionBuilder::session()?
+                            .internal_executor(false)
+                            .build()
+                            .await
+                    };
+                    match session_bus.await {
+                        Ok(session_bus) => {
+                            let result = run_event_loop(&executor, session_bus, rx).await;
+                            if let Err(error) = &result {
+                                // The event loop is gone, so whatever was
+                                // reported before no longer holds.
+                                AppContext::write().connection_status =
+                                    connection_status(Err(error));
+                            }
+                            result.unwrap();
+                        }
+                        Err(error) => {
+                            AppContext::write().connection_status = connection_status(Err(&error));
                         }
                     }
                 }))
@@ -110,6 +159,7 @@ impl AppContext {
                 toolkit_version: None,
                 id: None,
                 desktop_address: None,
+                connection_status: ConnectionStatus::Pending,
                 adapters: Vec::new(),
             }))
         })
@@ -132,6 +182,16 @@ impl AppContext {
             .push(AdapterAndContext(id, Arc::downgrade(context)));
     }
 
+    /// Returns the contexts of the live adapters other than the given one.
+    pub(crate) fn other_contexts(&self, id: usize) -> Vec<Arc<Context>> {
+        self.adapters
+            .iter()
+            .filter_map(AdapterAndContext::upgrade)
+            .filter(|(adapter, _)| *adapter != id)
+            .map(|(_, context)| context)
+            .collect()
+    }
+
     pub(crate) fn remove_adapter(&mut self, id: usize) {
         if let Ok(index) = self.adapter_index(id) {
             self.adapters.remove(index);
@@ -171,11 +231,15 @@ async fn run_event_loop(
         select! {
             change = changes.next() => {
                 atspi_bus = None;
+                let mut status = ConnectionStatus::Disabled;
                 if let Some(change) = change {
                     if change.get().await? {
-                        atspi_bus = map_or_ignoring_broken_pipe(Bus::new(&session_bus, executor).await, None, Some)?;
+                        let bus = Bus::new(&session_bus, executor).await;
+                        status = connection_status(bus.as_ref().map(Bus::address));
+                        atspi_bus = map_or_ignoring_broken_pipe(bus, None, Some)?;
                     }
                 }
+                AppContext::write().connection_status = status;
                 if atspi_bus.is_some() {
                     for (_, adapter) in &adapters {
                         adapter.register_tree();
@@ -191,6 +255,19 @@ async fn run_event_loop(
     }
 }
 
+/// Removes the adapter with the given ID, returning `true` if it was
+/// the last one, in which case the application should be removed from
+/// the accessibility bus.
+fn remove_adapter(adapters: &mut Vec<(usize, LazyAdapter)>, id: usize) -> bool {
+    match adapters.binary_search_by(|adapter| adapter.0.cmp(&id)) {
+        Ok(index) => {
+            adapters.remove(index);
+            adapters.is_empty()
+        }
+        Err(_) => false,
+    }
+}
+
 async fn process_adapter_message(
     atspi_bus: &Option<Bus>,
     adapters: &mut Vec<(usize, LazyAdapter)>,
@@ -198,15 +275,21 @@ async fn process_adapter_message(
 ) -> zbus::Result<()> {
     match message {
         Message::AddAdapter { id, adapter } => {
+            let is_first = adapters.is_empty();
             adapters.push((id, adapter));
-            if atspi_bus.is_some() {
+            if let Some(bus) = atspi_bus {
+                if is_first {
+                    bus.register_root_node().await?;
+                }
                 let adapter = &adapters.last_mut().unwrap().1;
                 adapter.register_tree();
             }
         }
         Message::RemoveAdapter { id } => {
-            if let Ok(index) = adapters.binary_search_by(|adapter| adapter.0.cmp(&id)) {
-                adapters.remove(index);
+            if remove_adapter(adapters, id) {
+                if let Some(bus) = atspi_bus {
+                    bus.unregister_root_node().await?;
+                }
             }
         }
         Message::RegisterInterfaces {
@@ -248,3 +331,36 @@ async fn process_adapter_message(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::AdapterImpl;
+    use once_cell::sync::Lazy;
+
+    fn uninitialized_adapter() -> LazyAdapter {
+        Arc::new(Lazy::new(Box::new(|| -> AdapterImpl { unreachable!() })))
+    }
+
+    #[test]
+    fn removing_last_adapter_deregisters_application() {
+        let mut adapters = vec![(0, uninitialized_adapter()), (1, uninitialized_adapter())];
+        assert!(!remove_adapter(&mut adapters, 0));
+        assert!(!remove_adapter(&mut adapters, 0));
+        assert!(remove_adapter(&mut adapters, 1));
+        assert!(adapters.is_empty());
+    }
+
+    #[test]
+    fn connection_status_reflects_result() {
+        assert_eq!(
+            connection_status(Ok("unix:path=/run/user/1000/at-spi/bus")),
+            ConnectionStatus::Connected("unix:path=/run/user/1000/at-spi/bus".into())
+        );
+        let error = zbus::Error::Address("invalid address".into());
+        let ConnectionStatus::Failed(message) = connection_status(Err(&error)) else {
+            panic!("expected a failed connection");
+        };
+        assert!(message.contains("invalid address"));
+    }
+}
@@ -30,4 +30,5 @@ mod node;
 mod util;
 
 pub use adapter::Adapter;
+pub use context::ConnectionStatus;
 pub(crate) use node::{PlatformNode, PlatformRootNode};
@@ -14,20 +14,21 @@ use crate::{
         interfaces::{Action as AtspiAction, ObjectEvent, Property},
         ObjectId, OwnedObjectAddress, Rect as AtspiRect,
     },
-    context::{AdapterAndContext, AppContext, Context},
+    context::{is_any_tree_busy, AdapterAndContext, AppContext, Context},
     filters::{filter, filter_detached},
     util::WindowBounds,
 };
 use accesskit::{
-    Action, ActionData, ActionRequest, Affine, Checked, DefaultActionVerb, Live, NodeId, Point,
-    Rect, Role,
+    Action, ActionData, ActionRequest, Affine, AriaCurrent, Checked, DefaultActionVerb, Live,
+    NodeId, Orientation, Point, Rect, Role,
 };
 use accesskit_consumer::{DetachedNode, FilterResult, Node, NodeState, TreeState};
 use atspi::{
-    CoordType, Interface, InterfaceSet, Layer, Live as AtspiLive, Role as AtspiRole, State,
-    StateSet,
+    CoordType, Interface, InterfaceSet, Layer, Live as AtspiLive, RelationType, Role as AtspiRole,
+    ScrollType, State, StateSet,
 };
 use std::{
+    collections::{HashMap, HashSet},
     iter::FusedIterator,
     sync::{Arc, RwLockReadGuard, Weak},
 };
@@ -41,6 +42,7 @@ pub(crate) enum NodeWrapper<'a> {
     DetachedNode {
         adapter: usize,
         node: &'a DetachedNode,
+        current_state: &'a TreeState,
     },
 }
 
@@ -67,7 +69,11 @@ impl<'a> NodeWrapper<'a> {
     }
 
     pub fn description(&self) -> String {
-        String::new()
+        match self {
+            Self::Node { node, .. } => node.computed_description(),
+            Self::DetachedNode { node, .. } => node.computed_description(),
+        }
+        .unwrap_or_default()
     }
 
     pub fn parent_id(&self) -> Option<NodeId> {
@@ -113,6 +119,9 @@ impl<'a> NodeWrapper<'a> {
         if self.node_state().has_role_description() {
             return AtspiRole::Extended;
         }
+        if self.node_state().is_primary_content() {
+            return AtspiRole::Landmark;
+        }
 
         match self.node_state().role() {
             Role::Alert => AtspiRole::Notification,
@@ -199,6 +208,10 @@ impl<'a> NodeWrapper<'a> {
             Role::GraphicsObject => AtspiRole::Panel,
             Role::GraphicsSymbol => AtspiRole::Image,
             Role::Grid => AtspiRole::Table,
+            // Orca announces panels, so a group without a name, which only
+            // exists to lay out its children, gets the quiet section role.
+            // Naming or unnaming it emits a role change.
+            Role::Group if self.name().is_none() => AtspiRole::Section,
             Role::Group => AtspiRole::Panel,
             Role::Heading => AtspiRole::Heading,
             Role::Iframe | Role::IframePresentational => AtspiRole::InternalFrame,
@@ -326,8 +339,13 @@ impl<'a> NodeWrapper<'a> {
 
     fn is_focused(&self) -> bool {
         match self {
-            Self::Node { node, .. } => node.is_focused(),
-            Self::DetachedNode { node, .. } => node.is_focused(),
+            Self::Node { node, .. } => node
+                .tree_state
+                .focus()
+                .map_or(false, |focus| effective_focus(focus).id() == node.id()),
+            Self::DetachedNode { node, .. } => {
+                node.is_focused() && node.active_descendant().is_none()
+            }
         }
     }
 
@@ -368,14 +386,23 @@ impl<'a> NodeWrapper<'a> {
             });
         }
 
+        match state.orientation() {
+            Some(Orientation::Horizontal) => atspi_state.insert(State::Horizontal),
+            Some(Orientation::Vertical) => atspi_state.insert(State::Vertical),
+            None => {}
+        }
+
         // Special case for indeterminate progressbar.
         if state.role() == Role::ProgressIndicator && state.numeric_value().is_none() {
             atspi_state.insert(State::Indeterminate);
         }
 
+        if state.is_mixed() {
+            atspi_state.insert(State::Indeterminate);
+        }
+
         // Checked state
         match state.checked() {
-            Some(Checked::Mixed) => atspi_state.insert(State::Indeterminate),
             Some(Checked::True) if atspi_role == AtspiRole::ToggleButton => {
                 atspi_state.insert(State::Pressed)
             }
@@ -383,7 +410,9 @@ impl<'a> NodeWrapper<'a> {
             _ => {}
         }
 
-        if state.is_read_only_supported() && state.is_read_only_or_disabled() {
+        if state.is_read_only_supported()
+            && (self.is_effectively_read_only() || state.is_disabled())
+        {
             atspi_state.insert(State::ReadOnly);
         } else {
             atspi_state.insert(State::Enabled | State::Sensitive);
@@ -403,6 +432,44 @@ impl<'a> NodeWrapper<'a> {
         }
     }
 
+    fn is_effectively_read_only(&self) -> bool {
+        match self {
+            Self::Node { node, .. } => node.is_effectively_read_only(),
+            Self::DetachedNode {
+                node,
+                current_state,
+                ..
+            } => node.is_effectively_read_only(current_state),
+        }
+    }
+
+    /// Returns the object attributes that AT-SPI clients read for
+    /// information with no dedicated property, such as set positions and
+    /// the `current` token used to announce progress like "step 2 of 5".
+    pub fn attributes(&self) -> HashMap<String, String> {
+        let state = self.node_state();
+        let mut attributes = HashMap::new();
+        if let Some(position) = state.position_in_set() {
+            attributes.insert("posinset".into(), position.to_string());
+        }
+        if let Some(size) = state.size_of_set() {
+            attributes.insert("setsize".into(), size.to_string());
+        }
+        let current = match state.aria_current() {
+            None | Some(AriaCurrent::False) => None,
+            Some(AriaCurrent::True) => Some("true"),
+            Some(AriaCurrent::Page) => Some("page"),
+            Some(AriaCurrent::Step) => Some("step"),
+            Some(AriaCurrent::Location) => Some("location"),
+            Some(AriaCurrent::Date) => Some("date"),
+            Some(AriaCurrent::Time) => Some("time"),
+        };
+        if let Some(current) = current {
+            attributes.insert("current".into(), current.into());
+        }
+        attributes
+    }
+
     pub fn interfaces(&self) -> InterfaceSet {
         let state = self.node_state();
         let mut interfaces = InterfaceSet::new(Interface::Accessible);
@@ -415,9 +482,40 @@ impl<'a> NodeWrapper<'a> {
         if self.current_value().is_some() {
             interfaces.insert(Interface::Value);
         }
+        if state.is_text_input() {
+            interfaces.insert(Interface::EditableText);
+        }
+        if state.is_hyperlink() {
+            interfaces.insert(Interface::Hyperlink);
+        }
+        if self.has_hyperlinks() {
+            interfaces.insert(Interface::Hypertext);
+        }
         interfaces
     }
 
+    /// Whether this is a text container with links among its children.
+    fn has_hyperlinks(&self) -> bool {
+        let Self::Node { node, .. } = self else {
+            return false;
+        };
+        let is_text_container = node.is_text_input()
+            || matches!(
+                node.role(),
+                Role::Blockquote
+                    | Role::Caption
+                    | Role::Document
+                    | Role::Heading
+                    | Role::FigureCaption
+                    | Role::ListItem
+                    | Role::Paragraph
+            );
+        is_text_container
+            && node
+                .filtered_children(&filter)
+                .any(|child| child.is_hyperlink())
+    }
+
     pub(crate) fn live(&self) -> AtspiLive {
         let live = match self {
             Self::Node { node, .. } => node.live(),
@@ -430,16 +528,38 @@ impl<'a> NodeWrapper<'a> {
         }
     }
 
+    fn actions(&self) -> impl Iterator<Item = Action> {
+        let state = self.node_state();
+        let default = state.default_action_verb().map(|_| Action::Default);
+        let show_context_menu = state
+            .supports_show_context_menu()
+            .then_some(Action::ShowContextMenu);
+        let increment = state.supports_increment().then_some(Action::Increment);
+        let decrement = state.supports_decrement().then_some(Action::Decrement);
+        default
+            .into_iter()
+            .chain(show_context_menu)
+            .chain(increment)
+            .chain(decrement)
+    }
+
+    fn action(&self, index: i32) -> Option<Action> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.actions().nth(index))
+    }
+
     fn n_actions(&self) -> i32 {
-        match self.node_state().default_action_verb() {
-            Some(_) => 1,
-            None => 0,
-        }
+        self.actions().count() as i32
     }
 
     fn get_action_name(&self, index: i32) -> String {
-        if index != 0 {
-            return String::new();
+        match self.action(index) {
+            Some(Action::Default) => (),
+            Some(Action::ShowContextMenu) => return "showContextMenu".into(),
+            Some(Action::Increment) => return "increment".into(),
+            Some(Action::Decrement) => return "decrement".into(),
+            _ => return String::new(),
         }
         String::from(match self.node_state().default_action_verb() {
             Some(DefaultActionVerb::Click) => "click",
@@ -482,27 +602,82 @@ impl<'a> NodeWrapper<'a> {
         }
     }
 
+    /// Returns the links among this node's filtered children, along with
+    /// the character range that each of them occupies in this node's text.
+    /// This adapter exposes that text as the names of the children, in order,
+    /// so each child, link or not, contributes the characters of its name.
+    fn hyperlinks(&self) -> Vec<(NodeId, i32, i32)> {
+        let Self::Node { node, .. } = self else {
+            return Vec::new();
+        };
+        let mut links = Vec::new();
+        let mut offset = 0;
+        for child in node.filtered_children(&filter) {
+            let length = child.name().map_or(0, |name| name.chars().count() as i32);
+            if child.is_hyperlink() {
+                links.push((child.id(), offset, offset + length));
+            }
+            offset += length;
+        }
+        links
+    }
+
+    fn hyperlink_range(&self) -> Option<(i32, i32)> {
+        let Self::Node { adapter, node } = self else {
+            return None;
+        };
+        let parent = node.filtered_parent(&filter)?;
+        let parent = NodeWrapper::Node {
+            adapter: *adapter,
+            node: &parent,
+        };
+        parent
+            .hyperlinks()
+            .into_iter()
+            .find(|(id, _, _)| *id == node.id())
+            .map(|(_, start, end)| (start, end))
+    }
+
     fn current_value(&self) -> Option<f64> {
         self.node_state().numeric_value()
     }
 
+    fn value_text(&self) -> String {
+        match self {
+            Self::Node { node, .. } => node.value(),
+            Self::DetachedNode { node, .. } => node.value(),
+        }
+        .unwrap_or_default()
+    }
+
     pub(crate) fn notify_changes(
         &self,
         window_bounds: &WindowBounds,
         adapter: &AdapterImpl,
         old: &NodeWrapper<'_>,
     ) {
+        // States, names and roles can depend on other nodes, so they're
+        // always compared, but bounds and children only come from the
+        // node's own properties.
+        let changes = self.node_state().diff(old.node_state());
         self.notify_state_changes(adapter, old);
         self.notify_property_changes(adapter, old);
-        self.notify_bounds_changes(window_bounds, adapter, old);
-        self.notify_children_changes(adapter, old);
+        if changes.bounds {
+            self.notify_bounds_changes(window_bounds, adapter, old);
+        }
+        if changes.children {
+            self.notify_children_changes(adapter, old);
+        }
     }
 
     fn notify_state_changes(&self, adapter: &AdapterImpl, old: &NodeWrapper<'_>) {
         let adapter_id = self.adapter();
         let old_state = old.state(true);
         let new_state = self.state(true);
-        let changed_states = old_state ^ new_state;
+        // Focus changes are reported by the adapter when the tree's focus
+        // or the focused node's active descendant moves.
+        let mut changed_states = old_state ^ new_state;
+        changed_states.remove(State::Focused);
         for state in changed_states.iter() {
             adapter.emit_object_event(
                 ObjectId::Node {
@@ -524,19 +699,8 @@ impl<'a> NodeWrapper<'a> {
                     adapter: adapter_id,
                     node: self.id(),
                 },
-                ObjectEvent::PropertyChanged(Property::Name(name.clone())),
+                ObjectEvent::PropertyChanged(Property::Name(name)),
             );
-
-            let live = self.live();
-            if live != AtspiLive::None {
-                adapter.emit_object_event(
-                    ObjectId::Node {
-                        adapter: adapter_id,
-                        node: self.id(),
-                    },
-                    ObjectEvent::Announcement(name, live),
-                );
-            }
         }
         let description = self.description();
         if description != old.description() {
@@ -577,6 +741,19 @@ impl<'a> NodeWrapper<'a> {
                     },
                     ObjectEvent::PropertyChanged(Property::Value(value)),
                 );
+            } else {
+                // Both values are reported as `accessible-value`, so the
+                // text only needs its own event if the number didn't change.
+                let text = self.value_text();
+                if text != old.value_text() {
+                    adapter.emit_object_event(
+                        ObjectId::Node {
+                            adapter: adapter_id,
+                            node: self.id(),
+                        },
+                        ObjectEvent::PropertyChanged(Property::ValueText(text)),
+                    );
+                }
             }
         }
     }
@@ -598,18 +775,46 @@ impl<'a> NodeWrapper<'a> {
         }
     }
 
+    /// Approximates the filtered children that were reported for this node
+    /// before the update. The old tree is gone by the time we're notified,
+    /// so children that are still in the tree are filtered by their new
+    /// state, while children that have since been removed are assumed to
+    /// have been included.
+    fn previous_filtered_child_ids(&self, old: &NodeWrapper<'_>) -> Vec<NodeId> {
+        let Self::Node { node, .. } = self else {
+            unreachable!()
+        };
+        let mut result = Vec::new();
+        for id in old.child_ids() {
+            match node.tree_state.node_by_id(id) {
+                None => result.push(id),
+                Some(child) => match filter(&child) {
+                    FilterResult::Include => result.push(id),
+                    FilterResult::ExcludeNode => {
+                        result.extend(child.filtered_children(&filter).map(|child| child.id()))
+                    }
+                    FilterResult::ExcludeSubtree => (),
+                },
+            }
+        }
+        result
+    }
+
     fn notify_children_changes(&self, adapter: &AdapterImpl, old: &NodeWrapper<'_>) {
         let adapter_id = self.adapter();
-        let old_children = old.child_ids().collect::<Vec<NodeId>>();
+        let old_children = self.previous_filtered_child_ids(old);
         let filtered_children = self.filtered_child_ids().collect::<Vec<NodeId>>();
-        for (index, child) in filtered_children.iter().enumerate() {
-            if !old_children.contains(child) {
+        // Removals are emitted from the last index to the first, followed by
+        // additions from the first index to the last, so that each index is
+        // valid at the point where the event is applied.
+        for (index, child) in old_children.iter().enumerate().rev() {
+            if !filtered_children.contains(child) {
                 adapter.emit_object_event(
                     ObjectId::Node {
                         adapter: adapter_id,
                         node: self.id(),
                     },
-                    ObjectEvent::ChildAdded(
+                    ObjectEvent::ChildRemoved(
                         index,
                         ObjectId::Node {
                             adapter: adapter_id,
@@ -619,17 +824,20 @@ impl<'a> NodeWrapper<'a> {
                 );
             }
         }
-        for child in old_children.into_iter() {
-            if !filtered_children.contains(&child) {
+        for (index, child) in filtered_children.iter().enumerate() {
+            if !old_children.contains(child) {
                 adapter.emit_object_event(
                     ObjectId::Node {
                         adapter: adapter_id,
                         node: self.id(),
                     },
-                    ObjectEvent::ChildRemoved(ObjectId::Node {
-                        adapter: adapter_id,
-                        node: child,
-                    }),
+                    ObjectEvent::ChildAdded(
+                        index,
+                        ObjectId::Node {
+                            adapter: adapter_id,
+                            node: *child,
+                        },
+                    ),
                 );
             }
         }
@@ -640,6 +848,23 @@ pub(crate) fn unknown_object(id: &ObjectId) -> fdo::Error {
     fdo::Error::UnknownObject(id.path().to_string())
 }
 
+/// Returns the node that should be reported as focused when `node` has
+/// keyboard focus, following active descendants as far as they lead.
+pub(crate) fn effective_focus(node: Node<'_>) -> Node<'_> {
+    let mut visited = HashSet::new();
+    let mut node = node;
+    while visited.insert(node.id()) {
+        match node
+            .active_descendant()
+            .and_then(|id| node.tree_state.node_by_id(id))
+        {
+            Some(descendant) => node = descendant,
+            None => break,
+        }
+    }
+    node
+}
+
 #[derive(Clone)]
 pub(crate) struct PlatformNode {
     context: Weak<Context>,
@@ -814,6 +1039,57 @@ impl PlatformNode {
         })
     }
 
+    pub fn attributes(&self) -> fdo::Result<HashMap<String, String>> {
+        self.resolve(|node| {
+            let wrapper = self.node_wrapper(&node);
+            Ok(wrapper.attributes())
+        })
+    }
+
+    pub fn relation_set(&self) -> fdo::Result<Vec<(RelationType, Vec<ObjectId>)>> {
+        self.resolve(|node| {
+            let to_ids = |nodes: &mut dyn Iterator<Item = Node<'_>>| {
+                nodes
+                    .filter(|node| filter(node) == FilterResult::Include)
+                    .map(|node| ObjectId::Node {
+                        adapter: self.adapter_id,
+                        node: node.id(),
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let mut relations = Vec::new();
+            let mut push = |relation, targets: &mut dyn Iterator<Item = Node<'_>>| {
+                let targets = to_ids(targets);
+                if !targets.is_empty() {
+                    relations.push((relation, targets));
+                }
+            };
+            push(RelationType::LabelledBy, &mut node.explicit_labels());
+            push(RelationType::LabelFor, &mut node.label_for().into_iter());
+            push(RelationType::DescribedBy, &mut node.described_by());
+            push(
+                RelationType::DescriptionFor,
+                &mut node.description_for().into_iter(),
+            );
+            push(RelationType::ControllerFor, &mut node.controlled());
+            push(
+                RelationType::ControlledBy,
+                &mut node.controlled_by().into_iter(),
+            );
+            push(RelationType::MemberOf, &mut node.member_of().into_iter());
+            push(RelationType::FlowsTo, &mut node.flow_to());
+            push(RelationType::FlowsFrom, &mut node.flow_from().into_iter());
+            push(RelationType::Details, &mut node.details());
+            push(
+                RelationType::DetailsFor,
+                &mut node.details_for().into_iter(),
+            );
+            push(RelationType::NodeParentOf, &mut node.owned());
+            push(RelationType::NodeChildOf, &mut node.owner().into_iter());
+            Ok(relations)
+        })
+    }
+
     pub fn n_actions(&self) -> fdo::Result<i32> {
         self.resolve(|node| {
             let wrapper = self.node_wrapper(&node);
@@ -845,11 +1121,12 @@ impl PlatformNode {
     }
 
     pub fn do_action(&self, index: i32) -> fdo::Result<bool> {
-        if index != 0 {
+        let action = self.resolve(|node| Ok(self.node_wrapper(&node).action(index)))?;
+        let Some(action) = action else {
             return Ok(false);
-        }
+        };
         self.do_action_internal(|_, _| ActionRequest {
-            action: Action::Default,
+            action,
             target: self.node_id,
             data: None,
         })?;
@@ -943,6 +1220,20 @@ impl PlatformNode {
         Ok(true)
     }
 
+    pub fn scroll_to(&self, _scroll_type: ScrollType) -> fdo::Result<bool> {
+        if !self.resolve(|node| Ok(node.supports_scroll_into_view()))? {
+            return Ok(false);
+        }
+        // AccessKit doesn't let the AT choose where the node ends up, so
+        // every scroll type maps to the same request.
+        self.do_action_internal(|_, _| ActionRequest {
+            action: Action::ScrollIntoView,
+            target: self.node_id,
+            data: None,
+        })?;
+        Ok(true)
+    }
+
     pub fn scroll_to_point(&self, coord_type: CoordType, x: i32, y: i32) -> fdo::Result<bool> {
         self.do_action_internal(|tree_state, context| {
             let window_bounds = context.read_root_window_bounds();
@@ -958,6 +1249,74 @@ impl PlatformNode {
         Ok(true)
     }
 
+    pub fn hyperlink_start_index(&self) -> fdo::Result<i32> {
+        self.resolve(|node| {
+            let wrapper = self.node_wrapper(&node);
+            Ok(wrapper.hyperlink_range().map_or(-1, |(start, _)| start))
+        })
+    }
+
+    pub fn hyperlink_end_index(&self) -> fdo::Result<i32> {
+        self.resolve(|node| {
+            let wrapper = self.node_wrapper(&node);
+            Ok(wrapper.hyperlink_range().map_or(-1, |(_, end)| end))
+        })
+    }
+
+    pub fn hyperlink_object(&self, index: i32) -> fdo::Result<Option<ObjectId>> {
+        self.resolve(|node| {
+            Ok((index == 0).then(|| ObjectId::Node {
+                adapter: self.adapter_id,
+                node: node.id(),
+            }))
+        })
+    }
+
+    pub fn hyperlink_uri(&self, index: i32) -> fdo::Result<String> {
+        self.resolve(|node| {
+            if index != 0 {
+                return Ok(String::new());
+            }
+            Ok(node.url().map(String::from).unwrap_or_default())
+        })
+    }
+
+    pub fn hyperlink_is_valid(&self) -> fdo::Result<bool> {
+        self.resolve(|node| Ok(node.url().is_some()))
+    }
+
+    pub fn n_links(&self) -> fdo::Result<i32> {
+        self.resolve(|node| {
+            let wrapper = self.node_wrapper(&node);
+            Ok(wrapper.hyperlinks().len() as i32)
+        })
+    }
+
+    pub fn get_link(&self, link_index: i32) -> fdo::Result<Option<ObjectId>> {
+        self.resolve(|node| {
+            let wrapper = self.node_wrapper(&node);
+            let links = wrapper.hyperlinks();
+            Ok(usize::try_from(link_index)
+                .ok()
+                .and_then(|index| links.get(index))
+                .map(|(id, _, _)| ObjectId::Node {
+                    adapter: self.adapter_id,
+                    node: *id,
+                }))
+        })
+    }
+
+    pub fn get_link_index(&self, character_index: i32) -> fdo::Result<i32> {
+        self.resolve(|node| {
+            let wrapper = self.node_wrapper(&node);
+            Ok(wrapper
+                .hyperlinks()
+                .iter()
+                .position(|(_, start, end)| (*start..*end).contains(&character_index))
+                .map_or(-1, |index| index as i32))
+        })
+    }
+
     pub fn minimum_value(&self) -> fdo::Result<f64> {
         self.resolve(|node| Ok(node.state().min_numeric_value().unwrap_or(std::f64::MIN)))
     }
@@ -977,6 +1336,13 @@ impl PlatformNode {
         })
     }
 
+    pub fn value_text(&self) -> fdo::Result<String> {
+        self.resolve(|node| {
+            let wrapper = self.node_wrapper(&node);
+            Ok(wrapper.value_text())
+        })
+    }
+
     pub fn set_current_value(&self, value: f64) -> fdo::Result<()> {
         self.do_action_internal(|_, _| ActionRequest {
             action: Action::SetValue,
@@ -984,6 +1350,85 @@ impl PlatformNode {
             data: Some(ActionData::NumericValue(value)),
         })
     }
+
+    /// Sends the action requests built by `f` if the node is an editable
+    /// text input, and returns whether any were sent. Read-only and
+    /// disabled inputs reject all edits.
+    fn edit_text<F>(&self, f: F) -> fdo::Result<bool>
+    where
+        for<'a> F: FnOnce(&Node<'a>) -> Option<Vec<ActionRequest>>,
+    {
+        let context = self.upgrade_context()?;
+        let tree = context.read_tree();
+        let Some(node) = tree.state().node_by_id(self.node_id) else {
+            return Err(unknown_object(&self.accessible_id()));
+        };
+        let requests =
+            if node.is_text_input() && !node.is_effectively_read_only() && !node.is_disabled() {
+                f(&node)
+            } else {
+                None
+            };
+        drop(tree);
+        let Some(requests) = requests else {
+            return Ok(false);
+        };
+        for request in requests {
+            context.do_action(request);
+        }
+        Ok(true)
+    }
+
+    /// Selects the text between the given offsets, counted in characters
+    /// as AT-SPI does, and replaces the selection with `text`.
+    fn replace_text(&self, start: i32, end: i32, text: &str) -> fdo::Result<bool> {
+        self.edit_text(|node| {
+            if !node.supports_text_ranges() {
+                return None;
+            }
+            let start = node.text_position_from_global_usv_index(start.try_into().ok()?)?;
+            let end = node.text_position_from_global_usv_index(end.try_into().ok()?)?;
+            let mut range = start.to_degenerate_range();
+            range.set_end(end);
+            Some(vec![
+                ActionRequest {
+                    action: Action::SetTextSelection,
+                    target: self.node_id,
+                    data: Some(ActionData::SetTextSelection(range.to_text_selection())),
+                },
+                ActionRequest {
+                    action: Action::ReplaceSelectedText,
+                    target: self.node_id,
+                    data: Some(ActionData::Value(text.into())),
+                },
+            ])
+        })
+    }
+
+    pub fn set_text_contents(&self, text: &str) -> fdo::Result<bool> {
+        self.edit_text(|_| {
+            Some(vec![ActionRequest {
+                action: Action::SetValue,
+                target: self.node_id,
+                data: Some(ActionData::Value(text.into())),
+            }])
+        })
+    }
+
+    pub fn insert_text(&self, position: i32, text: &str, length: i32) -> fdo::Result<bool> {
+        let text = match usize::try_from(length) {
+            Ok(length) => text.chars().take(length).collect(),
+            Err(_) => text.to_string(),
+        };
+        self.replace_text(position, position, &text)
+    }
+
+    pub fn delete_text(&self, start: i32, end: i32) -> fdo::Result<bool> {
+        if end < start {
+            return Ok(false);
+        }
+        self.replace_text(start, end, "")
+    }
 }
 
 #[derive(Clone)]
@@ -1050,6 +1495,23 @@ impl PlatformRootNode {
         })
     }
 
+    pub(crate) fn state(&self) -> fdo::Result<StateSet> {
+        self.resolve_app_context(|context| {
+            let is_busy = is_any_tree_busy(
+                context
+                    .adapters
+                    .iter()
+                    .filter_map(AdapterAndContext::upgrade)
+                    .map(|(_, context)| context),
+            );
+            let mut state = StateSet::empty();
+            if is_busy {
+                state.insert(State::Busy);
+            }
+            Ok(state)
+        })
+    }
+
     pub(crate) fn toolkit_name(&self) -> fdo::Result<String> {
         self.resolve_app_context(|context| Ok(context.toolkit_name.clone().unwrap_or_default()))
     }
@@ -1068,3 +1530,704 @@ impl PlatformRootNode {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use accesskit::{
+        ActionHandler, NodeBuilder, NodeClassSet, TextPosition, TextSelection, Tree, TreeUpdate,
+    };
+    use accesskit_consumer::Tree as ConsumerTree;
+    use std::sync::Mutex;
+
+    struct NullActionHandler;
+
+    impl ActionHandler for NullActionHandler {
+        fn do_action(&mut self, _request: ActionRequest) {}
+    }
+
+    struct RecordingActionHandler(Arc<Mutex<Vec<ActionRequest>>>);
+
+    impl ActionHandler for RecordingActionHandler {
+        fn do_action(&mut self, request: ActionRequest) {
+            self.0.lock().unwrap().push(request);
+        }
+    }
+
+    fn test_context(
+        update: TreeUpdate,
+        action_handler: impl ActionHandler + Send + 'static,
+    ) -> Arc<Context> {
+        Context::new(
+            ConsumerTree::new(update, false),
+            Box::new(action_handler),
+            WindowBounds::default(),
+        )
+    }
+
+    fn platform_node(context: &Arc<Context>, id: NodeId) -> PlatformNode {
+        PlatformNode::new(Arc::downgrade(context), 0, id)
+    }
+
+    #[test]
+    fn show_context_menu_action() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::Button);
+        builder.set_default_action_verb(DefaultActionVerb::Click);
+        builder.add_action(Action::ShowContextMenu);
+        let update = TreeUpdate {
+            nodes: vec![(NodeId(0), builder.build(&mut classes))],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let context = test_context(update, RecordingActionHandler(requests.clone()));
+        let node = platform_node(&context, NodeId(0));
+        assert_eq!(node.n_actions(), Ok(2));
+        assert_eq!(node.get_action_name(0).unwrap(), "click");
+        assert_eq!(node.get_action_name(1).unwrap(), "showContextMenu");
+        assert_eq!(node.do_action(1), Ok(true));
+        assert_eq!(node.do_action(2), Ok(false));
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![ActionRequest {
+                action: Action::ShowContextMenu,
+                target: NodeId(0),
+                data: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn scroll_to_requests_scroll_into_view() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::ListItem);
+        builder.add_action(Action::ScrollIntoView);
+        let update = TreeUpdate {
+            nodes: vec![(NodeId(0), builder.build(&mut classes))],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let context = test_context(update, RecordingActionHandler(requests.clone()));
+        let node = platform_node(&context, NodeId(0));
+        assert_eq!(node.scroll_to(ScrollType::TopLeft), Ok(true));
+        assert_eq!(node.scroll_to(ScrollType::Anywhere), Ok(true));
+        let request = ActionRequest {
+            action: Action::ScrollIntoView,
+            target: NodeId(0),
+            data: None,
+        };
+        assert_eq!(*requests.lock().unwrap(), vec![request.clone(), request]);
+    }
+
+    #[test]
+    fn scroll_to_requires_scroll_into_view() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![(
+                NodeId(0),
+                NodeBuilder::new(Role::ListItem).build(&mut classes),
+            )],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let context = test_context(update, RecordingActionHandler(requests.clone()));
+        let node = platform_node(&context, NodeId(0));
+        assert_eq!(node.scroll_to(ScrollType::Anywhere), Ok(false));
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn increment_ignores_slider_orientation() {
+        let mut classes = NodeClassSet::new();
+        let slider = |classes: &mut NodeClassSet, orientation: Option<Orientation>| {
+            let mut builder = NodeBuilder::new(Role::Slider);
+            builder.set_numeric_value(5.0);
+            builder.add_action(Action::Increment);
+            builder.add_action(Action::Decrement);
+            if let Some(orientation) = orientation {
+                builder.set_orientation(orientation);
+            }
+            builder.build(classes)
+        };
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Window);
+                    builder.set_children(vec![NodeId(1), NodeId(2)]);
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), slider(&mut classes, None)),
+                (NodeId(2), slider(&mut classes, Some(Orientation::Vertical))),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let context = test_context(update, RecordingActionHandler(requests.clone()));
+        let horizontal = platform_node(&context, NodeId(1));
+        let vertical = platform_node(&context, NodeId(2));
+        let states = |node: &PlatformNode| {
+            let states = node.state().unwrap();
+            (
+                states.contains(State::Horizontal),
+                states.contains(State::Vertical),
+            )
+        };
+        assert_eq!(states(&horizontal), (true, false));
+        assert_eq!(states(&vertical), (false, true));
+        for node in [&horizontal, &vertical] {
+            assert_eq!(node.get_action_name(0).unwrap(), "increment");
+            assert_eq!(node.get_action_name(1).unwrap(), "decrement");
+            assert_eq!(node.do_action(0), Ok(true));
+            assert_eq!(node.do_action(1), Ok(true));
+        }
+        let request = |target, action| ActionRequest {
+            action,
+            target: NodeId(target),
+            data: None,
+        };
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![
+                request(1, Action::Increment),
+                request(1, Action::Decrement),
+                request(2, Action::Increment),
+                request(2, Action::Decrement),
+            ]
+        );
+    }
+
+    #[test]
+    fn meter_reports_value() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::Meter);
+        builder.set_numeric_value(40.0);
+        builder.set_min_numeric_value(0.0);
+        builder.set_max_numeric_value(100.0);
+        builder.set_numeric_value_step(5.0);
+        builder.set_value("40%");
+        let update = TreeUpdate {
+            nodes: vec![(NodeId(0), builder.build(&mut classes))],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let context = test_context(update, NullActionHandler);
+        let node = platform_node(&context, NodeId(0));
+        let interfaces = node
+            .resolve(|n| Ok(node.node_wrapper(&n).interfaces()))
+            .unwrap();
+        assert!(interfaces.contains(Interface::Value));
+        assert_eq!(node.current_value(), Ok(40.0));
+        assert_eq!(node.minimum_value(), Ok(0.0));
+        assert_eq!(node.maximum_value(), Ok(100.0));
+        assert_eq!(node.minimum_increment(), Ok(5.0));
+        assert_eq!(node.value_text(), Ok("40%".into()));
+    }
+
+    #[test]
+    fn table_is_named_by_caption() {
+        let mut classes = NodeClassSet::new();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), {
+                    let mut builder = NodeBuilder::new(Role::Table);
+                    builder.set_caption(NodeId(1));
+                    builder.push_child(NodeId(1));
+                    builder.build(&mut classes)
+                }),
+                (NodeId(1), {
+                    let mut builder = NodeBuilder::new(Role::Caption);
+                    builder.set_name("Opening hours");
+                    builder.build(&mut classes)
+                }),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let context = test_context(update, NullActionHandler);
+        let table = platform_node(&context, NodeId(0));
+        assert_eq!(table.name(), Ok("Opening hours".into()));
+    }
+
+    #[test]
+    fn progress_reports_counts() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::ProgressIndicator);
+        builder.set_progress(450.0, 1000.0, "450 of 1000 MB");
+        let update = TreeUpdate {
+            nodes: vec![(NodeId(0), builder.build(&mut classes))],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let context = test_context(update, NullActionHandler);
+        let node = platform_node(&context, NodeId(0));
+        assert_eq!(node.current_value(), Ok(450.0));
+        assert_eq!(node.minimum_value(), Ok(0.0));
+        assert_eq!(node.maximum_value(), Ok(1000.0));
+        assert_eq!(node.value_text(), Ok("450 of 1000 MB".into()));
+    }
+
+    #[test]
+    fn date_input_reports_range() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let mut classes = NodeClassSet::new();
+        let min = UNIX_EPOCH + Duration::from_secs(1_704_067_200);
+        let max = UNIX_EPOCH + Duration::from_secs(1_735_603_200);
+        let mut builder = NodeBuilder::new(Role::DateInput);
+        builder.set_date_time_range(min, max);
+        builder.set_numeric_value(1_718_409_600_000.0);
+        let update = TreeUpdate {
+            nodes: vec![(NodeId(0), builder.build(&mut classes))],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let context = test_context(update, NullActionHandler);
+        let node = platform_node(&context, NodeId(0));
+        let interfaces = node
+            .resolve(|n| Ok(node.node_wrapper(&n).interfaces()))
+            .unwrap();
+        assert!(interfaces.contains(Interface::Value));
+        assert_eq!(node.minimum_value(), Ok(1_704_067_200_000.0));
+        assert_eq!(node.maximum_value(), Ok(1_735_603_200_000.0));
+        assert_eq!(node.current_value(), Ok(1_718_409_600_000.0));
+    }
+
+    #[test]
+    fn current_step_attributes() {
+        let mut classes = NodeClassSet::new();
+        let mut list = NodeBuilder::new(Role::List);
+        list.set_children(vec![NodeId(1)]);
+        let mut step = NodeBuilder::new(Role::ListItem);
+        step.set_aria_current(AriaCurrent::Step);
+        step.set_position_in_set(2);
+        step.set_size_of_set(5);
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), list.build(&mut classes)),
+                (NodeId(1), step.build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let context = test_context(update, NullActionHandler);
+        let node = platform_node(&context, NodeId(1));
+        let attributes = node.attributes().unwrap();
+        assert_eq!(attributes.len(), 3);
+        assert_eq!(attributes["posinset"], "2");
+        assert_eq!(attributes["setsize"], "5");
+        assert_eq!(attributes["current"], "step");
+        let list = platform_node(&context, NodeId(0));
+        assert!(list.attributes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn increment_and_decrement_actions() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::SpinButton);
+        builder.set_numeric_value(3.0);
+        builder.add_action(Action::Increment);
+        builder.add_action(Action::Decrement);
+        let update = TreeUpdate {
+            nodes: vec![(NodeId(0), builder.build(&mut classes))],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let context = test_context(update, RecordingActionHandler(requests.clone()));
+        let node = platform_node(&context, NodeId(0));
+        assert_eq!(node.n_actions(), Ok(2));
+        assert_eq!(node.get_action_name(0).unwrap(), "increment");
+        assert_eq!(node.get_action_name(1).unwrap(), "decrement");
+        assert_eq!(node.do_action(0), Ok(true));
+        assert_eq!(node.do_action(1), Ok(true));
+        let request = |action| ActionRequest {
+            action,
+            target: NodeId(0),
+            data: None,
+        };
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![request(Action::Increment), request(Action::Decrement)]
+        );
+    }
+
+    fn text_input_context(
+        read_only: bool,
+        requests: Arc<Mutex<Vec<ActionRequest>>>,
+    ) -> Arc<Context> {
+        let mut classes = NodeClassSet::new();
+        let mut input = NodeBuilder::new(Role::TextInput);
+        input.set_children(vec![NodeId(1)]);
+        if read_only {
+            input.set_read_only();
+        }
+        let mut text = NodeBuilder::new(Role::InlineTextBox);
+        text.set_value("hello");
+        text.set_character_lengths([1, 1, 1, 1, 1]);
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), input.build(&mut classes)),
+                (NodeId(1), text.build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        test_context(update, RecordingActionHandler(requests))
+    }
+
+    #[test]
+    fn insert_text_reaches_handler() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let context = text_input_context(false, requests.clone());
+        let node = platform_node(&context, NodeId(0));
+        let interfaces = node
+            .resolve(|n| Ok(node.node_wrapper(&n).interfaces()))
+            .unwrap();
+        assert!(interfaces.contains(Interface::EditableText));
+        assert_eq!(node.insert_text(5, " world!", 6), Ok(true));
+        let position = |character_index| TextPosition {
+            node: NodeId(1),
+            character_index,
+        };
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![
+                ActionRequest {
+                    action: Action::SetTextSelection,
+                    target: NodeId(0),
+                    data: Some(ActionData::SetTextSelection(TextSelection {
+                        anchor: position(5),
+                        focus: position(5),
+                    })),
+                },
+                ActionRequest {
+                    action: Action::ReplaceSelectedText,
+                    target: NodeId(0),
+                    data: Some(ActionData::Value(" world".into())),
+                },
+            ]
+        );
+        requests.lock().unwrap().clear();
+        assert_eq!(node.delete_text(1, 3), Ok(true));
+        assert_eq!(
+            requests.lock().unwrap()[0].data,
+            Some(ActionData::SetTextSelection(TextSelection {
+                anchor: position(1),
+                focus: position(3),
+            }))
+        );
+        assert_eq!(node.insert_text(6, "!", -1), Ok(false));
+    }
+
+    #[test]
+    fn read_only_input_rejects_edits() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let context = text_input_context(true, requests.clone());
+        let node = platform_node(&context, NodeId(0));
+        assert_eq!(node.insert_text(0, "hi", -1), Ok(false));
+        assert_eq!(node.delete_text(0, 1), Ok(false));
+        assert_eq!(node.set_text_contents("hi"), Ok(false));
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn owns_relation() {
+        let mut classes = NodeClassSet::new();
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![NodeId(1), NodeId(2)]);
+        let mut button = NodeBuilder::new(Role::Button);
+        button.set_name("Menu");
+        button.push_owned(NodeId(2));
+        let mut menu = NodeBuilder::new(Role::Menu);
+        menu.set_name("Options");
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), window.build(&mut classes)),
+                (NodeId(1), button.build(&mut classes)),
+                (NodeId(2), menu.build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let context = test_context(update, NullActionHandler);
+        let id = |node| ObjectId::Node { adapter: 0, node };
+        let button = platform_node(&context, NodeId(1));
+        assert_eq!(
+            button.relation_set().unwrap(),
+            vec![(RelationType::NodeParentOf, vec![id(NodeId(2))])]
+        );
+        let menu = platform_node(&context, NodeId(2));
+        assert_eq!(
+            menu.relation_set().unwrap(),
+            vec![(RelationType::NodeChildOf, vec![id(NodeId(1))])]
+        );
+    }
+
+    #[test]
+    fn relations() {
+        let mut classes = NodeClassSet::new();
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![NodeId(1), NodeId(2), NodeId(3), NodeId(4)]);
+        let mut label = NodeBuilder::new(Role::StaticText);
+        label.set_name("Volume");
+        let mut hint = NodeBuilder::new(Role::StaticText);
+        hint.set_name("Applies to all outputs");
+        let mut slider = NodeBuilder::new(Role::Slider);
+        slider.push_labelled_by(NodeId(1));
+        slider.push_described_by(NodeId(2));
+        slider.push_controlled(NodeId(4));
+        slider.set_numeric_value(5.0);
+        let mut meter = NodeBuilder::new(Role::Meter);
+        meter.set_name("Level");
+        meter.set_numeric_value(5.0);
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), window.build(&mut classes)),
+                (NodeId(1), label.build(&mut classes)),
+                (NodeId(2), hint.build(&mut classes)),
+                (NodeId(3), slider.build(&mut classes)),
+                (NodeId(4), meter.build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(3),
+        };
+        let context = test_context(update, NullActionHandler);
+        let id = |node| ObjectId::Node { adapter: 0, node };
+        let relations = |node_id| platform_node(&context, node_id).relation_set().unwrap();
+        assert_eq!(
+            relations(NodeId(3)),
+            vec![
+                (RelationType::LabelledBy, vec![id(NodeId(1))]),
+                (RelationType::DescribedBy, vec![id(NodeId(2))]),
+                (RelationType::ControllerFor, vec![id(NodeId(4))]),
+            ]
+        );
+        assert_eq!(
+            relations(NodeId(1)),
+            vec![(RelationType::LabelFor, vec![id(NodeId(3))])]
+        );
+        assert_eq!(
+            relations(NodeId(2)),
+            vec![(RelationType::DescriptionFor, vec![id(NodeId(3))])]
+        );
+        assert_eq!(
+            relations(NodeId(4)),
+            vec![(RelationType::ControlledBy, vec![id(NodeId(3))])]
+        );
+    }
+
+    #[test]
+    fn custom_role() {
+        let mut classes = NodeClassSet::new();
+        let mut builder = NodeBuilder::new(Role::Group);
+        builder.set_custom_role("feed", "news feed");
+        let update = TreeUpdate {
+            nodes: vec![(NodeId(0), builder.build(&mut classes))],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let context = test_context(update, NullActionHandler);
+        let node = platform_node(&context, NodeId(0));
+        assert_eq!(node.role(), Ok(AtspiRole::Extended));
+        assert_eq!(node.localized_role_name().unwrap(), "news feed");
+    }
+
+    #[test]
+    fn generic_containers_are_sections() {
+        let mut classes = NodeClassSet::new();
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![NodeId(1), NodeId(2), NodeId(3)]);
+        let mut focused_container = NodeBuilder::new(Role::GenericContainer);
+        focused_container.add_action(Action::Focus);
+        let mut named_group = NodeBuilder::new(Role::Group);
+        named_group.set_name("Shipping address");
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), window.build(&mut classes)),
+                (NodeId(1), focused_container.build(&mut classes)),
+                (NodeId(2), NodeBuilder::new(Role::Group).build(&mut classes)),
+                (NodeId(3), named_group.build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(1),
+        };
+        let context = test_context(update, NullActionHandler);
+        let role = |id| platform_node(&context, id).role();
+        assert_eq!(role(NodeId(1)), Ok(AtspiRole::Section));
+        assert_eq!(role(NodeId(2)), Ok(AtspiRole::Section));
+        assert_eq!(role(NodeId(3)), Ok(AtspiRole::Panel));
+    }
+
+    #[test]
+    fn primary_content_is_landmark() {
+        let mut classes = NodeClassSet::new();
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![NodeId(1)]);
+        let mut content = NodeBuilder::new(Role::ScrollView);
+        content.set_is_primary_content();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), window.build(&mut classes)),
+                (NodeId(1), content.build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let context = test_context(update, NullActionHandler);
+        let node = platform_node(&context, NodeId(1));
+        assert_eq!(node.role(), Ok(AtspiRole::Landmark));
+    }
+
+    #[test]
+    fn status_is_polite_status_bar() {
+        let mut classes = NodeClassSet::new();
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![NodeId(1)]);
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), window.build(&mut classes)),
+                (
+                    NodeId(1),
+                    NodeBuilder::new(Role::Status).build(&mut classes),
+                ),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = ConsumerTree::new(update, false);
+        let status = tree.state().node_by_id(NodeId(1)).unwrap();
+        let wrapper = NodeWrapper::Node {
+            adapter: 0,
+            node: &status,
+        };
+        assert_eq!(wrapper.role(), AtspiRole::StatusBar);
+        assert_eq!(wrapper.live(), AtspiLive::Polite);
+    }
+
+    #[test]
+    fn mixed_group_is_indeterminate() {
+        let mut classes = NodeClassSet::new();
+        let mut tree = NodeBuilder::new(Role::Tree);
+        tree.set_children(vec![NodeId(1)]);
+        let mut group = NodeBuilder::new(Role::TreeItem);
+        group.set_mixed();
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), tree.build(&mut classes)),
+                (NodeId(1), group.build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let context = test_context(update, NullActionHandler);
+        let node = platform_node(&context, NodeId(1));
+        let state = node.state().unwrap();
+        assert!(state.contains(State::Indeterminate));
+        assert!(!state.contains(State::Checked));
+    }
+
+    #[test]
+    fn hyperlinks_in_paragraph() {
+        let mut classes = NodeClassSet::new();
+        let mut node = |role, name: Option<&str>, url: Option<&str>| {
+            let mut builder = NodeBuilder::new(role);
+            if let Some(name) = name {
+                builder.set_name(name);
+            }
+            if let Some(url) = url {
+                builder.set_url(url);
+            }
+            builder.build(&mut classes)
+        };
+        let mut paragraph = NodeBuilder::new(Role::Paragraph);
+        paragraph.set_children(vec![NodeId(1), NodeId(2), NodeId(3), NodeId(4), NodeId(5)]);
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(1), node(Role::StaticText, Some("Visit "), None)),
+                (
+                    NodeId(2),
+                    node(Role::Link, Some("AccessKit"), Some("https://accesskit.dev")),
+                ),
+                (NodeId(3), node(Role::StaticText, Some(" or "), None)),
+                // An inline node in rich text that only has a URL.
+                (
+                    NodeId(4),
+                    node(Role::StaticText, Some("GitHub"), Some("https://github.com")),
+                ),
+                (NodeId(5), node(Role::StaticText, Some("."), None)),
+                (NodeId(0), paragraph.build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = ConsumerTree::new(update, false);
+        let state = tree.state();
+
+        let paragraph = state.root();
+        let wrapper = NodeWrapper::Node {
+            adapter: 0,
+            node: &paragraph,
+        };
+        assert!(wrapper.interfaces().contains(Interface::Hypertext));
+        assert_eq!(
+            wrapper.hyperlinks(),
+            vec![(NodeId(2), 6, 15), (NodeId(4), 19, 25)]
+        );
+
+        let link = state.node_by_id(NodeId(4)).unwrap();
+        let wrapper = NodeWrapper::Node {
+            adapter: 0,
+            node: &link,
+        };
+        assert!(wrapper.interfaces().contains(Interface::Hyperlink));
+        assert_eq!(wrapper.hyperlink_range(), Some((19, 25)));
+        assert_eq!(link.url(), Some("https://github.com"));
+
+        // Removed nodes have no links to report.
+        let detached = link.detached();
+        let wrapper = NodeWrapper::DetachedNode {
+            adapter: 0,
+            node: &detached,
+            current_state: state,
+        };
+        assert_eq!(wrapper.hyperlinks(), vec![]);
+        assert_eq!(wrapper.hyperlink_range(), None);
+    }
+
+    #[test]
+    fn hypertext_requires_link_in_text_container() {
+        let mut classes = NodeClassSet::new();
+        let mut window = NodeBuilder::new(Role::Window);
+        window.set_children(vec![NodeId(1), NodeId(3)]);
+        let mut paragraph = NodeBuilder::new(Role::Paragraph);
+        paragraph.set_children(vec![NodeId(2)]);
+        let update = TreeUpdate {
+            nodes: vec![
+                (NodeId(0), window.build(&mut classes)),
+                (NodeId(1), paragraph.build(&mut classes)),
+                (
+                    NodeId(2),
+                    NodeBuilder::new(Role::StaticText).build(&mut classes),
+                ),
+                (NodeId(3), NodeBuilder::new(Role::Link).build(&mut classes)),
+            ],
+            tree: Some(Tree::new(NodeId(0))),
+            focus: NodeId(0),
+        };
+        let tree = ConsumerTree::new(update, false);
+        let state = tree.state();
+
+        for id in [NodeId(0), NodeId(1)] {
+            let node = state.node_by_id(id).unwrap();
+            let wrapper = NodeWrapper::Node {
+                adapter: 0,
+                node: &node,
+            };
+            assert!(!wrapper.interfaces().contains(Interface::Hypertext));
+        }
+    }
+}
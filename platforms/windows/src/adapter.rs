@@ -4,8 +4,10 @@
 // the LICENSE-MIT file), at your option.
 
 use accesskit::{ActionHandler, Live, NodeId, Role, TreeUpdate};
-use accesskit_consumer::{DetachedNode, FilterResult, Node, Tree, TreeChangeHandler, TreeState};
-use std::{collections::HashSet, sync::Arc};
+use accesskit_consumer::{
+    AnnouncementFilter, DetachedNode, FilterResult, Node, Tree, TreeChangeHandler, TreeState,
+};
+use std::{collections::HashSet, mem, sync::Arc};
 use windows::Win32::{
     Foundation::*,
     UI::{Accessibility::*, WindowsAndMessaging::*},
@@ -16,13 +18,16 @@ use crate::{
     filters::{filter, filter_detached},
     init::UiaInitMarker,
     node::{NodeWrapper, PlatformNode},
-    util::QueuedEvent,
+    util::{QueuedEvent, VariantFactory},
 };
 
 struct AdapterChangeHandler<'a> {
     context: &'a Arc<Context>,
     queue: Vec<QueuedEvent>,
     text_changed: HashSet<NodeId>,
+    enabled_changed: HashSet<NodeId>,
+    disabled_toggled: Vec<NodeId>,
+    announcements: AnnouncementFilter,
 }
 
 impl AdapterChangeHandler<'_> {
@@ -49,6 +54,50 @@ impl AdapterChangeHandler<'_> {
         self.text_changed.insert(id);
     }
 
+    fn announce(&mut self, node: &Node, element: IRawElementProviderSimple) {
+        if self.announcements.allow(node, &node.name().unwrap()) {
+            self.queue.push(QueuedEvent::Simple {
+                element,
+                event_id: UIA_LiveRegionChangedEventId,
+            });
+        }
+    }
+
+    /// Descendants that aren't disabled themselves inherit a change
+    /// to whether an ancestor is disabled, but may not be in the update.
+    /// This runs once all updated nodes have been handled, so nodes that
+    /// already had an `IsEnabled` change queued are skipped.
+    fn enqueue_inherited_enabled_changes(&mut self, tree_state: &TreeState) {
+        for id in mem::take(&mut self.disabled_toggled) {
+            if let Some(node) = tree_state.node_by_id(id) {
+                self.enqueue_descendant_enabled_changes(&node, !node.is_effectively_disabled());
+            }
+        }
+    }
+
+    fn enqueue_descendant_enabled_changes(&mut self, node: &Node, is_enabled: bool) {
+        for child in node.children() {
+            if child.is_disabled() {
+                continue;
+            }
+            if filter(&child) == FilterResult::Include && self.enabled_changed.insert(child.id()) {
+                let platform_node = PlatformNode::new(self.context, child.id());
+                self.queue.push(QueuedEvent::PropertyChanged {
+                    element: platform_node.into(),
+                    property_id: UIA_IsEnabledPropertyId,
+                    old_value: VariantFactory::from(!is_enabled).into(),
+                    new_value: VariantFactory::from(is_enabled).into(),
+                });
+            }
+            self.enqueue_descendant_enabled_changes(&child, is_enabled);
+        }
+    }
+
+    fn into_queued_events(mut self, tree_state: &TreeState) -> QueuedEvents {
+        self.enqueue_inherited_enabled_changes(tree_state);
+        QueuedEvents(self.queue)
+    }
+
     fn insert_text_change_if_needed(&mut self, node: &Node) {
         if node.role() != Role::InlineTextBox {
             return;
@@ -83,10 +132,7 @@ impl TreeChangeHandler for AdapterChangeHandler<'_> {
         if node.name().is_some() && node.live() != Live::Off {
             let platform_node = PlatformNode::new(self.context, node.id());
             let element: IRawElementProviderSimple = platform_node.into();
-            self.queue.push(QueuedEvent::Simple {
-                element,
-                event_id: UIA_LiveRegionChangedEventId,
-            });
+            self.announce(node, element);
         }
     }
 
@@ -94,12 +140,20 @@ impl TreeChangeHandler for AdapterChangeHandler<'_> {
         if old_node.raw_value() != new_node.raw_value() {
             self.insert_text_change_if_needed(new_node);
         }
+        if old_node.is_effectively_disabled(new_node.tree_state)
+            != new_node.is_effectively_disabled()
+        {
+            self.enabled_changed.insert(new_node.id());
+            if old_node.is_disabled() != new_node.is_disabled() {
+                self.disabled_toggled.push(new_node.id());
+            }
+        }
         if filter(new_node) != FilterResult::Include {
             return;
         }
         let platform_node = PlatformNode::new(self.context, new_node.id());
         let element: IRawElementProviderSimple = platform_node.into();
-        let old_wrapper = NodeWrapper::DetachedNode(old_node);
+        let old_wrapper = NodeWrapper::DetachedNode(old_node, new_node.tree_state);
         let new_wrapper = NodeWrapper::Node(new_node);
         new_wrapper.enqueue_property_changes(&mut self.queue, &element, &old_wrapper);
         if new_node.name().is_some()
@@ -108,10 +162,7 @@ impl TreeChangeHandler for AdapterChangeHandler<'_> {
                 || new_node.live() != old_node.live()
                 || filter_detached(old_node) != FilterResult::Include)
         {
-            self.queue.push(QueuedEvent::Simple {
-                element,
-                event_id: UIA_LiveRegionChangedEventId,
-            });
+            self.announce(new_node, element);
         }
     }
 
@@ -140,6 +191,7 @@ impl TreeChangeHandler for AdapterChangeHandler<'_> {
 
 pub struct Adapter {
     context: Arc<Context>,
+    is_msaa_bridge_enabled: bool,
 }
 
 impl Adapter {
@@ -159,7 +211,28 @@ impl Adapter {
             Tree::new(initial_state, is_window_focused),
             action_handler,
         );
-        Self { context }
+        Self {
+            context,
+            is_msaa_bridge_enabled: true,
+        }
+    }
+
+    /// Set whether the adapter answers legacy MSAA requests for the client
+    /// area of the window through the UIA-to-MSAA bridge. This is enabled
+    /// by default. Applications that only target UIA clients can disable it
+    /// to avoid the overhead of the bridge; [`Adapter::handle_wm_getobject`]
+    /// then declines `OBJID_CLIENT` requests, leaving them to `DefWindowProc`.
+    pub fn with_msaa_bridge(mut self, enabled: bool) -> Self {
+        self.is_msaa_bridge_enabled = enabled;
+        self
+    }
+
+    /// Set the maximum number of live region announcements made in response
+    /// to a single update, or `None`, the default, for no limit. Refer to
+    /// [`accesskit_consumer::Tree::set_max_announcements`] for details.
+    pub fn set_max_announcements(&self, value: Option<usize>) {
+        let mut tree = self.context.tree.write().unwrap();
+        tree.set_max_announcements(value);
     }
 
     fn change_handler(&self) -> AdapterChangeHandler {
@@ -167,6 +240,9 @@ impl Adapter {
             context: &self.context,
             queue: Vec::new(),
             text_changed: HashSet::new(),
+            enabled_changed: HashSet::new(),
+            disabled_toggled: Vec::new(),
+            announcements: AnnouncementFilter::default(),
         }
     }
 
@@ -181,7 +257,7 @@ impl Adapter {
         let mut handler = self.change_handler();
         let mut tree = self.context.tree.write().unwrap();
         tree.update_and_process_changes(update, &mut handler);
-        QueuedEvents(handler.queue)
+        handler.into_queued_events(tree.state())
     }
 
     /// Update the tree state based on whether the window is focused.
@@ -195,7 +271,7 @@ impl Adapter {
         let mut handler = self.change_handler();
         let mut tree = self.context.tree.write().unwrap();
         tree.update_host_focus_state_and_process_changes(is_focused, &mut handler);
-        QueuedEvents(handler.queue)
+        handler.into_queued_events(tree.state())
     }
 
     fn root_platform_node(&self) -> PlatformNode {
@@ -232,6 +308,9 @@ impl Adapter {
         if objid < 0 && objid != UiaRootObjectId && objid != OBJID_CLIENT.0 {
             return None;
         }
+        if objid == OBJID_CLIENT.0 && !self.is_msaa_bridge_enabled {
+            return None;
+        }
 
         let el: IRawElementProviderSimple = self.root_platform_node().into();
         Some(WmGetObjectResult {
@@ -307,6 +386,13 @@ impl QueuedEvents {
     }
 }
 
+#[cfg(test)]
+impl QueuedEvents {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &QueuedEvent> {
+        self.0.iter()
+    }
+}
+
 // We explicitly want to allow the queued events to be sent to the UI thread,
 // so implement Send even though windows-rs doesn't implement it for all
 // contained types. This is safe because we're not using COM threading.
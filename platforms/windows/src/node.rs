@@ -11,7 +11,8 @@
 #![allow(non_upper_case_globals)]
 
 use accesskit::{
-    Action, ActionData, ActionRequest, Checked, Live, NodeId, NodeIdContent, Point, Role,
+    Action, ActionData, ActionRequest, Checked, Live, NodeId, NodeIdContent, Orientation, Point,
+    Role,
 };
 use accesskit_consumer::{DetachedNode, FilterResult, Node, NodeState, TreeState};
 use paste::paste;
@@ -30,6 +31,10 @@ use crate::{
 
 const RUNTIME_ID_SIZE: usize = 3;
 
+/// The most increment or decrement actions that a single
+/// `IRangeValueProvider::SetValue` call is mapped onto.
+const MAX_VALUE_STEPS: usize = 100;
+
 fn runtime_id_from_node_id(id: NodeId) -> [i32; RUNTIME_ID_SIZE] {
     static_assertions::assert_eq_size!(NodeIdContent, u64);
     let id = id.0;
@@ -42,14 +47,14 @@ fn runtime_id_from_node_id(id: NodeId) -> [i32; RUNTIME_ID_SIZE] {
 
 pub(crate) enum NodeWrapper<'a> {
     Node(&'a Node<'a>),
-    DetachedNode(&'a DetachedNode),
+    DetachedNode(&'a DetachedNode, &'a TreeState),
 }
 
 impl<'a> NodeWrapper<'a> {
     fn node_state(&self) -> &'a NodeState {
         match self {
             Self::Node(node) => node.state(),
-            Self::DetachedNode(node) => node.state(),
+            Self::DetachedNode(node, _) => node.state(),
         }
     }
 
@@ -270,23 +275,108 @@ impl<'a> NodeWrapper<'a> {
         self.node_state().role_description()
     }
 
+    fn aria_role(&self) -> Option<&str> {
+        let state = self.node_state();
+        state.aria_role().or(match state.role() {
+            Role::AlertDialog => Some("alertdialog"),
+            _ if self.is_dialog() => Some("dialog"),
+            _ => None,
+        })
+    }
+
+    fn is_dialog(&self) -> bool {
+        let state = self.node_state();
+        match state.role() {
+            Role::Dialog | Role::AlertDialog => true,
+            // A modal node is only presented as a dialog if its role
+            // doesn't already have a more specific mapping.
+            Role::Unknown | Role::GenericContainer | Role::Group | Role::Pane => state.is_modal(),
+            _ => false,
+        }
+    }
+
+    fn orientation(&self) -> OrientationType {
+        match self.node_state().orientation() {
+            Some(Orientation::Horizontal) => OrientationType_Horizontal,
+            Some(Orientation::Vertical) => OrientationType_Vertical,
+            None => OrientationType_None,
+        }
+    }
+
+    fn landmark_type(&self) -> Option<UIA_LANDMARKTYPE_ID> {
+        let state = self.node_state();
+        if state.is_primary_content() || state.role() == Role::Main {
+            Some(UIA_MainLandmarkTypeId)
+        } else {
+            None
+        }
+    }
+
+    fn position_in_set(&self) -> Option<i32> {
+        self.node_state()
+            .position_in_set()
+            .and_then(|p| p.try_into().ok())
+    }
+
+    fn size_of_set(&self) -> Option<i32> {
+        self.node_state()
+            .size_of_set()
+            .and_then(|s| s.try_into().ok())
+    }
+
+    fn level(&self) -> Option<i32> {
+        self.node_state()
+            .hierarchical_level()
+            .and_then(|l| l.try_into().ok())
+    }
+
+    fn help_text(&self) -> Option<&str> {
+        self.node_state().tooltip()
+    }
+
+    fn item_status(&self) -> Option<&str> {
+        self.node_state().item_status()
+    }
+
+    fn automation_id(&self) -> Option<&str> {
+        self.node_state().automation_id()
+    }
+
+    fn access_key(&self) -> Option<&str> {
+        self.node_state().access_key()
+    }
+
+    fn accelerator_key(&self) -> Option<&str> {
+        self.node_state().keyboard_shortcut()
+    }
+
     fn name(&self) -> Option<String> {
         match self {
             Self::Node(node) => node.name(),
-            Self::DetachedNode(node) => node.name(),
+            Self::DetachedNode(node, _) => node.name(),
+        }
+    }
+
+    fn full_description(&self) -> Option<String> {
+        match self {
+            Self::Node(node) => node.computed_description(),
+            Self::DetachedNode(node, _) => node.computed_description(),
         }
     }
 
     fn is_content_element(&self) -> bool {
         let result = match self {
             Self::Node(node) => filter(node),
-            Self::DetachedNode(node) => filter_detached(node),
+            Self::DetachedNode(node, _) => filter_detached(node),
         };
         result == FilterResult::Include
     }
 
     fn is_enabled(&self) -> bool {
-        !self.node_state().is_disabled()
+        match self {
+            Self::Node(node) => !node.is_effectively_disabled(),
+            Self::DetachedNode(node, current_state) => !node.is_effectively_disabled(current_state),
+        }
     }
 
     fn is_focusable(&self) -> bool {
@@ -296,14 +386,14 @@ impl<'a> NodeWrapper<'a> {
     fn is_focused(&self) -> bool {
         match self {
             Self::Node(node) => node.is_focused(),
-            Self::DetachedNode(node) => node.is_focused(),
+            Self::DetachedNode(node, _) => node.is_focused(),
         }
     }
 
     fn live_setting(&self) -> LiveSetting {
         let live = match self {
             Self::Node(node) => node.live(),
-            Self::DetachedNode(node) => node.live(),
+            Self::DetachedNode(node, _) => node.live(),
         };
         match live {
             Live::Off => Off,
@@ -331,7 +421,7 @@ impl<'a> NodeWrapper<'a> {
     fn is_value_pattern_supported(&self) -> bool {
         match self {
             Self::Node(node) => node.has_value(),
-            Self::DetachedNode(node) => node.has_value(),
+            Self::DetachedNode(node, _) => node.has_value(),
         }
     }
 
@@ -342,12 +432,15 @@ impl<'a> NodeWrapper<'a> {
     fn value(&self) -> String {
         match self {
             Self::Node(node) => node.value().unwrap(),
-            Self::DetachedNode(node) => node.value().unwrap(),
+            Self::DetachedNode(node, _) => node.value().unwrap(),
         }
     }
 
     fn is_read_only(&self) -> bool {
-        self.node_state().is_read_only()
+        match self {
+            Self::Node(node) => node.is_effectively_read_only(),
+            Self::DetachedNode(node, current_state) => node.is_effectively_read_only(current_state),
+        }
     }
 
     fn numeric_value(&self) -> f64 {
@@ -373,45 +466,86 @@ impl<'a> NodeWrapper<'a> {
     }
 
     fn is_selection_item_pattern_supported(&self) -> bool {
-        match self.node_state().role() {
-            // TODO: tables (#29)
-            // https://www.w3.org/TR/core-aam-1.1/#mapping_state-property_table
-            // SelectionItem.IsSelected is exposed when aria-checked is True or
-            // False, for 'radio' and 'menuitemradio' roles.
-            Role::RadioButton | Role::MenuItemRadio => matches!(
-                self.node_state().checked(),
-                Some(Checked::True | Checked::False)
-            ),
-            // https://www.w3.org/TR/wai-aria-1.1/#aria-selected
-            // SelectionItem.IsSelected is exposed when aria-select is True or False.
-            Role::ListBoxOption
-            | Role::ListItem
-            | Role::MenuListOption
-            | Role::Tab
-            | Role::TreeItem => self.node_state().is_selected().is_some(),
-            _ => false,
-        }
+        self.node_state().is_selectable_item()
     }
 
     fn is_selected(&self) -> bool {
-        match self.node_state().role() {
-            // https://www.w3.org/TR/core-aam-1.1/#mapping_state-property_table
-            // SelectionItem.IsSelected is set according to the True or False
-            // value of aria-checked for 'radio' and 'menuitemradio' roles.
-            Role::RadioButton | Role::MenuItemRadio => {
-                self.node_state().checked() == Some(Checked::True)
-            }
-            // https://www.w3.org/TR/wai-aria-1.1/#aria-selected
-            // SelectionItem.IsSelected is set according to the True or False
-            // value of aria-selected.
-            _ => self.node_state().is_selected().unwrap_or(false),
+        self.node_state().is_selected_item()
+    }
+
+    fn is_in_multiselectable_container(&self) -> bool {
+        match self {
+            Self::Node(node) => node
+                .selection_container()
+                .map_or(false, |container| container.is_multiselectable()),
+            Self::DetachedNode(..) => false,
         }
     }
 
+    fn is_selection_pattern_supported(&self) -> bool {
+        self.node_state().is_selection_container()
+    }
+
+    fn can_select_multiple(&self) -> bool {
+        self.node_state().is_multiselectable()
+    }
+
+    fn is_selection_required(&self) -> bool {
+        self.node_state().is_required()
+    }
+
+    fn is_grid_pattern_supported(&self) -> bool {
+        self.node_state().is_table()
+    }
+
+    fn row_count(&self) -> i32 {
+        self.node_state().table_row_count().unwrap_or(0) as i32
+    }
+
+    fn column_count(&self) -> i32 {
+        self.node_state().table_column_count().unwrap_or(0) as i32
+    }
+
+    fn is_table_pattern_supported(&self) -> bool {
+        self.is_grid_pattern_supported()
+    }
+
+    fn row_or_column_major(&self) -> RowOrColumnMajor {
+        RowOrColumnMajor_RowMajor
+    }
+
+    fn is_grid_item_pattern_supported(&self) -> bool {
+        self.node_state().is_table_cell()
+    }
+
+    fn cell_row(&self) -> i32 {
+        self.node_state().table_cell_row_index().unwrap_or(0) as i32
+    }
+
+    fn cell_column(&self) -> i32 {
+        self.node_state().table_cell_column_index().unwrap_or(0) as i32
+    }
+
+    fn cell_row_span(&self) -> i32 {
+        self.node_state().table_cell_row_span() as i32
+    }
+
+    fn cell_column_span(&self) -> i32 {
+        self.node_state().table_cell_column_span() as i32
+    }
+
+    fn is_table_item_pattern_supported(&self) -> bool {
+        self.is_grid_item_pattern_supported()
+    }
+
+    fn is_scroll_item_pattern_supported(&self) -> bool {
+        self.node_state().supports_scroll_into_view()
+    }
+
     fn is_text_pattern_supported(&self) -> bool {
         match self {
             Self::Node(node) => node.supports_text_ranges(),
-            Self::DetachedNode(node) => node.supports_text_ranges(),
+            Self::DetachedNode(node, _) => node.supports_text_ranges(),
         }
     }
 
@@ -432,14 +566,23 @@ impl<'a> NodeWrapper<'a> {
         element: &IRawElementProviderSimple,
         old: &NodeWrapper,
     ) {
-        if self.is_selection_item_pattern_supported()
-            && self.is_selected()
-            && !(old.is_selection_item_pattern_supported() && old.is_selected())
-        {
-            queue.push(QueuedEvent::Simple {
-                element: element.clone(),
-                event_id: UIA_SelectionItem_ElementSelectedEventId,
-            });
+        let is_selected = self.is_selected();
+        let was_selected = old.is_selected();
+        if is_selected != was_selected {
+            // Items of a multi-selectable container report additions and
+            // removals; otherwise, only the newly selected item is reported.
+            let event_id = match (is_selected, self.is_in_multiselectable_container()) {
+                (true, false) => Some(UIA_SelectionItem_ElementSelectedEventId),
+                (true, true) => Some(UIA_SelectionItem_ElementAddedToSelectionEventId),
+                (false, true) => Some(UIA_SelectionItem_ElementRemovedFromSelectionEventId),
+                (false, false) => None,
+            };
+            if let Some(event_id) = event_id {
+                queue.push(QueuedEvent::Simple {
+                    element: element.clone(),
+                    event_id,
+                });
+            }
         }
         if self.is_text_pattern_supported()
             && old.is_text_pattern_supported()
@@ -473,6 +616,7 @@ impl<'a> NodeWrapper<'a> {
 
 #[implement(
     IRawElementProviderSimple,
+    IRawElementProviderSimple2,
     IRawElementProviderFragment,
     IRawElementProviderFragmentRoot,
     IToggleProvider,
@@ -480,6 +624,12 @@ impl<'a> NodeWrapper<'a> {
     IValueProvider,
     IRangeValueProvider,
     ISelectionItemProvider,
+    ISelectionProvider,
+    IGridProvider,
+    ITableProvider,
+    IGridItemProvider,
+    ITableItemProvider,
+    IScrollItemProvider,
     ITextProvider
 )]
 pub(crate) struct PlatformNode {
@@ -601,6 +751,17 @@ impl PlatformNode {
             node_id,
         }
     }
+
+    fn relative_array(&self, nodes: &[Node]) -> Result<*mut SAFEARRAY> {
+        let elements = nodes
+            .iter()
+            .map(|node| {
+                let element: IRawElementProviderSimple = self.relative(node.id()).into();
+                element.cast::<IUnknown>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(safe_array_from_com_slice(&elements))
+    }
 }
 
 #[allow(non_snake_case)]
@@ -630,6 +791,24 @@ impl IRawElementProviderSimple_Impl for PlatformNode {
                     }
                 }
                 match property_id {
+                    UIA_LabeledByPropertyId => {
+                        let label = node.labelled_by().find(|label| label.id() != node.id());
+                        if let Some(label) = label {
+                            let element: IRawElementProviderSimple =
+                                self.relative(label.id()).into();
+                            result = element.cast::<IUnknown>()?.into();
+                        }
+                    }
+                    UIA_ControllerForPropertyId => {
+                        let controlled = node
+                            .controlled()
+                            .filter(|controlled| controlled.id() != node.id())
+                            .collect::<Vec<_>>();
+                        if !controlled.is_empty() {
+                            result =
+                                VariantFactory::from_com_array(self.relative_array(&controlled)?);
+                        }
+                    }
                     UIA_FrameworkIdPropertyId => result = state.toolkit_name().into(),
                     UIA_ProviderDescriptionPropertyId => {
                         result = app_and_toolkit_description(state).into()
@@ -652,6 +831,17 @@ impl IRawElementProviderSimple_Impl for PlatformNode {
     }
 }
 
+#[allow(non_snake_case)]
+impl IRawElementProviderSimple2_Impl for PlatformNode {
+    fn ShowContextMenu(&self) -> Result<()> {
+        self.do_action(|| ActionRequest {
+            action: Action::ShowContextMenu,
+            target: self.node_id,
+            data: None,
+        })
+    }
+}
+
 #[allow(non_snake_case)]
 impl IRawElementProviderFragment_Impl for PlatformNode {
     fn Navigate(&self, direction: NavigateDirection) -> Result<IRawElementProviderFragment> {
@@ -846,13 +1036,26 @@ macro_rules! patterns {
 properties! {
     (ControlType, control_type),
     (LocalizedControlType, localized_control_type),
+    (AriaRole, aria_role),
+    (LandmarkType, landmark_type),
     (Name, name),
+    (HelpText, help_text),
+    (FullDescription, full_description),
+    (AutomationId, automation_id),
+    (ItemStatus, item_status),
+    (AccessKey, access_key),
+    (AcceleratorKey, accelerator_key),
     (IsContentElement, is_content_element),
     (IsControlElement, is_content_element),
     (IsEnabled, is_enabled),
     (IsKeyboardFocusable, is_focusable),
     (HasKeyboardFocus, is_focused),
-    (LiveSetting, live_setting)
+    (LiveSetting, live_setting),
+    (PositionInSet, position_in_set),
+    (SizeOfSet, size_of_set),
+    (Level, level),
+    (IsDialog, is_dialog),
+    (Orientation, orientation)
 }
 
 patterns! {
@@ -865,7 +1068,10 @@ patterns! {
     )),
     (Invoke, is_invoke_pattern_supported, (), (
         fn Invoke(&self) -> Result<()> {
-            self.do_default_action()
+            self.do_default_action()?;
+            // SAFETY: We know we're running inside a full COM implementation.
+            let element: IRawElementProviderSimple = unsafe { self.cast() }?;
+            unsafe { UiaRaiseAutomationEvent(&element, UIA_Invoke_InvokedEventId) }
         }
     )),
     (Value, is_value_pattern_supported, (
@@ -892,13 +1098,60 @@ patterns! {
         (LargeChange, numeric_value_jump, f64)
     ), (
         fn SetValue(&self, value: f64) -> Result<()> {
-            self.do_action(|| {
-                ActionRequest {
-                    action: Action::SetValue,
-                    target: self.node_id,
-                    data: Some(ActionData::NumericValue(value)),
+            if !value.is_finite() {
+                return Err(invalid_arg());
+            }
+            // UIA has no increment or decrement operation, so when a node
+            // only declares those actions, map a move in either direction
+            // onto as many steps as it takes to reach the new value,
+            // clamped to the node's range and to a bounded number of steps.
+            let request = self.resolve(|node| {
+                if node.supports_set_value() {
+                    return Ok(Some((
+                        Action::SetValue,
+                        Some(ActionData::NumericValue(value)),
+                        1,
+                    )));
                 }
-            })
+                let current = match node.numeric_value() {
+                    Some(current) if current.is_finite() => current,
+                    _ => return Err(invalid_operation()),
+                };
+                let mut target = value;
+                if let Some(min) = node.min_numeric_value().filter(|min| min.is_finite()) {
+                    target = target.max(min);
+                }
+                if let Some(max) = node.max_numeric_value().filter(|max| max.is_finite()) {
+                    target = target.min(max);
+                }
+                if target == current {
+                    return Ok(None);
+                }
+                let action = if target > current && node.supports_increment() {
+                    Action::Increment
+                } else if target < current && node.supports_decrement() {
+                    Action::Decrement
+                } else {
+                    return Err(invalid_operation());
+                };
+                let count = match node.numeric_value_step() {
+                    Some(step) if step.is_finite() && step > 0.0 => {
+                        ((target - current).abs() / step).ceil() as usize
+                    }
+                    _ => 1,
+                };
+                Ok(Some((action, None, count.min(MAX_VALUE_STEPS))))
+            })?;
+            if let Some((action, data, count)) = request {
+                for _ in 0..count {
+                    self.do_action(|| ActionRequest {
+                        action,
+                        target: self.node_id,
+                        data: data.clone(),
+                    })?;
+                }
+            }
+            Ok(())
         }
     )),
     (SelectionItem, is_selection_item_pattern_supported, (
@@ -919,10 +1172,77 @@ patterns! {
         },
 
         fn SelectionContainer(&self) -> Result<IRawElementProviderSimple> {
-            // TODO: implement when we work on list boxes (#23)
-            // We return E_FAIL here because that's what Chromium does
-            // if it can't find a container.
-            Err(Error::new(E_FAIL, "".into()))
+            self.resolve(|node| match node.selection_container() {
+                Some(container) => Ok(self.relative(container.id()).into()),
+                // We return E_FAIL here because that's what Chromium does
+                // if it can't find a container.
+                None => Err(Error::new(E_FAIL, "".into())),
+            })
+        }
+    )),
+    (Selection, is_selection_pattern_supported, (
+        (CanSelectMultiple, can_select_multiple, BOOL),
+        (IsSelectionRequired, is_selection_required, BOOL)
+    ), (
+        fn GetSelection(&self) -> Result<*mut SAFEARRAY> {
+            self.resolve(|node| self.relative_array(&node.selected_items()))
+        }
+    )),
+    (Grid, is_grid_pattern_supported, (
+        (RowCount, row_count, i32),
+        (ColumnCount, column_count, i32)
+    ), (
+        fn GetItem(&self, row: i32, column: i32) -> Result<IRawElementProviderSimple> {
+            self.resolve(|node| {
+                let row = usize::try_from(row).map_err(|_| invalid_arg())?;
+                let column = usize::try_from(column).map_err(|_| invalid_arg())?;
+                match node.table_cell_at(row, column) {
+                    Some(cell) => Ok(self.relative(cell.id()).into()),
+                    None => Err(invalid_arg()),
+                }
+            })
+        }
+    )),
+    (Table, is_table_pattern_supported, (
+        (RowOrColumnMajor, row_or_column_major, RowOrColumnMajor)
+    ), (
+        fn GetRowHeaders(&self) -> Result<*mut SAFEARRAY> {
+            self.resolve(|node| self.relative_array(&node.table_row_headers()))
+        },
+
+        fn GetColumnHeaders(&self) -> Result<*mut SAFEARRAY> {
+            self.resolve(|node| self.relative_array(&node.table_column_headers()))
+        }
+    )),
+    (GridItem, is_grid_item_pattern_supported, (
+        (Row, cell_row, i32),
+        (Column, cell_column, i32),
+        (RowSpan, cell_row_span, i32),
+        (ColumnSpan, cell_column_span, i32)
+    ), (
+        fn ContainingGrid(&self) -> Result<IRawElementProviderSimple> {
+            self.resolve(|node| match node.containing_table() {
+                Some(table) => Ok(self.relative(table.id()).into()),
+                None => Err(element_not_available()),
+            })
+        }
+    )),
+    (TableItem, is_table_item_pattern_supported, (), (
+        fn GetRowHeaderItems(&self) -> Result<*mut SAFEARRAY> {
+            self.resolve(|node| self.relative_array(&node.cell_row_headers()))
+        },
+
+        fn GetColumnHeaderItems(&self) -> Result<*mut SAFEARRAY> {
+            self.resolve(|node| self.relative_array(&node.cell_column_headers()))
+        }
+    )),
+    (ScrollItem, is_scroll_item_pattern_supported, (), (
+        fn ScrollIntoView(&self) -> Result<()> {
+            self.do_action(|| ActionRequest {
+                action: Action::ScrollIntoView,
+                target: self.node_id,
+                data: None,
+            })
         }
     )),
     (Text, is_text_pattern_supported, (), (
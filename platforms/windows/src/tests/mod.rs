@@ -267,12 +267,12 @@ where
 struct SendableUiaElement(IUIAutomationElement);
 unsafe impl Send for SendableUiaElement {}
 
-pub(crate) struct ReceivedFocusEvent {
+pub(crate) struct ReceivedEvent {
     mutex: Mutex<Option<SendableUiaElement>>,
     cv: Condvar,
 }
 
-impl ReceivedFocusEvent {
+impl ReceivedEvent {
     fn new() -> Arc<Self> {
         Arc::new(Self {
             mutex: Mutex::new(None),
@@ -306,7 +306,7 @@ impl ReceivedFocusEvent {
 
 #[implement(Windows::Win32::UI::Accessibility::IUIAutomationFocusChangedEventHandler)]
 pub(crate) struct FocusEventHandler {
-    received: Arc<ReceivedFocusEvent>,
+    received: Arc<ReceivedEvent>,
 }
 // Because we create a UIA client in the COM MTA, this event handler
 // _will_ be called from a different thread, and possibly multiple threads
@@ -315,11 +315,8 @@ static_assertions::assert_impl_all!(FocusEventHandler: Send, Sync);
 
 impl FocusEventHandler {
     #[allow(clippy::new_ret_no_self)] // it does return self, but wrapped
-    pub(crate) fn new() -> (
-        IUIAutomationFocusChangedEventHandler,
-        Arc<ReceivedFocusEvent>,
-    ) {
-        let received = ReceivedFocusEvent::new();
+    pub(crate) fn new() -> (IUIAutomationFocusChangedEventHandler, Arc<ReceivedEvent>) {
+        let received = ReceivedEvent::new();
         (
             Self {
                 received: Arc::clone(&received),
@@ -338,5 +335,38 @@ impl IUIAutomationFocusChangedEventHandler_Impl for FocusEventHandler {
     }
 }
 
+#[implement(Windows::Win32::UI::Accessibility::IUIAutomationEventHandler)]
+pub(crate) struct AutomationEventHandler {
+    received: Arc<ReceivedEvent>,
+}
+// See the comment on `FocusEventHandler`.
+static_assertions::assert_impl_all!(AutomationEventHandler: Send, Sync);
+
+impl AutomationEventHandler {
+    #[allow(clippy::new_ret_no_self)] // it does return self, but wrapped
+    pub(crate) fn new() -> (IUIAutomationEventHandler, Arc<ReceivedEvent>) {
+        let received = ReceivedEvent::new();
+        (
+            Self {
+                received: Arc::clone(&received),
+            }
+            .into(),
+            received,
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+impl IUIAutomationEventHandler_Impl for AutomationEventHandler {
+    fn HandleAutomationEvent(
+        &self,
+        sender: Option<&IUIAutomationElement>,
+        _event_id: UIA_EVENT_ID,
+    ) -> Result<()> {
+        self.received.put(sender.unwrap().clone());
+        Ok(())
+    }
+}
+
 mod simple;
 mod subclassed;
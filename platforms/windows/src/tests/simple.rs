@@ -4,10 +4,16 @@
 // the LICENSE-MIT file), at your option.
 
 use accesskit::{
-    Action, ActionHandler, ActionRequest, Node, NodeBuilder, NodeClassSet, NodeId, Role, Tree,
-    TreeUpdate,
+    Action, ActionHandler, ActionRequest, DefaultActionVerb, Node, NodeBuilder, NodeClassSet,
+    NodeId, Role, TextPosition, TextSelection, Tree, TreeUpdate,
 };
-use windows::{core::*, Win32::UI::Accessibility::*};
+use std::sync::{Arc, Mutex};
+use windows::{
+    core::*,
+    Win32::{System::Com::VARIANT, UI::Accessibility::*},
+};
+
+use crate::util::{QueuedEvent, VariantFactory};
 
 use super::*;
 
@@ -21,6 +27,7 @@ fn make_button(name: &str, classes: &mut NodeClassSet) -> Node {
     let mut builder = NodeBuilder::new(Role::Button);
     builder.set_name(name);
     builder.add_action(Action::Focus);
+    builder.set_default_action_verb(DefaultActionVerb::Click);
     builder.build(classes)
 }
 
@@ -62,6 +69,19 @@ where
     )
 }
 
+fn count_events(events: &crate::QueuedEvents, f: impl Fn(&QueuedEvent) -> bool) -> usize {
+    events.iter().filter(|event| f(event)).count()
+}
+
+fn count_property_changes(events: &crate::QueuedEvents, id: UIA_PROPERTY_ID) -> usize {
+    count_events(events, |event| {
+        matches!(
+            event,
+            QueuedEvent::PropertyChanged { property_id, .. } if *property_id == id
+        )
+    })
+}
+
 #[test]
 fn has_native_uia() -> Result<()> {
     scope(|s| {
@@ -191,3 +211,959 @@ fn focus() -> Result<()> {
         Ok(())
     })
 }
+
+#[test]
+fn msaa_bridge_disabled() -> Result<()> {
+    scope(|s| {
+        let adapter = Adapter::new(
+            s.window,
+            get_initial_state(),
+            false,
+            Box::new(NullActionHandler {}),
+            UiaInitMarker::new(),
+        )
+        .with_msaa_bridge(false);
+        let client = adapter.handle_wm_getobject(WPARAM(0), LPARAM(OBJID_CLIENT.0 as _));
+        assert!(client.is_none());
+        let uia = adapter.handle_wm_getobject(WPARAM(0), LPARAM(UiaRootObjectId as _));
+        assert!(uia.is_some());
+        Ok(())
+    })
+}
+
+struct RecordingActionHandler(Arc<Mutex<Vec<ActionRequest>>>);
+
+impl ActionHandler for RecordingActionHandler {
+    fn do_action(&mut self, request: ActionRequest) {
+        self.0.lock().unwrap().push(request);
+    }
+}
+
+#[test]
+fn invoke() -> Result<()> {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    super::scope(
+        WINDOW_TITLE,
+        get_initial_state(),
+        Box::new(RecordingActionHandler(Arc::clone(&requests))),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let name: VARIANT = VariantFactory::from("Button 1").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let button = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            assert!(is_button_1(&button));
+            let (invoked_event_handler, received_invoked_event) = AutomationEventHandler::new();
+            unsafe {
+                s.uia.AddAutomationEventHandler(
+                    UIA_Invoke_InvokedEventId,
+                    &button,
+                    TreeScope_Element,
+                    None,
+                    &invoked_event_handler,
+                )
+            }?;
+            let pattern: IUIAutomationInvokePattern =
+                unsafe { button.GetCurrentPatternAs(UIA_InvokePatternId) }?;
+            unsafe { pattern.Invoke() }?;
+            received_invoked_event.wait(is_button_1);
+            assert_eq!(
+                *requests.lock().unwrap(),
+                vec![ActionRequest {
+                    action: Action::Default,
+                    target: BUTTON_1_ID,
+                    data: None,
+                }]
+            );
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn range_value_maps_to_increment_and_decrement() -> Result<()> {
+    const STEPPER_ID: NodeId = NodeId(1);
+
+    let mut classes = NodeClassSet::new();
+    let initial_state = TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![STEPPER_ID]);
+                builder.build(&mut classes)
+            }),
+            (STEPPER_ID, {
+                let mut builder = NodeBuilder::new(Role::SpinButton);
+                builder.set_name("Quantity");
+                builder.set_numeric_value(3.0);
+                builder.set_min_numeric_value(0.0);
+                builder.set_max_numeric_value(1000.0);
+                builder.set_numeric_value_step(1.0);
+                builder.add_action(Action::Increment);
+                builder.add_action(Action::Decrement);
+                builder.build(&mut classes)
+            }),
+        ],
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: WINDOW_ID,
+    };
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    super::scope(
+        WINDOW_TITLE,
+        initial_state,
+        Box::new(RecordingActionHandler(Arc::clone(&requests))),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let name: VARIANT = VariantFactory::from("Quantity").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let stepper = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let pattern: IUIAutomationRangeValuePattern =
+                unsafe { stepper.GetCurrentPatternAs(UIA_RangeValuePatternId) }?;
+            unsafe { pattern.SetValue(4.0) }?;
+            unsafe { pattern.SetValue(2.0) }?;
+            let request = |action| ActionRequest {
+                action,
+                target: STEPPER_ID,
+                data: None,
+            };
+            assert_eq!(
+                *requests.lock().unwrap(),
+                vec![request(Action::Increment), request(Action::Decrement)]
+            );
+            requests.lock().unwrap().clear();
+
+            // Larger moves take as many steps as needed, rounding up.
+            unsafe { pattern.SetValue(6.0) }?;
+            assert_eq!(
+                *requests.lock().unwrap(),
+                vec![request(Action::Increment); 3]
+            );
+            requests.lock().unwrap().clear();
+            unsafe { pattern.SetValue(1.5) }?;
+            assert_eq!(
+                *requests.lock().unwrap(),
+                vec![request(Action::Decrement); 2]
+            );
+            requests.lock().unwrap().clear();
+
+            // The target is clamped to the range, and the number of steps
+            // is bounded.
+            unsafe { pattern.SetValue(-50.0) }?;
+            assert_eq!(
+                *requests.lock().unwrap(),
+                vec![request(Action::Decrement); 3]
+            );
+            requests.lock().unwrap().clear();
+            unsafe { pattern.SetValue(1e300) }?;
+            assert_eq!(
+                *requests.lock().unwrap(),
+                vec![request(Action::Increment); 100]
+            );
+            requests.lock().unwrap().clear();
+
+            // Values that aren't finite are rejected.
+            assert!(unsafe { pattern.SetValue(f64::NAN) }.is_err());
+            assert!(unsafe { pattern.SetValue(f64::INFINITY) }.is_err());
+            assert!(requests.lock().unwrap().is_empty());
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn show_context_menu() -> Result<()> {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    super::scope(
+        WINDOW_TITLE,
+        get_initial_state(),
+        Box::new(RecordingActionHandler(Arc::clone(&requests))),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let name: VARIANT = VariantFactory::from("Button 2").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let button: IUIAutomationElement3 =
+                unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?.cast()?;
+            unsafe { button.ShowContextMenu() }?;
+            assert_eq!(
+                *requests.lock().unwrap(),
+                vec![ActionRequest {
+                    action: Action::ShowContextMenu,
+                    target: BUTTON_2_ID,
+                    data: None,
+                }]
+            );
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn scroll_into_view() -> Result<()> {
+    const ITEM_ID: NodeId = NodeId(1);
+
+    let mut classes = NodeClassSet::new();
+    let initial_state = TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![ITEM_ID]);
+                builder.build(&mut classes)
+            }),
+            (ITEM_ID, {
+                let mut builder = NodeBuilder::new(Role::ListItem);
+                builder.set_name("Item");
+                builder.add_action(Action::ScrollIntoView);
+                builder.build(&mut classes)
+            }),
+        ],
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: WINDOW_ID,
+    };
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    super::scope(
+        WINDOW_TITLE,
+        initial_state,
+        Box::new(RecordingActionHandler(Arc::clone(&requests))),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let name: VARIANT = VariantFactory::from("Item").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let item = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let pattern: IUIAutomationScrollItemPattern =
+                unsafe { item.GetCurrentPatternAs(UIA_ScrollItemPatternId) }?;
+            unsafe { pattern.ScrollIntoView() }?;
+            assert_eq!(
+                *requests.lock().unwrap(),
+                vec![ActionRequest {
+                    action: Action::ScrollIntoView,
+                    target: ITEM_ID,
+                    data: None,
+                }]
+            );
+            Ok(())
+        },
+    )
+}
+
+fn get_list_box_state(multiselectable: bool) -> TreeUpdate {
+    const LIST_BOX_ID: NodeId = NodeId(1);
+
+    let mut classes = NodeClassSet::new();
+    let option = |classes: &mut NodeClassSet, name: &str, selected: bool| {
+        let mut builder = NodeBuilder::new(Role::ListBoxOption);
+        builder.set_name(name);
+        builder.set_selected(selected);
+        builder.build(classes)
+    };
+    TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![LIST_BOX_ID]);
+                builder.build(&mut classes)
+            }),
+            (LIST_BOX_ID, {
+                let mut builder = NodeBuilder::new(Role::ListBox);
+                builder.set_name("Fruits");
+                if multiselectable {
+                    builder.set_multiselectable();
+                }
+                builder.set_children(vec![NodeId(2), NodeId(3), NodeId(4)]);
+                builder.build(&mut classes)
+            }),
+            (NodeId(2), option(&mut classes, "Apple", true)),
+            (NodeId(3), option(&mut classes, "Banana", false)),
+            (NodeId(4), option(&mut classes, "Cherry", multiselectable)),
+        ],
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: LIST_BOX_ID,
+    }
+}
+
+#[test]
+fn selection() -> Result<()> {
+    for multiselectable in [false, true] {
+        super::scope(
+            WINDOW_TITLE,
+            get_list_box_state(multiselectable),
+            Box::new(NullActionHandler {}),
+            |s| {
+                let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+                let name: VARIANT = VariantFactory::from("Fruits").into();
+                let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+                let list_box = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+                let pattern: IUIAutomationSelectionPattern =
+                    unsafe { list_box.GetCurrentPatternAs(UIA_SelectionPatternId) }?;
+                assert_eq!(
+                    unsafe { pattern.CurrentCanSelectMultiple() }?.as_bool(),
+                    multiselectable
+                );
+                assert!(!unsafe { pattern.CurrentIsSelectionRequired() }?.as_bool());
+                let selection = unsafe { pattern.GetCurrentSelection() }?;
+                let mut names = Vec::new();
+                for i in 0..unsafe { selection.Length() }? {
+                    let item = unsafe { selection.GetElement(i) }?;
+                    let name: String = unsafe { item.CurrentName() }?.try_into().unwrap();
+                    names.push(name);
+                }
+                let expected: &[&str] = if multiselectable {
+                    &["Apple", "Cherry"]
+                } else {
+                    &["Apple"]
+                };
+                assert_eq!(names, expected);
+
+                let item = unsafe { selection.GetElement(0) }?;
+                let item_pattern: IUIAutomationSelectionItemPattern =
+                    unsafe { item.GetCurrentPatternAs(UIA_SelectionItemPatternId) }?;
+                let container = unsafe { item_pattern.CurrentSelectionContainer() }?;
+                let container_name: String =
+                    unsafe { container.CurrentName() }?.try_into().unwrap();
+                assert_eq!(container_name, "Fruits");
+                Ok(())
+            },
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn labelled_by() -> Result<()> {
+    const LABEL_ID: NodeId = NodeId(1);
+    const INPUT_ID: NodeId = NodeId(2);
+
+    let mut classes = NodeClassSet::new();
+    let initial_state = TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![LABEL_ID, INPUT_ID]);
+                builder.build(&mut classes)
+            }),
+            (LABEL_ID, {
+                let mut builder = NodeBuilder::new(Role::StaticText);
+                builder.set_name("Email");
+                builder.build(&mut classes)
+            }),
+            (INPUT_ID, {
+                let mut builder = NodeBuilder::new(Role::TextInput);
+                builder.push_labelled_by(LABEL_ID);
+                builder.add_action(Action::Focus);
+                builder.build(&mut classes)
+            }),
+        ],
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: INPUT_ID,
+    };
+    super::scope(
+        WINDOW_TITLE,
+        initial_state,
+        Box::new(NullActionHandler {}),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let control_type: VARIANT = VariantFactory::from(UIA_EditControlTypeId).into();
+            let condition = unsafe {
+                s.uia
+                    .CreatePropertyCondition(UIA_ControlTypePropertyId, control_type)
+            }?;
+            let input = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let name: String = unsafe { input.CurrentName() }?.try_into().unwrap();
+            assert_eq!(name, "Email");
+            let label = unsafe { input.CurrentLabeledBy() }?;
+            let label_name: String = unsafe { label.CurrentName() }?.try_into().unwrap();
+            assert_eq!(label_name, "Email");
+            let control_type = unsafe { label.CurrentControlType() }?;
+            assert_eq!(control_type, UIA_TextControlTypeId);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn controller_for() -> Result<()> {
+    const BUTTON_ID: NodeId = NodeId(1);
+    const RESULTS_ID: NodeId = NodeId(2);
+
+    let mut classes = NodeClassSet::new();
+    let initial_state = TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![BUTTON_ID, RESULTS_ID]);
+                builder.build(&mut classes)
+            }),
+            (BUTTON_ID, {
+                let mut builder = NodeBuilder::new(Role::Button);
+                builder.set_name("Apply filter");
+                builder.push_controlled(RESULTS_ID);
+                builder.add_action(Action::Focus);
+                builder.build(&mut classes)
+            }),
+            (RESULTS_ID, {
+                let mut builder = NodeBuilder::new(Role::List);
+                builder.set_name("Results");
+                builder.build(&mut classes)
+            }),
+        ],
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: BUTTON_ID,
+    };
+    super::scope(
+        WINDOW_TITLE,
+        initial_state,
+        Box::new(NullActionHandler {}),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let name: VARIANT = VariantFactory::from("Apply filter").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let button = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let controlled = unsafe { button.CurrentControllerFor() }?;
+            assert_eq!(element_names(&controlled)?, vec!["Results"]);
+            let name: VARIANT = VariantFactory::from("Results").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let results = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let controlled = unsafe { results.CurrentControllerFor() }?;
+            assert_eq!(unsafe { controlled.Length() }?, 0);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn help_text_and_keys() -> Result<()> {
+    let mut classes = NodeClassSet::new();
+    let initial_state = TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![BUTTON_1_ID]);
+                builder.build(&mut classes)
+            }),
+            (BUTTON_1_ID, {
+                let mut builder = NodeBuilder::new(Role::Button);
+                builder.set_name("Save");
+                builder.set_tooltip("Save the document");
+                builder.set_access_key("S");
+                builder.set_keyboard_shortcut("Ctrl+S");
+                builder.add_action(Action::Focus);
+                builder.build(&mut classes)
+            }),
+        ],
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: BUTTON_1_ID,
+    };
+    super::scope(
+        WINDOW_TITLE,
+        initial_state,
+        Box::new(NullActionHandler {}),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let control_type: VARIANT = VariantFactory::from(UIA_ButtonControlTypeId).into();
+            let condition = unsafe {
+                s.uia
+                    .CreatePropertyCondition(UIA_ControlTypePropertyId, control_type)
+            }?;
+            let button = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let help_text: String = unsafe { button.CurrentHelpText() }?.try_into().unwrap();
+            assert_eq!(help_text, "Save the document");
+            let access_key: String = unsafe { button.CurrentAccessKey() }?.try_into().unwrap();
+            assert_eq!(access_key, "S");
+            let accelerator_key: String = unsafe { button.CurrentAcceleratorKey() }?
+                .try_into()
+                .unwrap();
+            assert_eq!(accelerator_key, "Ctrl+S");
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn position_size_and_level() -> Result<()> {
+    const TREE_ID: NodeId = NodeId(1);
+    const ITEM_ID: NodeId = NodeId(2);
+
+    let mut classes = NodeClassSet::new();
+    let initial_state = TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![TREE_ID]);
+                builder.build(&mut classes)
+            }),
+            (TREE_ID, {
+                let mut builder = NodeBuilder::new(Role::Tree);
+                builder.set_children(vec![ITEM_ID]);
+                builder.build(&mut classes)
+            }),
+            (ITEM_ID, {
+                let mut builder = NodeBuilder::new(Role::TreeItem);
+                builder.set_name("Item");
+                builder.set_position_in_set(3);
+                builder.set_size_of_set(10);
+                builder.set_hierarchical_level(2);
+                builder.add_action(Action::Focus);
+                builder.build(&mut classes)
+            }),
+        ],
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: ITEM_ID,
+    };
+    super::scope(
+        WINDOW_TITLE,
+        initial_state,
+        Box::new(NullActionHandler {}),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let name: VARIANT = VariantFactory::from("Item").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let item = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let int_property = |id| -> Result<i32> {
+                let value = unsafe { item.GetCurrentPropertyValue(id) }?;
+                Ok(unsafe { value.Anonymous.Anonymous.Anonymous.lVal })
+            };
+            assert_eq!(int_property(UIA_PositionInSetPropertyId)?, 3);
+            assert_eq!(int_property(UIA_SizeOfSetPropertyId)?, 10);
+            assert_eq!(int_property(UIA_LevelPropertyId)?, 2);
+            Ok(())
+        },
+    )
+}
+
+fn get_automation_id_state(button_name: &str, automation_id: &str) -> TreeUpdate {
+    let mut classes = NodeClassSet::new();
+    let mut state = get_initial_state();
+    state.nodes[1].1 = {
+        let mut builder = NodeBuilder::new(Role::Button);
+        builder.set_name(button_name);
+        builder.add_action(Action::Focus);
+        builder.set_automation_id(automation_id);
+        builder.build(&mut classes)
+    };
+    state
+}
+
+#[test]
+fn automation_id() -> Result<()> {
+    super::scope(
+        WINDOW_TITLE,
+        get_automation_id_state("Button 1", "button-1"),
+        Box::new(NullActionHandler {}),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let id: VARIANT = VariantFactory::from("button-1").into();
+            let condition = unsafe {
+                s.uia
+                    .CreatePropertyCondition(UIA_AutomationIdPropertyId, id)
+            }?;
+            let button = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            assert!(is_button_1(&button));
+            let automation_id = unsafe { button.CurrentAutomationId() }?;
+            assert_eq!(automation_id, "button-1");
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn modal_is_dialog() -> Result<()> {
+    const DIALOG_ID: NodeId = NodeId(1);
+    const MENU_ID: NodeId = NodeId(2);
+
+    let mut classes = NodeClassSet::new();
+    let initial_state = TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![DIALOG_ID, MENU_ID]);
+                builder.build(&mut classes)
+            }),
+            (DIALOG_ID, {
+                let mut builder = NodeBuilder::new(Role::Group);
+                builder.set_name("Confirm");
+                builder.set_modal();
+                builder.build(&mut classes)
+            }),
+            (MENU_ID, {
+                let mut builder = NodeBuilder::new(Role::Menu);
+                builder.set_name("Options");
+                builder.set_modal();
+                builder.build(&mut classes)
+            }),
+        ],
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: WINDOW_ID,
+    };
+    super::scope(
+        WINDOW_TITLE,
+        initial_state,
+        Box::new(NullActionHandler {}),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let name: VARIANT = VariantFactory::from("Confirm").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let dialog = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let is_dialog = unsafe { dialog.GetCurrentPropertyValue(UIA_IsDialogPropertyId) }?;
+            assert!(unsafe { is_dialog.Anonymous.Anonymous.Anonymous.boolVal }.as_bool());
+            let aria_role: String = unsafe { dialog.CurrentAriaRole() }?.try_into().unwrap();
+            assert_eq!(aria_role, "dialog");
+
+            // A modal node with a more specific role keeps it.
+            let name: VARIANT = VariantFactory::from("Options").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let menu = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let is_dialog = unsafe { menu.GetCurrentPropertyValue(UIA_IsDialogPropertyId) }?;
+            assert!(!unsafe { is_dialog.Anonymous.Anonymous.Anonymous.boolVal }.as_bool());
+            Ok(())
+        },
+    )
+}
+
+const GROUP_ID: NodeId = NodeId(3);
+
+fn get_group_state(is_group_disabled: bool, is_button_focusable: bool) -> TreeUpdate {
+    let mut classes = NodeClassSet::new();
+    TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![GROUP_ID, BUTTON_2_ID]);
+                builder.build(&mut classes)
+            }),
+            (GROUP_ID, {
+                let mut builder = NodeBuilder::new(Role::Group);
+                builder.set_children(vec![BUTTON_1_ID]);
+                if is_group_disabled {
+                    builder.set_disabled();
+                }
+                builder.build(&mut classes)
+            }),
+            (BUTTON_1_ID, make_button("Button 1", &mut classes)),
+            (BUTTON_2_ID, {
+                let mut builder = NodeBuilder::new(Role::Button);
+                builder.set_name("Button 2");
+                if is_button_focusable {
+                    builder.add_action(Action::Focus);
+                }
+                builder.build(&mut classes)
+            }),
+        ],
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: WINDOW_ID,
+    }
+}
+
+#[test]
+fn enabled_and_focusable() -> Result<()> {
+    super::scope(
+        WINDOW_TITLE,
+        get_group_state(true, false),
+        Box::new(NullActionHandler {}),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let find = |name: &str| {
+                let name: VARIANT = VariantFactory::from(name).into();
+                let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+                unsafe { root.FindFirst(TreeScope_Descendants, &condition) }
+            };
+            let button_1 = find("Button 1")?;
+            let is_enabled: bool = unsafe { button_1.CurrentIsEnabled() }?.into();
+            assert!(!is_enabled);
+            let is_focusable: bool = unsafe { button_1.CurrentIsKeyboardFocusable() }?.into();
+            assert!(is_focusable);
+            let button_2 = find("Button 2")?;
+            let is_enabled: bool = unsafe { button_2.CurrentIsEnabled() }?.into();
+            assert!(is_enabled);
+            let is_focusable: bool = unsafe { button_2.CurrentIsKeyboardFocusable() }?.into();
+            assert!(!is_focusable);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn enabled_and_focusable_change_events() -> Result<()> {
+    scope(|s| {
+        let adapter = Adapter::new(
+            s.window,
+            get_group_state(false, false),
+            false,
+            Box::new(NullActionHandler {}),
+            UiaInitMarker::new(),
+        );
+        let events = adapter.update(get_group_state(true, true));
+        // The group and the button that inherits its disabled state.
+        assert_eq!(count_property_changes(&events, UIA_IsEnabledPropertyId), 2);
+        assert_eq!(
+            count_property_changes(&events, UIA_IsKeyboardFocusablePropertyId),
+            1
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn inherited_enabled_change_is_queued_once() -> Result<()> {
+    scope(|s| {
+        let adapter = Adapter::new(
+            s.window,
+            get_group_state(false, false),
+            false,
+            Box::new(NullActionHandler {}),
+            UiaInitMarker::new(),
+        );
+        // The button inside the group is also in the update, so it's
+        // handled both as an updated node and as a descendant of the group.
+        let mut update = get_group_state(true, false);
+        let mut classes = NodeClassSet::new();
+        update.nodes[2].1 = make_button("Renamed", &mut classes);
+        let events = adapter.update(update);
+        assert_eq!(count_property_changes(&events, UIA_IsEnabledPropertyId), 2);
+        assert_eq!(count_property_changes(&events, UIA_NamePropertyId), 1);
+        Ok(())
+    })
+}
+
+#[test]
+fn automation_id_is_stable_across_updates() -> Result<()> {
+    scope(|s| {
+        let adapter = Adapter::new(
+            s.window,
+            get_automation_id_state("Button 1", "button-1"),
+            false,
+            Box::new(NullActionHandler {}),
+            UiaInitMarker::new(),
+        );
+        let events = adapter.update(get_automation_id_state("Renamed", "button-1"));
+        assert_eq!(count_property_changes(&events, UIA_NamePropertyId), 1);
+        assert_eq!(
+            count_property_changes(&events, UIA_AutomationIdPropertyId),
+            0
+        );
+        let events = adapter.update(get_automation_id_state("Renamed", "button-2"));
+        assert_eq!(
+            count_property_changes(&events, UIA_AutomationIdPropertyId),
+            1
+        );
+        Ok(())
+    })
+}
+
+fn get_item_status_state(status: &str) -> TreeUpdate {
+    let mut classes = NodeClassSet::new();
+    let mut state = get_initial_state();
+    state.nodes[1].1 = {
+        let mut builder = NodeBuilder::new(Role::Button);
+        builder.set_name("Button 1");
+        builder.add_action(Action::Focus);
+        builder.set_item_status(status);
+        builder.build(&mut classes)
+    };
+    state
+}
+
+#[test]
+fn item_status() -> Result<()> {
+    super::scope(
+        WINDOW_TITLE,
+        get_item_status_state("Loading"),
+        Box::new(NullActionHandler {}),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let name: VARIANT = VariantFactory::from("Button 1").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let button = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let status = unsafe { button.CurrentItemStatus() }?;
+            assert_eq!(status, "Loading");
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn item_status_change_event() -> Result<()> {
+    scope(|s| {
+        let adapter = Adapter::new(
+            s.window,
+            get_item_status_state("Loading"),
+            false,
+            Box::new(NullActionHandler {}),
+            UiaInitMarker::new(),
+        );
+        let events = adapter.update(get_item_status_state("Error"));
+        assert_eq!(count_property_changes(&events, UIA_ItemStatusPropertyId), 1);
+        Ok(())
+    })
+}
+
+fn get_text_input_state(caret: usize) -> TreeUpdate {
+    const INPUT_ID: NodeId = NodeId(1);
+    const TEXT_ID: NodeId = NodeId(2);
+
+    let mut classes = NodeClassSet::new();
+    let caret = TextPosition {
+        node: TEXT_ID,
+        character_index: caret,
+    };
+    TreeUpdate {
+        nodes: vec![
+            (WINDOW_ID, {
+                let mut builder = NodeBuilder::new(Role::Window);
+                builder.set_children(vec![INPUT_ID]);
+                builder.build(&mut classes)
+            }),
+            (INPUT_ID, {
+                let mut builder = NodeBuilder::new(Role::TextInput);
+                builder.set_name("Message");
+                builder.set_children(vec![TEXT_ID]);
+                builder.add_action(Action::Focus);
+                builder.set_text_selection(TextSelection {
+                    anchor: caret,
+                    focus: caret,
+                });
+                builder.build(&mut classes)
+            }),
+            (TEXT_ID, {
+                let mut builder = NodeBuilder::new(Role::InlineTextBox);
+                builder.set_value("hello");
+                builder.set_character_lengths([1, 1, 1, 1, 1]);
+                builder.build(&mut classes)
+            }),
+        ],
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: INPUT_ID,
+    }
+}
+
+#[test]
+fn text_selection_change_event() -> Result<()> {
+    scope(|s| {
+        let adapter = Adapter::new(
+            s.window,
+            get_text_input_state(0),
+            false,
+            Box::new(NullActionHandler {}),
+            UiaInitMarker::new(),
+        );
+        let count = |events: &crate::QueuedEvents| {
+            count_events(events, |event| {
+                matches!(
+                    event,
+                    QueuedEvent::Simple { event_id, .. }
+                        if *event_id == UIA_Text_TextSelectionChangedEventId
+                )
+            })
+        };
+        let events = adapter.update(get_text_input_state(3));
+        assert_eq!(count(&events), 1);
+        let events = adapter.update(get_text_input_state(3));
+        assert_eq!(count(&events), 0);
+        Ok(())
+    })
+}
+
+fn get_grid_state() -> TreeUpdate {
+    const GRID_ID: NodeId = NodeId(1);
+    const HEADER_ROW_ID: NodeId = NodeId(2);
+    const BODY_ROW_ID: NodeId = NodeId(3);
+
+    let mut classes = NodeClassSet::new();
+    let parent = |role, children: Vec<NodeId>| {
+        let mut builder = NodeBuilder::new(role);
+        builder.set_children(children);
+        builder
+    };
+    let cell = |role, name: &str, row, column| {
+        let mut builder = NodeBuilder::new(role);
+        builder.set_name(name);
+        builder.set_table_cell_row_index(row);
+        builder.set_table_cell_column_index(column);
+        builder
+    };
+    let mut grid = parent(Role::Grid, vec![HEADER_ROW_ID, BODY_ROW_ID]);
+    grid.set_name("Inventory");
+    grid.set_table_row_count(2);
+    grid.set_table_column_count(2);
+    let nodes = vec![
+        (WINDOW_ID, parent(Role::Window, vec![GRID_ID])),
+        (GRID_ID, grid),
+        (
+            HEADER_ROW_ID,
+            parent(Role::Row, vec![NodeId(10), NodeId(11)]),
+        ),
+        (BODY_ROW_ID, parent(Role::Row, vec![NodeId(12), NodeId(13)])),
+        (NodeId(10), cell(Role::ColumnHeader, "Item", 0, 0)),
+        (NodeId(11), cell(Role::ColumnHeader, "Quantity", 0, 1)),
+        (NodeId(12), cell(Role::Cell, "Apples", 1, 0)),
+        (NodeId(13), cell(Role::Cell, "3", 1, 1)),
+    ];
+    TreeUpdate {
+        nodes: nodes
+            .into_iter()
+            .map(|(id, builder)| (id, builder.build(&mut classes)))
+            .collect(),
+        tree: Some(Tree::new(WINDOW_ID)),
+        focus: WINDOW_ID,
+    }
+}
+
+fn element_names(array: &IUIAutomationElementArray) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for i in 0..unsafe { array.Length() }? {
+        let name = unsafe { array.GetElement(i)?.CurrentName() }?;
+        names.push(name.try_into().unwrap());
+    }
+    Ok(names)
+}
+
+#[test]
+fn grid_cell_lookup() -> Result<()> {
+    super::scope(
+        WINDOW_TITLE,
+        get_grid_state(),
+        Box::new(NullActionHandler {}),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let name: VARIANT = VariantFactory::from("Inventory").into();
+            let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+            let grid = unsafe { root.FindFirst(TreeScope_Descendants, &condition) }?;
+            let grid: IUIAutomationGridPattern =
+                unsafe { grid.GetCurrentPatternAs(UIA_GridPatternId) }?;
+            assert_eq!(unsafe { grid.CurrentRowCount() }?, 2);
+            assert_eq!(unsafe { grid.CurrentColumnCount() }?, 2);
+            let cell = unsafe { grid.GetItem(1, 0) }?;
+            let name: String = unsafe { cell.CurrentName() }?.try_into().unwrap();
+            assert_eq!(name, "Apples");
+            let cell: IUIAutomationGridItemPattern =
+                unsafe { cell.GetCurrentPatternAs(UIA_GridItemPatternId) }?;
+            assert_eq!(unsafe { cell.CurrentRow() }?, 1);
+            assert_eq!(unsafe { cell.CurrentColumn() }?, 0);
+            assert!(unsafe { grid.GetItem(2, 0) }.is_err());
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn table_headers() -> Result<()> {
+    super::scope(
+        WINDOW_TITLE,
+        get_grid_state(),
+        Box::new(NullActionHandler {}),
+        |s| {
+            let root = unsafe { s.uia.ElementFromHandle(s.window) }?;
+            let find = |name: &str| {
+                let name: VARIANT = VariantFactory::from(name).into();
+                let condition = unsafe { s.uia.CreatePropertyCondition(UIA_NamePropertyId, name) }?;
+                unsafe { root.FindFirst(TreeScope_Descendants, &condition) }
+            };
+            let table: IUIAutomationTablePattern =
+                unsafe { find("Inventory")?.GetCurrentPatternAs(UIA_TablePatternId) }?;
+            let headers = unsafe { table.GetCurrentColumnHeaders() }?;
+            assert_eq!(element_names(&headers)?, vec!["Item", "Quantity"]);
+            let cell: IUIAutomationTableItemPattern =
+                unsafe { find("3")?.GetCurrentPatternAs(UIA_TableItemPatternId) }?;
+            let headers = unsafe { cell.GetCurrentColumnHeaderItems() }?;
+            assert_eq!(element_names(&headers)?, vec!["Quantity"]);
+            Ok(())
+        },
+    )
+}
@@ -48,6 +48,16 @@ impl VariantFactory {
     pub(crate) fn is_empty(&self) -> bool {
         self.0 == VT_EMPTY
     }
+
+    /// Wraps a SAFEARRAY of `IUnknown` pointers, such as one created by
+    /// [`safe_array_from_com_slice`]. The resulting variant takes ownership
+    /// of the array.
+    pub(crate) fn from_com_array(value: *mut SAFEARRAY) -> Self {
+        Self(
+            VARENUM(VT_ARRAY.0 | VT_UNKNOWN.0),
+            VARIANT_0_0_0 { parray: value },
+        )
+    }
 }
 
 impl From<&str> for VariantFactory {
@@ -122,6 +132,24 @@ impl From<UIA_CONTROLTYPE_ID> for VariantFactory {
     }
 }
 
+impl From<RowOrColumnMajor> for VariantFactory {
+    fn from(value: RowOrColumnMajor) -> Self {
+        value.0.into()
+    }
+}
+
+impl From<OrientationType> for VariantFactory {
+    fn from(value: OrientationType) -> Self {
+        value.0.into()
+    }
+}
+
+impl From<UIA_LANDMARKTYPE_ID> for VariantFactory {
+    fn from(value: UIA_LANDMARKTYPE_ID) -> Self {
+        (value.0 as i32).into()
+    }
+}
+
 const VARIANT_FALSE: i16 = 0i16;
 const VARIANT_TRUE: i16 = -1i16;
 